@@ -0,0 +1,50 @@
+use crate::{EcallAbi, EcallRegisters, Register, RegisterLayout};
+use fuste_ecall::Ecall;
+
+/// The abort ECALL, which ends the program with a message for the host, e.g. where it panicked.
+///
+/// It returns only when no system serves it.
+pub struct AbortAbi;
+
+impl EcallAbi for AbortAbi {
+	const ECALL: Ecall = Ecall::Abort;
+
+	type Args = AbortArgs;
+
+	type Returns = ();
+}
+
+/// The message is passed as a buffer in a0 and a1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AbortArgs {
+	pub message_address: u32,
+	pub message_length: u32,
+}
+
+impl RegisterLayout for AbortArgs {
+	fn write_registers(&self, registers: &mut EcallRegisters) {
+		registers.set(Register::A0, self.message_address);
+		registers.set(Register::A1, self.message_length);
+	}
+
+	fn read_registers(registers: &EcallRegisters) -> Self {
+		Self {
+			message_address: registers.get(Register::A0),
+			message_length: registers.get(Register::A1),
+		}
+	}
+}
+
+#[cfg(test)]
+pub mod tests {
+	use super::*;
+
+	#[test]
+	fn test_abort_args_layout() {
+		let args = AbortArgs { message_address: 0x1000, message_length: 12 };
+		let mut registers = EcallRegisters::new();
+		args.write_registers(&mut registers);
+		assert_eq!(registers.values(), [0x1000, 12, 0, 0, 0, 0, 0]);
+		assert_eq!(AbortArgs::read_registers(&registers), args);
+	}
+}
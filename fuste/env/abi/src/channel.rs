@@ -0,0 +1,194 @@
+use crate::{EcallAbi, EcallRegisters, Register, RegisterLayout};
+use fuste_ecall::Ecall;
+
+/// The open channel ECALL.
+pub struct OpenChannelAbi;
+
+impl EcallAbi for OpenChannelAbi {
+	const ECALL: Ecall = Ecall::OpenChannel;
+
+	type Args = ChannelArgs;
+
+	type Returns = ChannelReturns;
+}
+
+/// The check channel ECALL, which takes and returns the same as [OpenChannelAbi].
+pub struct CheckChannelAbi;
+
+impl EcallAbi for CheckChannelAbi {
+	const ECALL: Ecall = Ecall::CheckChannel;
+
+	type Args = ChannelArgs;
+
+	type Returns = ChannelReturns;
+}
+
+/// The wait channel ECALL, which parks the program until the channel progresses or the timeout
+/// passes.
+pub struct WaitChannelAbi;
+
+impl EcallAbi for WaitChannelAbi {
+	const ECALL: Ecall = Ecall::WaitChannel;
+
+	type Args = WaitChannelArgs;
+
+	type Returns = WaitChannelReturns;
+}
+
+/// The system ID is passed in a0, the buffer the system reads in a1 and a2, and the buffer it
+/// writes in a3 and a4.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChannelArgs {
+	pub system_id: u32,
+	pub read_buffer_address: u32,
+	pub read_buffer_length: u32,
+	pub write_buffer_address: u32,
+	pub write_buffer_length: u32,
+}
+
+impl RegisterLayout for ChannelArgs {
+	fn write_registers(&self, registers: &mut EcallRegisters) {
+		registers.set(Register::A0, self.system_id);
+		registers.set(Register::A1, self.read_buffer_address);
+		registers.set(Register::A2, self.read_buffer_length);
+		registers.set(Register::A3, self.write_buffer_address);
+		registers.set(Register::A4, self.write_buffer_length);
+	}
+
+	fn read_registers(registers: &EcallRegisters) -> Self {
+		Self {
+			system_id: registers.get(Register::A0),
+			read_buffer_address: registers.get(Register::A1),
+			read_buffer_length: registers.get(Register::A2),
+			write_buffer_address: registers.get(Register::A3),
+			write_buffer_length: registers.get(Register::A4),
+		}
+	}
+}
+
+/// The status code is returned in a5, the number of bytes written in a4, replacing the write
+/// buffer's length, and the system's own status in a6.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChannelReturns {
+	pub status: i32,
+	pub size: u32,
+	pub system_status: i32,
+}
+
+impl RegisterLayout for ChannelReturns {
+	fn write_registers(&self, registers: &mut EcallRegisters) {
+		registers.set(Register::A5, self.status as u32);
+		registers.set(Register::A4, self.size);
+		registers.set(Register::A6, self.system_status as u32);
+	}
+
+	fn read_registers(registers: &EcallRegisters) -> Self {
+		Self {
+			status: registers.get(Register::A5) as i32,
+			size: registers.get(Register::A4),
+			system_status: registers.get(Register::A6) as i32,
+		}
+	}
+}
+
+/// The timeout of a wait which only ends once the channel progresses.
+pub const NO_TIMEOUT: u64 = u64::MAX;
+
+/// The system ID is passed in a0 and the timeout in nanoseconds in a1 (low word) and a2 (high
+/// word), [NO_TIMEOUT] waiting for as long as it takes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WaitChannelArgs {
+	pub system_id: u32,
+	pub timeout_nanos: u64,
+}
+
+impl RegisterLayout for WaitChannelArgs {
+	fn write_registers(&self, registers: &mut EcallRegisters) {
+		registers.set(Register::A0, self.system_id);
+		registers.set(Register::A1, self.timeout_nanos as u32);
+		registers.set(Register::A2, (self.timeout_nanos >> 32) as u32);
+	}
+
+	fn read_registers(registers: &EcallRegisters) -> Self {
+		Self {
+			system_id: registers.get(Register::A0),
+			timeout_nanos: u64::from(registers.get(Register::A1))
+				| (u64::from(registers.get(Register::A2)) << 32),
+		}
+	}
+}
+
+/// The status code is returned in a3 and the system's own status in a4.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WaitChannelReturns {
+	pub status: i32,
+	pub system_status: i32,
+}
+
+impl RegisterLayout for WaitChannelReturns {
+	fn write_registers(&self, registers: &mut EcallRegisters) {
+		registers.set(Register::A3, self.status as u32);
+		registers.set(Register::A4, self.system_status as u32);
+	}
+
+	fn read_registers(registers: &EcallRegisters) -> Self {
+		Self {
+			status: registers.get(Register::A3) as i32,
+			system_status: registers.get(Register::A4) as i32,
+		}
+	}
+}
+
+#[cfg(test)]
+pub mod tests {
+	use super::*;
+
+	#[test]
+	fn test_channel_layout() {
+		let args = ChannelArgs {
+			system_id: 0x516d,
+			read_buffer_address: 0x2000,
+			read_buffer_length: 4,
+			write_buffer_address: 0x3000,
+			write_buffer_length: 64,
+		};
+		let returns = ChannelReturns { status: 0, size: 40, system_status: -2 };
+
+		let mut registers = EcallRegisters::new();
+		args.write_registers(&mut registers);
+		assert_eq!(ChannelArgs::read_registers(&registers), args);
+
+		returns.write_registers(&mut registers);
+		assert_eq!(registers.values(), [0x516d, 0x2000, 4, 0x3000, 40, 0, -2i32 as u32]);
+		assert_eq!(ChannelReturns::read_registers(&registers), returns);
+	}
+
+	#[test]
+	fn test_channel_args_take_shared_register() {
+		let args = ChannelArgs {
+			system_id: 1,
+			read_buffer_address: 0,
+			read_buffer_length: 0,
+			write_buffer_address: 0x3000,
+			write_buffer_length: 64,
+		};
+		let unserved = ChannelReturns { status: -1, size: 0, system_status: 0 };
+
+		let returns = crate::ecall::<OpenChannelAbi>(&args, &unserved);
+		assert_eq!(returns, ChannelReturns { status: -1, size: 64, system_status: 0 });
+	}
+
+	#[test]
+	fn test_wait_channel_layout() {
+		let args = WaitChannelArgs { system_id: 0x516d, timeout_nanos: 0x0000_0001_0000_0002 };
+		let returns = WaitChannelReturns { status: 2, system_status: -1 };
+
+		let mut registers = EcallRegisters::new();
+		args.write_registers(&mut registers);
+		assert_eq!(WaitChannelArgs::read_registers(&registers), args);
+
+		returns.write_registers(&mut registers);
+		assert_eq!(registers.values(), [0x516d, 2, 1, 2, -1i32 as u32, 0, 0]);
+		assert_eq!(WaitChannelReturns::read_registers(&registers), returns);
+	}
+}
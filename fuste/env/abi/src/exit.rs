@@ -0,0 +1,42 @@
+use crate::{EcallAbi, EcallRegisters, Register, RegisterLayout};
+use fuste_ecall::Ecall;
+
+/// The exit ECALL, which doesn't return.
+pub struct ExitAbi;
+
+impl EcallAbi for ExitAbi {
+	const ECALL: Ecall = Ecall::Exit;
+
+	type Args = ExitArgs;
+
+	type Returns = ();
+}
+
+/// The status is passed by value in a0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExitArgs {
+	pub status: u32,
+}
+
+impl RegisterLayout for ExitArgs {
+	fn write_registers(&self, registers: &mut EcallRegisters) {
+		registers.set(Register::A0, self.status);
+	}
+
+	fn read_registers(registers: &EcallRegisters) -> Self {
+		Self { status: registers.get(Register::A0) }
+	}
+}
+
+#[cfg(test)]
+pub mod tests {
+	use super::*;
+
+	#[test]
+	fn test_exit_args_layout() {
+		let mut registers = EcallRegisters::new();
+		ExitArgs { status: 3 }.write_registers(&mut registers);
+		assert_eq!(registers.get(Register::A0), 3);
+		assert_eq!(ExitArgs::read_registers(&registers), ExitArgs { status: 3 });
+	}
+}
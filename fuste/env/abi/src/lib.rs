@@ -0,0 +1,216 @@
+#![no_std]
+#![allow(unexpected_cfgs)]
+
+//! The registers each ECALL takes its arguments in and returns its results in.
+//!
+//! Programs make ECALLs and systems serve them through the same [RegisterLayout]s, so the two
+//! sides can't disagree about where a value goes. The ECALL number is passed in a7 and the
+//! arguments and results in a0–a6, each ECALL choosing which through its [EcallAbi].
+pub mod abort;
+pub mod channel;
+pub mod exit;
+pub mod random;
+pub mod read;
+pub mod return_data;
+pub mod time;
+pub mod write;
+
+use fuste_ecall::Ecall;
+
+/// The register holding the ECALL number.
+pub const ECALL_NUMBER_REGISTER: u8 = 17;
+
+/// The registers ECALLs take their arguments in and return their results in.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Register {
+	A0 = 10,
+	A1 = 11,
+	A2 = 12,
+	A3 = 13,
+	A4 = 14,
+	A5 = 15,
+	A6 = 16,
+}
+
+impl Register {
+	pub const ALL: [Register; 7] = [
+		Register::A0,
+		Register::A1,
+		Register::A2,
+		Register::A3,
+		Register::A4,
+		Register::A5,
+		Register::A6,
+	];
+
+	/// The index of the register in the register file.
+	pub const fn index(self) -> u8 {
+		self as u8
+	}
+
+	const fn offset(self) -> usize {
+		(self as u8 - Register::A0 as u8) as usize
+	}
+}
+
+/// The values of a0–a6 going into or coming out of an ECALL.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EcallRegisters([u32; 7]);
+
+impl EcallRegisters {
+	pub const fn new() -> Self {
+		Self([0; 7])
+	}
+
+	/// The registers from a0 to a6.
+	pub const fn from_values(values: [u32; 7]) -> Self {
+		Self(values)
+	}
+
+	/// The values from a0 to a6.
+	pub const fn values(&self) -> [u32; 7] {
+		self.0
+	}
+
+	/// Reads each register with the function, e.g. from a machine's register file.
+	pub fn from_fn(mut read: impl FnMut(Register) -> u32) -> Self {
+		Self(Register::ALL.map(&mut read))
+	}
+
+	pub fn get(&self, register: Register) -> u32 {
+		self.0[register.offset()]
+	}
+
+	pub fn set(&mut self, register: Register, value: u32) {
+		self.0[register.offset()] = value;
+	}
+
+	/// The registers with their values, from a0 to a6.
+	pub fn iter(&self) -> impl Iterator<Item = (Register, u32)> + '_ {
+		Register::ALL.into_iter().zip(self.0)
+	}
+}
+
+/// Arguments or results laid out in the ECALL registers.
+pub trait RegisterLayout: Sized {
+	/// Writes the values into their registers, leaving the other registers as they are.
+	fn write_registers(&self, registers: &mut EcallRegisters);
+
+	fn read_registers(registers: &EcallRegisters) -> Self;
+}
+
+/// For ECALLs without arguments or results.
+impl RegisterLayout for () {
+	fn write_registers(&self, _registers: &mut EcallRegisters) {}
+
+	fn read_registers(_registers: &EcallRegisters) -> Self {}
+}
+
+/// An ECALL and the layouts of its arguments and results.
+pub trait EcallAbi {
+	const ECALL: Ecall;
+
+	type Args: RegisterLayout;
+
+	type Returns: RegisterLayout;
+}
+
+/// Makes the ECALL with the arguments and returns its results.
+///
+/// The results are preset to `unserved` before the ECALL, so that is what they read when no
+/// system serves it. Where the arguments and results share a register, the argument is passed.
+pub fn ecall<Abi: EcallAbi>(args: &Abi::Args, unserved: &Abi::Returns) -> Abi::Returns {
+	let mut registers = EcallRegisters::new();
+	unserved.write_registers(&mut registers);
+	args.write_registers(&mut registers);
+	Abi::Returns::read_registers(&raw_ecall(Abi::ECALL.to_u32(), registers))
+}
+
+/// Makes the ECALL numbered `ecall` with the registers, returning them as the system left them.
+#[inline(never)]
+pub fn raw_ecall(ecall: u32, registers: EcallRegisters) -> EcallRegisters {
+	#[cfg(target_family = "fuste")]
+	{
+		let [mut a0, mut a1, mut a2, mut a3, mut a4, mut a5, mut a6] = registers.0;
+		unsafe {
+			core::arch::asm!(
+				"ecall",
+				inout("a0") a0,
+				inout("a1") a1,
+				inout("a2") a2,
+				inout("a3") a3,
+				inout("a4") a4,
+				inout("a5") a5,
+				inout("a6") a6,
+				in("a7") ecall,
+			);
+		}
+		EcallRegisters([a0, a1, a2, a3, a4, a5, a6])
+	}
+
+	#[cfg(not(target_family = "fuste"))]
+	{
+		// Nothing serves ECALLs off the fuste target.
+		let _ecall = ecall;
+		registers
+	}
+}
+
+/// Makes an ECALL which doesn't return, like [exit::ExitAbi].
+#[inline(never)]
+pub fn ecall_noreturn<Abi: EcallAbi>(args: &Abi::Args) -> ! {
+	let mut registers = EcallRegisters::new();
+	args.write_registers(&mut registers);
+	let _ecall = Abi::ECALL.to_u32();
+	let [_a0, _a1, _a2, _a3, _a4, _a5, _a6] = registers.values();
+
+	#[cfg(target_family = "fuste")]
+	{
+		unsafe {
+			core::arch::asm!(
+				"ecall",
+				in("a0") _a0,
+				in("a1") _a1,
+				in("a2") _a2,
+				in("a3") _a3,
+				in("a4") _a4,
+				in("a5") _a5,
+				in("a6") _a6,
+				in("a7") _ecall,
+			);
+		}
+	}
+
+	// Hosts shouldn't resume the program after the ECALL, but if one does it spins here rather
+	// than running whatever follows.
+	#[allow(clippy::empty_loop)]
+	loop {}
+}
+
+#[cfg(test)]
+pub mod tests {
+	use super::*;
+
+	#[test]
+	fn test_registers_by_name() {
+		let registers = EcallRegisters::from_fn(|register| register.index() as u32);
+		assert_eq!(registers.get(Register::A0), 10);
+		assert_eq!(registers.get(Register::A6), 16);
+		assert_eq!(registers.iter().count(), 7);
+
+		let mut registers = EcallRegisters::new();
+		registers.set(Register::A3, 3);
+		assert_eq!(
+			registers.iter().find(|(register, _)| *register == Register::A3),
+			Some((Register::A3, 3))
+		);
+	}
+
+	#[test]
+	fn test_unserved_ecall_returns_preset() {
+		let unserved = write::WriteReturns { status: -1, system_status: 0 };
+		let args = write::WriteArgs { system_id: 1, buffer_address: 0x1000, buffer_length: 4 };
+		assert_eq!(ecall::<write::WriteAbi>(&args, &unserved), unserved);
+	}
+}
@@ -0,0 +1,74 @@
+use crate::{EcallAbi, EcallRegisters, Register, RegisterLayout};
+use fuste_ecall::Ecall;
+
+/// The random ECALL, filling a buffer with random bytes from the host.
+pub struct RandomAbi;
+
+impl EcallAbi for RandomAbi {
+	const ECALL: Ecall = Ecall::Random;
+
+	type Args = RandomArgs;
+
+	type Returns = RandomReturns;
+}
+
+/// The buffer to fill is passed in a0 and a1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RandomArgs {
+	pub buffer_address: u32,
+	pub buffer_length: u32,
+}
+
+impl RegisterLayout for RandomArgs {
+	fn write_registers(&self, registers: &mut EcallRegisters) {
+		registers.set(Register::A0, self.buffer_address);
+		registers.set(Register::A1, self.buffer_length);
+	}
+
+	fn read_registers(registers: &EcallRegisters) -> Self {
+		Self {
+			buffer_address: registers.get(Register::A0),
+			buffer_length: registers.get(Register::A1),
+		}
+	}
+}
+
+/// The status code is returned in a2 and the system's own status in a3.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RandomReturns {
+	pub status: i32,
+	pub system_status: i32,
+}
+
+impl RegisterLayout for RandomReturns {
+	fn write_registers(&self, registers: &mut EcallRegisters) {
+		registers.set(Register::A2, self.status as u32);
+		registers.set(Register::A3, self.system_status as u32);
+	}
+
+	fn read_registers(registers: &EcallRegisters) -> Self {
+		Self {
+			status: registers.get(Register::A2) as i32,
+			system_status: registers.get(Register::A3) as i32,
+		}
+	}
+}
+
+#[cfg(test)]
+pub mod tests {
+	use super::*;
+
+	#[test]
+	fn test_random_layout() {
+		let args = RandomArgs { buffer_address: 0x3000, buffer_length: 32 };
+		let returns = RandomReturns { status: -3, system_status: 5 };
+
+		let mut registers = EcallRegisters::new();
+		args.write_registers(&mut registers);
+		returns.write_registers(&mut registers);
+		assert_eq!(registers.values(), [0x3000, 32, -3i32 as u32, 5, 0, 0, 0]);
+
+		assert_eq!(RandomArgs::read_registers(&registers), args);
+		assert_eq!(RandomReturns::read_registers(&registers), returns);
+	}
+}
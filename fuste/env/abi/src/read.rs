@@ -0,0 +1,82 @@
+use crate::{EcallAbi, EcallRegisters, Register, RegisterLayout};
+use fuste_ecall::Ecall;
+
+/// The read ECALL, reading from a read system into a buffer.
+pub struct ReadAbi;
+
+impl EcallAbi for ReadAbi {
+	const ECALL: Ecall = Ecall::Read;
+
+	type Args = ReadArgs;
+
+	type Returns = ReadReturns;
+}
+
+/// The system ID is passed in a0 and the buffer's address and length in a1 and a2, as for
+/// [crate::write::WriteArgs].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadArgs {
+	pub system_id: u32,
+	pub buffer_address: u32,
+	pub buffer_length: u32,
+}
+
+impl RegisterLayout for ReadArgs {
+	fn write_registers(&self, registers: &mut EcallRegisters) {
+		registers.set(Register::A0, self.system_id);
+		registers.set(Register::A1, self.buffer_address);
+		registers.set(Register::A2, self.buffer_length);
+	}
+
+	fn read_registers(registers: &EcallRegisters) -> Self {
+		Self {
+			system_id: registers.get(Register::A0),
+			buffer_address: registers.get(Register::A1),
+			buffer_length: registers.get(Register::A2),
+		}
+	}
+}
+
+/// The status code is returned in a3, the system's own status in a4 and the number of bytes read
+/// in a5.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadReturns {
+	pub status: i32,
+	pub system_status: i32,
+	pub size: u32,
+}
+
+impl RegisterLayout for ReadReturns {
+	fn write_registers(&self, registers: &mut EcallRegisters) {
+		registers.set(Register::A3, self.status as u32);
+		registers.set(Register::A4, self.system_status as u32);
+		registers.set(Register::A5, self.size);
+	}
+
+	fn read_registers(registers: &EcallRegisters) -> Self {
+		Self {
+			status: registers.get(Register::A3) as i32,
+			system_status: registers.get(Register::A4) as i32,
+			size: registers.get(Register::A5),
+		}
+	}
+}
+
+#[cfg(test)]
+pub mod tests {
+	use super::*;
+
+	#[test]
+	fn test_read_layout() {
+		let args = ReadArgs { system_id: 0, buffer_address: 0x2000, buffer_length: 64 };
+		let returns = ReadReturns { status: 0, system_status: 0, size: 12 };
+
+		let mut registers = EcallRegisters::new();
+		args.write_registers(&mut registers);
+		returns.write_registers(&mut registers);
+		assert_eq!(registers.values(), [0, 0x2000, 64, 0, 0, 12, 0]);
+
+		assert_eq!(ReadArgs::read_registers(&registers), args);
+		assert_eq!(ReadReturns::read_registers(&registers), returns);
+	}
+}
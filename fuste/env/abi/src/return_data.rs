@@ -0,0 +1,64 @@
+use crate::{EcallAbi, EcallRegisters, Register, RegisterLayout};
+use fuste_ecall::Ecall;
+
+/// The return data ECALL, which hands a buffer back to the host as the program's result.
+pub struct ReturnDataAbi;
+
+impl EcallAbi for ReturnDataAbi {
+	const ECALL: Ecall = Ecall::ReturnData;
+
+	type Args = ReturnDataArgs;
+
+	type Returns = ReturnDataReturns;
+}
+
+/// The data is passed as a buffer in a0 and a1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReturnDataArgs {
+	pub data_address: u32,
+	pub data_length: u32,
+}
+
+impl RegisterLayout for ReturnDataArgs {
+	fn write_registers(&self, registers: &mut EcallRegisters) {
+		registers.set(Register::A0, self.data_address);
+		registers.set(Register::A1, self.data_length);
+	}
+
+	fn read_registers(registers: &EcallRegisters) -> Self {
+		Self { data_address: registers.get(Register::A0), data_length: registers.get(Register::A1) }
+	}
+}
+
+/// The status is returned in a2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReturnDataReturns {
+	pub status: i32,
+}
+
+impl RegisterLayout for ReturnDataReturns {
+	fn write_registers(&self, registers: &mut EcallRegisters) {
+		registers.set(Register::A2, self.status as u32);
+	}
+
+	fn read_registers(registers: &EcallRegisters) -> Self {
+		Self { status: registers.get(Register::A2) as i32 }
+	}
+}
+
+#[cfg(test)]
+pub mod tests {
+	use super::*;
+
+	#[test]
+	fn test_return_data_layout() {
+		let args = ReturnDataArgs { data_address: 0x1000, data_length: 12 };
+		let returns = ReturnDataReturns { status: -2 };
+		let mut registers = EcallRegisters::new();
+		args.write_registers(&mut registers);
+		returns.write_registers(&mut registers);
+		assert_eq!(registers.values(), [0x1000, 12, -2i32 as u32, 0, 0, 0, 0]);
+		assert_eq!(ReturnDataArgs::read_registers(&registers), args);
+		assert_eq!(ReturnDataReturns::read_registers(&registers), returns);
+	}
+}
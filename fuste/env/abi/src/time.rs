@@ -0,0 +1,58 @@
+use crate::{EcallAbi, EcallRegisters, Register, RegisterLayout};
+use fuste_ecall::Ecall;
+
+/// The time ECALL, reading the host's clock.
+pub struct TimeAbi;
+
+impl EcallAbi for TimeAbi {
+	const ECALL: Ecall = Ecall::Time;
+
+	type Args = ();
+
+	type Returns = TimeReturns;
+}
+
+/// The status code is returned in a1, the nanoseconds since the clock's epoch in a2 (low word)
+/// and a3 (high word), and the kind of clock in a4.
+///
+/// a0 is left for the [fuste_ecall::EcallStatus] of an ECALL nothing serves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeReturns {
+	pub status: i32,
+	pub nanos: u64,
+	pub clock: u32,
+}
+
+impl RegisterLayout for TimeReturns {
+	fn write_registers(&self, registers: &mut EcallRegisters) {
+		registers.set(Register::A1, self.status as u32);
+		registers.set(Register::A2, self.nanos as u32);
+		registers.set(Register::A3, (self.nanos >> 32) as u32);
+		registers.set(Register::A4, self.clock);
+	}
+
+	fn read_registers(registers: &EcallRegisters) -> Self {
+		Self {
+			status: registers.get(Register::A1) as i32,
+			nanos: u64::from(registers.get(Register::A2))
+				| (u64::from(registers.get(Register::A3)) << 32),
+			clock: registers.get(Register::A4),
+		}
+	}
+}
+
+#[cfg(test)]
+pub mod tests {
+	use super::*;
+
+	#[test]
+	fn test_time_layout() {
+		let returns = TimeReturns { status: 0, nanos: 0x0000_0001_0000_0002, clock: 2 };
+
+		let mut registers = EcallRegisters::new();
+		returns.write_registers(&mut registers);
+		assert_eq!(registers.values(), [0, 0, 2, 1, 2, 0, 0]);
+
+		assert_eq!(TimeReturns::read_registers(&registers), returns);
+	}
+}
@@ -0,0 +1,77 @@
+use crate::{EcallAbi, EcallRegisters, Register, RegisterLayout};
+use fuste_ecall::Ecall;
+
+/// The write ECALL, writing a buffer to a write system.
+pub struct WriteAbi;
+
+impl EcallAbi for WriteAbi {
+	const ECALL: Ecall = Ecall::Write;
+
+	type Args = WriteArgs;
+
+	type Returns = WriteReturns;
+}
+
+/// The system ID is passed in a0 and the buffer's address and length in a1 and a2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WriteArgs {
+	pub system_id: u32,
+	pub buffer_address: u32,
+	pub buffer_length: u32,
+}
+
+impl RegisterLayout for WriteArgs {
+	fn write_registers(&self, registers: &mut EcallRegisters) {
+		registers.set(Register::A0, self.system_id);
+		registers.set(Register::A1, self.buffer_address);
+		registers.set(Register::A2, self.buffer_length);
+	}
+
+	fn read_registers(registers: &EcallRegisters) -> Self {
+		Self {
+			system_id: registers.get(Register::A0),
+			buffer_address: registers.get(Register::A1),
+			buffer_length: registers.get(Register::A2),
+		}
+	}
+}
+
+/// The status code is returned in a3 and the system's own status in a4.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WriteReturns {
+	pub status: i32,
+	pub system_status: i32,
+}
+
+impl RegisterLayout for WriteReturns {
+	fn write_registers(&self, registers: &mut EcallRegisters) {
+		registers.set(Register::A3, self.status as u32);
+		registers.set(Register::A4, self.system_status as u32);
+	}
+
+	fn read_registers(registers: &EcallRegisters) -> Self {
+		Self {
+			status: registers.get(Register::A3) as i32,
+			system_status: registers.get(Register::A4) as i32,
+		}
+	}
+}
+
+#[cfg(test)]
+pub mod tests {
+	use super::*;
+
+	#[test]
+	fn test_write_layout() {
+		let args = WriteArgs { system_id: 1, buffer_address: 0x2000, buffer_length: 12 };
+		let returns = WriteReturns { status: -3, system_status: 7 };
+
+		let mut registers = EcallRegisters::new();
+		args.write_registers(&mut registers);
+		returns.write_registers(&mut registers);
+		assert_eq!(registers.values(), [1, 0x2000, 12, -3i32 as u32, 7, 0, 0]);
+
+		assert_eq!(WriteArgs::read_registers(&registers), args);
+		assert_eq!(WriteReturns::read_registers(&registers), returns);
+	}
+}
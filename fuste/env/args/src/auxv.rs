@@ -0,0 +1,22 @@
+use crate::InitialStack;
+
+/// Ends the auxiliary vector.
+pub const AT_NULL: usize = 0;
+/// The page size of the machine.
+pub const AT_PAGESZ: usize = 6;
+/// The address of 16 random bytes, e.g. for seeding hash maps.
+pub const AT_RANDOM: usize = 25;
+
+/// Gets the value of an auxiliary vector entry.
+pub fn get(key: usize) -> Option<usize> {
+	InitialStack::current().auxv(key)
+}
+
+pub fn page_size() -> Option<usize> {
+	get(AT_PAGESZ)
+}
+
+/// The 16 random bytes the host placed on the stack.
+pub fn random_bytes() -> Option<&'static [u8; 16]> {
+	get(AT_RANDOM).map(|address| unsafe { &*(address as *const [u8; 16]) })
+}
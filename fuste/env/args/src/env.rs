@@ -0,0 +1,43 @@
+use crate::{string, InitialStack};
+
+/// The environment variables the program was started with, see [vars].
+#[derive(Debug, Clone)]
+pub struct Vars {
+	stack: InitialStack,
+	index: usize,
+}
+
+impl Vars {
+	pub(crate) fn new(stack: InitialStack, index: usize) -> Self {
+		Self { stack, index }
+	}
+}
+
+impl Iterator for Vars {
+	type Item = (&'static str, &'static str);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.stack.argc.is_null() {
+			return None;
+		}
+
+		let address = self.stack.word(self.index);
+		if address == 0 {
+			return None;
+		}
+		self.index += 1;
+
+		let entry = unsafe { string(address) };
+		Some(entry.split_once('=').unwrap_or((entry, "")))
+	}
+}
+
+/// Iterates over the environment variables as `(name, value)` pairs.
+pub fn vars() -> Vars {
+	InitialStack::current().vars()
+}
+
+/// Gets the value of the environment variable with the given name.
+pub fn var(name: &str) -> Option<&'static str> {
+	vars().find(|(key, _)| *key == name).map(|(_, value)| value)
+}
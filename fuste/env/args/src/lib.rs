@@ -0,0 +1,206 @@
+#![no_std]
+
+//! Reads the arguments, environment and auxiliary vector the host lays out at the top of the
+//! stack, following the RISC-V psABI:
+//!
+//! ```text
+//! sp -> argc
+//!       argv[0], ..., argv[argc - 1], 0
+//!       envp[0], ..., envp[n - 1], 0
+//!       auxv pairs, ..., AT_NULL, 0
+//!       strings and random bytes
+//! ```
+//!
+//! Nothing is copied, the strings are borrowed from the stack for the rest of the program.
+pub mod auxv;
+pub mod env;
+
+use core::ffi::{c_char, CStr};
+
+/// The words the host placed at the stack pointer the program was entered with.
+#[derive(Debug, Clone, Copy)]
+pub struct InitialStack {
+	argc: *const usize,
+}
+
+static mut INITIAL_STACK: InitialStack = InitialStack { argc: core::ptr::null() };
+
+/// Records the stack pointer the program was entered with.
+///
+/// # Safety
+///
+/// Must be called by the startup code before anything reads the arguments, with a pointer
+/// accepted by [InitialStack::new].
+pub unsafe fn init(argc: *const usize) {
+	INITIAL_STACK = InitialStack::new(argc);
+}
+
+/// Reads a NUL-terminated string from the initial stack.
+///
+/// Strings which aren't valid UTF-8 read as empty, hosts only pass UTF-8.
+unsafe fn string(address: usize) -> &'static str {
+	CStr::from_ptr(address as *const c_char).to_str().unwrap_or_default()
+}
+
+impl InitialStack {
+	/// # Safety
+	///
+	/// The pointer must be null, when the host passed nothing, or point at an initial stack
+	/// which is never modified or freed.
+	pub const unsafe fn new(argc: *const usize) -> Self {
+		Self { argc }
+	}
+
+	/// The stack recorded by [init].
+	pub fn current() -> Self {
+		unsafe { INITIAL_STACK }
+	}
+
+	fn word(&self, index: usize) -> usize {
+		unsafe { *self.argc.add(index) }
+	}
+
+	pub fn argc(&self) -> usize {
+		if self.argc.is_null() {
+			return 0;
+		}
+		self.word(0)
+	}
+
+	/// Iterates over the arguments, starting with the program name.
+	pub fn args(&self) -> Args {
+		Args { stack: *self, index: 0, len: self.argc() }
+	}
+
+	/// Iterates over the environment variables as `(name, value)` pairs.
+	pub fn vars(&self) -> env::Vars {
+		env::Vars::new(*self, self.argc() + 2)
+	}
+
+	/// The index of the first auxiliary vector word, after the environment.
+	fn auxv_index(&self) -> usize {
+		let mut index = self.argc() + 2;
+		while self.word(index) != 0 {
+			index += 1;
+		}
+		index + 1
+	}
+
+	/// Gets the value of an auxiliary vector entry.
+	pub fn auxv(&self, key: usize) -> Option<usize> {
+		if self.argc.is_null() {
+			return None;
+		}
+
+		let mut index = self.auxv_index();
+		loop {
+			match self.word(index) {
+				auxv::AT_NULL => return None,
+				entry if entry == key => return Some(self.word(index + 1)),
+				_ => index += 2,
+			}
+		}
+	}
+}
+
+/// The arguments the program was started with, see [args].
+#[derive(Debug, Clone)]
+pub struct Args {
+	stack: InitialStack,
+	index: usize,
+	len: usize,
+}
+
+impl Iterator for Args {
+	type Item = &'static str;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.index >= self.len {
+			return None;
+		}
+		self.index += 1;
+		Some(unsafe { string(self.stack.word(self.index)) })
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		let remaining = self.len - self.index;
+		(remaining, Some(remaining))
+	}
+}
+
+impl ExactSizeIterator for Args {}
+
+/// Iterates over the arguments the program was started with, starting with the program name.
+///
+/// Empty if the host didn't pass any arguments.
+pub fn args() -> Args {
+	InitialStack::current().args()
+}
+
+#[cfg(test)]
+pub mod tests {
+	use super::*;
+
+	fn address(string: &'static CStr) -> usize {
+		string.as_ptr() as usize
+	}
+
+	fn words() -> [usize; 12] {
+		[
+			2,
+			address(c"prog"),
+			address(c"--fast"),
+			0,
+			address(c"HOME=/root"),
+			address(c"EMPTY"),
+			0,
+			auxv::AT_PAGESZ,
+			4096,
+			auxv::AT_NULL,
+			0,
+			0,
+		]
+	}
+
+	#[test]
+	fn test_args() {
+		let words = words();
+		let stack = unsafe { InitialStack::new(words.as_ptr()) };
+
+		let mut args = stack.args();
+		assert_eq!(args.len(), 2);
+		assert_eq!(args.next(), Some("prog"));
+		assert_eq!(args.next(), Some("--fast"));
+		assert_eq!(args.next(), None);
+	}
+
+	#[test]
+	fn test_vars() {
+		let words = words();
+		let stack = unsafe { InitialStack::new(words.as_ptr()) };
+
+		let mut vars = stack.vars();
+		assert_eq!(vars.next(), Some(("HOME", "/root")));
+		assert_eq!(vars.next(), Some(("EMPTY", "")));
+		assert_eq!(vars.next(), None);
+		assert_eq!(stack.vars().find(|(name, _)| *name == "HOME").map(|(_, v)| v), Some("/root"));
+	}
+
+	#[test]
+	fn test_auxv() {
+		let words = words();
+		let stack = unsafe { InitialStack::new(words.as_ptr()) };
+
+		assert_eq!(stack.auxv(auxv::AT_PAGESZ), Some(4096));
+		assert_eq!(stack.auxv(auxv::AT_RANDOM), None);
+	}
+
+	#[test]
+	fn test_empty() {
+		let stack = unsafe { InitialStack::new(core::ptr::null()) };
+
+		assert_eq!(stack.args().next(), None);
+		assert_eq!(stack.vars().next(), None);
+		assert_eq!(stack.auxv(auxv::AT_PAGESZ), None);
+	}
+}
@@ -3,6 +3,12 @@
 
 pub mod ops;
 use core::fmt::{self, Display};
+use core::time::Duration;
+#[cfg(target_family = "fuste")]
+use fuste_abi::channel::{
+	ChannelArgs, ChannelReturns, CheckChannelAbi, OpenChannelAbi, WaitChannelAbi, WaitChannelArgs,
+	WaitChannelReturns, NO_TIMEOUT,
+};
 use fuste_ecall::Ecall;
 
 /// The system ID is a 32-bit value that identifies the system to write to.
@@ -216,44 +222,40 @@ pub enum ChannelOp {
 	Check = Ecall::CheckChannel as u32,
 }
 
-#[inline(never)]
 pub fn channel_op(
 	system_id: ChannelSystemId,
 	op: ChannelOp,
-	_read_buffer: &[u8],
-	_read_write_buffer: &mut [u8],
+	read_buffer: &[u8],
+	read_write_buffer: &mut [u8],
 ) -> Result<ChannelStatus, ChannelError> {
-	let _ecall = op as u32;
-	let _system_id = system_id.to_u32();
-	let _size: u32;
-	let _status: i32;
-	let _system_status: i32;
-	let _status_ignored = ChannelStatusCode::Ignored.to_i32();
-
 	#[cfg(target_family = "fuste")]
 	{
-		unsafe {
-			core::arch::asm!(
-				"ecall",
-				in("a7") _ecall,                // syscall number for channel operation
-				in("a0") _system_id,            // the channel system id
-				in("a1") _read_buffer.as_ptr(),      // pointer to buffer
-				in("a2") _read_buffer.len(),         // length
-				in("a3") _read_write_buffer.as_ptr(), // pointer to write buffer
-				in("a4") _read_write_buffer.len(), // pointer to write buffer
-				in("a5") _status_ignored,       // if this isn't reset, the system must have ignored the call
-				lateout("a5") _status,          // return value (bytes written or -errno)
-				lateout("a4") _size,            // the size of the buffer written
-				lateout("a6") _system_status,   // the system status of the operation
-			);
-		}
-
-		let channel_status = ChannelStatus::try_from_words(_size, _status, _system_status)?;
+		let args = ChannelArgs {
+			system_id: system_id.to_u32(),
+			read_buffer_address: read_buffer.as_ptr() as u32,
+			read_buffer_length: read_buffer.len() as u32,
+			write_buffer_address: read_write_buffer.as_mut_ptr() as u32,
+			write_buffer_length: read_write_buffer.len() as u32,
+		};
+		// If this isn't reset, the system must have ignored the call.
+		let unserved = ChannelReturns {
+			status: ChannelStatusCode::Ignored.to_i32(),
+			size: 0,
+			system_status: 0,
+		};
+		let returns = match op {
+			ChannelOp::Open => fuste_abi::ecall::<OpenChannelAbi>(&args, &unserved),
+			ChannelOp::Check => fuste_abi::ecall::<CheckChannelAbi>(&args, &unserved),
+		};
+
+		let channel_status =
+			ChannelStatus::try_from_words(returns.size, returns.status, returns.system_status)?;
 		channel_status.ok()
 	}
 
 	#[cfg(not(target_family = "fuste"))]
 	{
+		let _ = (system_id, op, read_buffer, read_write_buffer);
 		Err(ChannelError::NotImplemented)
 	}
 }
@@ -275,3 +277,35 @@ pub fn check(
 ) -> Result<ChannelStatus, ChannelError> {
 	channel_op(system_id, ChannelOp::Check, read_buffer, write_buffer)
 }
+
+/// Parks the program until the channel has progressed or the timeout has passed, rather than
+/// checking it over and over.
+///
+/// The status is [ChannelStatusCode::Success] if the channel progressed and
+/// [ChannelStatusCode::Holding] if the timeout passed first, or straight away if the host has
+/// nothing to wait for, so check the channel for its status. Without a timeout the wait lasts for
+/// as long as the channel takes.
+pub fn wait(
+	system_id: ChannelSystemId,
+	timeout: Option<Duration>,
+) -> Result<ChannelStatus, ChannelError> {
+	#[cfg(target_family = "fuste")]
+	{
+		// A timeout too long to give in nanoseconds is as good as none.
+		let timeout_nanos = timeout
+			.map_or(NO_TIMEOUT, |timeout| u64::try_from(timeout.as_nanos()).unwrap_or(NO_TIMEOUT));
+		let args = WaitChannelArgs { system_id: system_id.to_u32(), timeout_nanos };
+		// If this isn't reset, the system must have ignored the call.
+		let unserved =
+			WaitChannelReturns { status: ChannelStatusCode::Ignored.to_i32(), system_status: 0 };
+		let returns = fuste_abi::ecall::<WaitChannelAbi>(&args, &unserved);
+
+		ChannelStatus::try_from_words(0, returns.status, returns.system_status)?.ok()
+	}
+
+	#[cfg(not(target_family = "fuste"))]
+	{
+		let _ = (system_id, timeout);
+		Err(ChannelError::NotImplemented)
+	}
+}
@@ -1,24 +1,25 @@
-use super::{check, open, ChannelError, ChannelStatus, ChannelSystemId};
+use super::{check, open, wait, ChannelError, ChannelStatus, ChannelSystemId};
 
 /// Writes to a channel once and blocks until the operation is complete.
+///
+/// While the system holds the channel, the program waits for it to progress before checking it
+/// again, unless the host can't park it, in which case it checks straight away.
 pub fn block_on_channel(
 	system_id: ChannelSystemId,
 	read_buffer: &[u8],
 	read_write_buffer: &mut [u8],
 ) -> Result<ChannelStatus, ChannelError> {
-	let status = open(system_id.clone(), read_buffer, read_write_buffer)?;
+	let mut status = open(system_id.clone(), read_buffer, read_write_buffer)?;
+	let mut can_wait = true;
 
 	// Success means completion
-	if status.is_success() {
-		Ok(status.clone())
-	} else {
-		loop {
-			let status = check(system_id.clone(), read_buffer, read_write_buffer)?;
-			if status.is_success() {
-				return Ok(status);
-			}
+	while !status.is_success() {
+		if status.is_holding() && can_wait {
+			can_wait = wait(system_id.clone(), None).is_ok();
 		}
+		status = check(system_id.clone(), read_buffer, read_write_buffer)?;
 	}
+	Ok(status)
 }
 
 /// Blocks on a channel once and returns the slice of the buffer that was written by the system.
@@ -50,6 +51,7 @@ pub fn block_on_channel_stream_request<'r, 'w>(
 	let max_buffer_len = read_write_buffer.len();
 	let mut status = open(system_id.clone(), read_buffer, read_write_buffer)?;
 	let mut written_len = status.size as usize;
+	let mut can_wait = true;
 
 	loop {
 		// SAFETY: the system guarantees that it wrote at most `buffer.len()` bytes
@@ -68,6 +70,10 @@ pub fn block_on_channel_stream_request<'r, 'w>(
 			return Ok(());
 		}
 
+		if status.is_holding() && can_wait {
+			can_wait = wait(system_id.clone(), None).is_ok();
+		}
+
 		// The check tells the system to turn over the next operation
 		status = check(system_id.clone(), read_buffer, read_write_buffer)?;
 		written_len = status.size as usize;
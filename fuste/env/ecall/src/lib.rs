@@ -21,9 +21,15 @@ impl Display for EcallError {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Ecall {
 	Exit = 93,
+	Abort = 94,
+	ReturnData = 95,
+	Read = 63,
 	Write = 64,
 	OpenChannel = 33,
 	CheckChannel = 34,
+	WaitChannel = 35,
+	Random = 278,
+	Time = 113,
 }
 
 impl Ecall {
@@ -34,9 +40,15 @@ impl Ecall {
 	pub fn try_from_u32(value: u32) -> Result<Self, EcallError> {
 		match value {
 			93 => Ok(Ecall::Exit),
+			94 => Ok(Ecall::Abort),
+			95 => Ok(Ecall::ReturnData),
+			63 => Ok(Ecall::Read),
 			64 => Ok(Ecall::Write),
 			33 => Ok(Ecall::OpenChannel),
 			34 => Ok(Ecall::CheckChannel),
+			35 => Ok(Ecall::WaitChannel),
+			278 => Ok(Ecall::Random),
+			113 => Ok(Ecall::Time),
 			_ => Err(EcallError::InvalidEcall(value)),
 		}
 	}
@@ -71,18 +83,30 @@ pub mod tests {
 	#[test]
 	fn test_ecall_try_from_u32() {
 		assert_eq!(Ecall::try_from_u32(93), Ok(Ecall::Exit));
+		assert_eq!(Ecall::try_from_u32(94), Ok(Ecall::Abort));
+		assert_eq!(Ecall::try_from_u32(95), Ok(Ecall::ReturnData));
+		assert_eq!(Ecall::try_from_u32(63), Ok(Ecall::Read));
 		assert_eq!(Ecall::try_from_u32(64), Ok(Ecall::Write));
 		assert_eq!(Ecall::try_from_u32(33), Ok(Ecall::OpenChannel));
 		assert_eq!(Ecall::try_from_u32(34), Ok(Ecall::CheckChannel));
-		assert_eq!(Ecall::try_from_u32(35), Err(EcallError::InvalidEcall(35)));
+		assert_eq!(Ecall::try_from_u32(35), Ok(Ecall::WaitChannel));
+		assert_eq!(Ecall::try_from_u32(278), Ok(Ecall::Random));
+		assert_eq!(Ecall::try_from_u32(113), Ok(Ecall::Time));
+		assert_eq!(Ecall::try_from_u32(36), Err(EcallError::InvalidEcall(36)));
 	}
 
 	#[test]
 	fn test_ecall_to_u32() {
 		assert_eq!(Ecall::Exit.to_u32(), 93);
+		assert_eq!(Ecall::Abort.to_u32(), 94);
+		assert_eq!(Ecall::ReturnData.to_u32(), 95);
+		assert_eq!(Ecall::Read.to_u32(), 63);
 		assert_eq!(Ecall::Write.to_u32(), 64);
 		assert_eq!(Ecall::OpenChannel.to_u32(), 33);
 		assert_eq!(Ecall::CheckChannel.to_u32(), 34);
+		assert_eq!(Ecall::WaitChannel.to_u32(), 35);
+		assert_eq!(Ecall::Random.to_u32(), 278);
+		assert_eq!(Ecall::Time.to_u32(), 113);
 	}
 
 	#[test]
@@ -1,70 +1,181 @@
 #![no_std]
 #![allow(unexpected_cfgs)]
 
-use core::fmt::{self, Display};
-use fuste_ecall::Ecall;
+use core::fmt::{self, Display, Write};
+use core::panic::PanicInfo;
+use fuste_abi::abort::{AbortAbi, AbortArgs};
+use fuste_abi::exit::{ExitAbi, ExitArgs};
+#[cfg(target_family = "fuste")]
+use fuste_abi::return_data::{ReturnDataAbi, ReturnDataArgs, ReturnDataReturns};
 
+/// The most bytes of a panic's location and message sent to the host.
+pub const PANIC_MESSAGE_CAPACITY: usize = 256;
+
+/// The status a program exits with, which the host sees as its exit code.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub enum ExitError {
-	InvalidExitStatus(u32),
+pub enum ExitStatus {
+	Success,
+	Error,
+	Terminated,
+	Unsupported,
+	/// Any other exit code, above those of the named statuses.
+	Code(u32),
 }
 
-impl Display for ExitError {
-	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl ExitStatus {
+	pub fn to_u32(self) -> u32 {
 		match self {
-			ExitError::InvalidExitStatus(value) => write!(f, "Invalid exit status: {}", value),
+			ExitStatus::Success => 0,
+			ExitStatus::Error => 1,
+			ExitStatus::Terminated => 2,
+			ExitStatus::Unsupported => 3,
+			ExitStatus::Code(code) => code,
+		}
+	}
+
+	/// The status for the exit code, so that codes 0 to 3 are always the named statuses.
+	pub fn from_u32(value: u32) -> Self {
+		match value {
+			0 => ExitStatus::Success,
+			1 => ExitStatus::Error,
+			2 => ExitStatus::Terminated,
+			3 => ExitStatus::Unsupported,
+			code => ExitStatus::Code(code),
 		}
 	}
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub enum ExitStatus {
+pub enum ReturnDataStatusCode {
+	/// The host holds more return data than it allows.
+	TooLarge = -2,
+	Ignored = -1,
 	Success = 0,
-	Error = 1,
-	Terminated = 2,
-	Unsupported = 3,
 }
 
-impl ExitStatus {
-	pub fn to_u32(self) -> u32 {
-		self as u32
+impl ReturnDataStatusCode {
+	pub fn to_i32(self) -> i32 {
+		self as i32
 	}
 
-	pub fn try_from_u32(value: u32) -> Result<Self, ExitError> {
+	pub fn try_from_i32(value: i32) -> Result<Self, ReturnDataError> {
 		match value {
-			0 => Ok(ExitStatus::Success),
-			1 => Ok(ExitStatus::Error),
-			2 => Ok(ExitStatus::Terminated),
-			3 => Ok(ExitStatus::Unsupported),
-			_ => Err(ExitError::InvalidExitStatus(value)),
+			-2 => Ok(ReturnDataStatusCode::TooLarge),
+			-1 => Ok(ReturnDataStatusCode::Ignored),
+			0 => Ok(ReturnDataStatusCode::Success),
+			_ => Err(ReturnDataError::InvalidStatusCode(value)),
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReturnDataError {
+	TooLarge,
+	Ignored,
+	InvalidStatusCode(i32),
+	NotImplemented,
+}
+
+impl Display for ReturnDataError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			ReturnDataError::TooLarge => write!(f, "Return data too large"),
+			ReturnDataError::Ignored => write!(f, "Return data ignored"),
+			ReturnDataError::InvalidStatusCode(code) => write!(f, "Invalid status code: {code}"),
+			ReturnDataError::NotImplemented => write!(f, "Not implemented"),
 		}
 	}
 }
 
-#[inline(never)]
 pub fn exit(status: ExitStatus) -> ! {
-	let _status = status.to_u32();
-	let _ecall = Ecall::Exit.to_u32();
+	fuste_abi::ecall_noreturn::<ExitAbi>(&ExitArgs { status: status.to_u32() })
+}
 
+/// Exits with the code, e.g. to tell the host which check failed.
+pub fn exit_code(code: u32) -> ! {
+	exit(ExitStatus::from_u32(code))
+}
+
+/// Hands the data back to the host as the program's result, replacing any handed back before.
+pub fn return_data(data: &[u8]) -> Result<(), ReturnDataError> {
 	#[cfg(target_family = "fuste")]
 	{
-		unsafe {
-			core::arch::asm!(
-				"ecall",
-				in("a0") _status,
-				in("a7") _ecall,
-				options(noreturn)
-			);
+		let args =
+			ReturnDataArgs { data_address: data.as_ptr() as u32, data_length: data.len() as u32 };
+		// If this isn't reset, the system must have ignored the call.
+		let unserved = ReturnDataReturns { status: ReturnDataStatusCode::Ignored.to_i32() };
+		let returns = fuste_abi::ecall::<ReturnDataAbi>(&args, &unserved);
+
+		match ReturnDataStatusCode::try_from_i32(returns.status)? {
+			ReturnDataStatusCode::Success => Ok(()),
+			ReturnDataStatusCode::Ignored => Err(ReturnDataError::Ignored),
+			ReturnDataStatusCode::TooLarge => Err(ReturnDataError::TooLarge),
 		}
 	}
 
 	#[cfg(not(target_family = "fuste"))]
 	{
-		loop {
-			// we could make this call to a machine field that
-			// is only compiled and available in non fuste targets.
-			// that would potentially make testing fun.
+		let _ = data;
+		Err(ReturnDataError::NotImplemented)
+	}
+}
+
+/// Ends the program with the message for the host, or with [ExitStatus::Error] if nothing serves
+/// the abort ECALL.
+pub fn abort(message: &[u8]) -> ! {
+	let args = AbortArgs {
+		message_address: message.as_ptr() as u32,
+		message_length: message.len() as u32,
+	};
+	fuste_abi::ecall::<AbortAbi>(&args, &());
+	exit(ExitStatus::Error)
+}
+
+/// Aborts with where the program panicked and why, e.g.
+/// `panicked at src/main.rs:12: assertion failed`.
+pub fn abort_with_panic(info: &PanicInfo) -> ! {
+	let mut message = MessageBuffer::<PANIC_MESSAGE_CAPACITY>::new();
+	if let Some(location) = info.location() {
+		let _ = write!(message, "panicked at {}:{}: ", location.file(), location.line());
+	} else {
+		let _ = write!(message, "panicked: ");
+	}
+	let _ = write!(message, "{}", info.message());
+	abort(message.as_bytes())
+}
+
+/// A message formatted on the stack, cut short at a character boundary if it doesn't fit.
+pub struct MessageBuffer<const CAPACITY: usize> {
+	bytes: [u8; CAPACITY],
+	length: usize,
+}
+
+impl<const CAPACITY: usize> MessageBuffer<CAPACITY> {
+	pub const fn new() -> Self {
+		Self { bytes: [0; CAPACITY], length: 0 }
+	}
+
+	pub fn as_bytes(&self) -> &[u8] {
+		&self.bytes[..self.length]
+	}
+}
+
+impl<const CAPACITY: usize> Default for MessageBuffer<CAPACITY> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<const CAPACITY: usize> Write for MessageBuffer<CAPACITY> {
+	fn write_str(&mut self, s: &str) -> fmt::Result {
+		let mut length = s.len().min(CAPACITY - self.length);
+		while !s.is_char_boundary(length) {
+			length -= 1;
 		}
+		self.bytes[self.length..self.length + length].copy_from_slice(&s.as_bytes()[..length]);
+		self.length += length;
+		// The rest of the message is dropped rather than failing the formatting.
+		Ok(())
 	}
 }
 
@@ -73,12 +184,37 @@ pub mod tests {
 	use super::*;
 
 	#[test]
-	fn test_exit_status_try_from_u32() {
-		assert_eq!(ExitStatus::try_from_u32(0), Ok(ExitStatus::Success));
-		assert_eq!(ExitStatus::try_from_u32(1), Ok(ExitStatus::Error));
-		assert_eq!(ExitStatus::try_from_u32(2), Ok(ExitStatus::Terminated));
-		assert_eq!(ExitStatus::try_from_u32(3), Ok(ExitStatus::Unsupported));
-		assert_eq!(ExitStatus::try_from_u32(4), Err(ExitError::InvalidExitStatus(4)));
+	fn test_exit_status_from_u32() {
+		assert_eq!(ExitStatus::from_u32(0), ExitStatus::Success);
+		assert_eq!(ExitStatus::from_u32(1), ExitStatus::Error);
+		assert_eq!(ExitStatus::from_u32(2), ExitStatus::Terminated);
+		assert_eq!(ExitStatus::from_u32(3), ExitStatus::Unsupported);
+		assert_eq!(ExitStatus::from_u32(4), ExitStatus::Code(4));
+		assert_eq!(ExitStatus::from_u32(u32::MAX), ExitStatus::Code(u32::MAX));
+	}
+
+	#[test]
+	fn test_return_data_status_code_try_from_i32() {
+		assert_eq!(ReturnDataStatusCode::try_from_i32(0), Ok(ReturnDataStatusCode::Success));
+		assert_eq!(ReturnDataStatusCode::try_from_i32(-1), Ok(ReturnDataStatusCode::Ignored));
+		assert_eq!(ReturnDataStatusCode::try_from_i32(-2), Ok(ReturnDataStatusCode::TooLarge));
+		assert_eq!(
+			ReturnDataStatusCode::try_from_i32(1),
+			Err(ReturnDataError::InvalidStatusCode(1))
+		);
+	}
+
+	#[test]
+	fn test_message_buffer_truncates() {
+		let line = 12;
+		let mut message = MessageBuffer::<8>::new();
+		let _ = write!(message, "src/main.rs:{line}");
+		assert_eq!(message.as_bytes(), b"src/main");
+
+		// The 2 byte character doesn't fit in the last byte, so it's left out whole.
+		let mut message = MessageBuffer::<4>::new();
+		let _ = write!(message, "abcé");
+		assert_eq!(message.as_bytes(), b"abc");
 	}
 
 	#[test]
@@ -87,5 +223,6 @@ pub mod tests {
 		assert_eq!(ExitStatus::Error.to_u32(), 1);
 		assert_eq!(ExitStatus::Terminated.to_u32(), 2);
 		assert_eq!(ExitStatus::Unsupported.to_u32(), 3);
+		assert_eq!(ExitStatus::Code(42).to_u32(), 42);
 	}
 }
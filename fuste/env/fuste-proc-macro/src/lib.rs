@@ -1,10 +1,84 @@
+use fuste_ecall::Ecall;
+use fuste_manifest::Manifest;
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse_macro_input, ItemFn};
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::{parse_macro_input, Expr, ExprArray, ItemFn, Lit, MetaNameValue, Token};
+
+/// Parses an integer literal such as `8192` or `0x1d`.
+fn parse_u32(expr: &Expr) -> syn::Result<u32> {
+	match expr {
+		Expr::Lit(lit) => match &lit.lit {
+			Lit::Int(int) => int.base10_parse(),
+			_ => Err(syn::Error::new_spanned(expr, "expected an integer")),
+		},
+		_ => Err(syn::Error::new_spanned(expr, "expected an integer")),
+	}
+}
+
+/// Parses an ECALL given by name, e.g. `write`, or by number.
+fn parse_ecall(expr: &Expr) -> syn::Result<u32> {
+	let Expr::Path(path) = expr else {
+		return parse_u32(expr);
+	};
+
+	let ecall = match path.path.get_ident().map(|ident| ident.to_string()).as_deref() {
+		Some("exit") => Ecall::Exit,
+		Some("abort") => Ecall::Abort,
+		Some("return_data") => Ecall::ReturnData,
+		Some("read") => Ecall::Read,
+		Some("write") => Ecall::Write,
+		Some("open_channel") => Ecall::OpenChannel,
+		Some("check_channel") => Ecall::CheckChannel,
+		Some("wait_channel") => Ecall::WaitChannel,
+		Some("random") => Ecall::Random,
+		Some("time") => Ecall::Time,
+		_ => return Err(syn::Error::new_spanned(expr, "unknown ecall")),
+	};
+	Ok(ecall.to_u32())
+}
+
+fn parse_list(expr: &Expr, parse: fn(&Expr) -> syn::Result<u32>) -> syn::Result<Vec<u32>> {
+	match expr {
+		Expr::Array(ExprArray { elems, .. }) => elems.iter().map(parse).collect(),
+		_ => Err(syn::Error::new_spanned(expr, "expected a list, e.g. [1, 2]")),
+	}
+}
+
+/// Parses the requirements given to the attribute, e.g.
+/// `#[fuste::main(ecalls = [exit, write], write_systems = [1], stack_size = 8192)]`.
+fn parse_manifest(attr: proc_macro2::TokenStream) -> syn::Result<Manifest> {
+	let mut manifest = Manifest::default();
+	let arguments = Punctuated::<MetaNameValue, Token![,]>::parse_terminated.parse2(attr)?;
+
+	for argument in &arguments {
+		let value = &argument.value;
+		match argument.path.get_ident().map(|ident| ident.to_string()).as_deref() {
+			Some("memory_size") => manifest.memory_size = Some(parse_u32(value)?),
+			Some("stack_size") => manifest.stack_size = Some(parse_u32(value)?),
+			Some("ecalls") => manifest.ecalls = parse_list(value, parse_ecall)?,
+			Some("write_systems") => manifest.write_systems = parse_list(value, parse_u32)?,
+			Some("channel_systems") => manifest.channel_systems = parse_list(value, parse_u32)?,
+			_ => return Err(syn::Error::new_spanned(&argument.path, "unknown requirement")),
+		}
+	}
+
+	Ok(manifest)
+}
 
 /// Attribute macro: #[entry]
+///
+/// Also emits the program's `.fuste.manifest` section, listing the requirements given to the
+/// attribute for the host to check before running the program.
 #[proc_macro_attribute]
-pub fn main(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn main(attr: TokenStream, item: TokenStream) -> TokenStream {
+	let manifest = match parse_manifest(attr.into()) {
+		Ok(manifest) => manifest.to_bytes(),
+		Err(error) => return error.to_compile_error().into(),
+	};
+	let manifest_len = manifest.len();
+
 	// Parse the function the user wrote
 	let input_fn = parse_macro_input!(item as ItemFn);
 
@@ -17,34 +91,42 @@ pub fn main(_attr: TokenStream, item: TokenStream) -> TokenStream {
 	// Generate expanded code
 	let expanded = quote! {
 
-		// Startup entry point
-		#[no_mangle]
-		fn _start() -> ! {
-			unsafe {
-				::core::arch::asm!(
-					"la sp, {stack}",
-					stack = sym _stack_end
-				);
-			}
-			_main();
-		}
+		// Startup entry point, written in assembly so nothing touches the stack before it's set.
+		// Hosts passing arguments enter with sp at argc, others leave it zero.
+		::core::arch::global_asm!(
+			".section .text._start, \"ax\", @progbits",
+			".globl _start",
+			"_start:",
+			"mv a0, sp",
+			"bnez sp, 1f",
+			"la sp, {stack}",
+			"1:",
+			"tail {main}",
+			stack = sym _stack_end,
+			main = sym _main,
+		);
 
 		extern "C" {
 			static _stack_end: u32;
 		}
 
+		// Program manifest, read by the host before loading
+		#[used]
+		#[link_section = ".fuste.manifest"]
+		static __FUSTE_MANIFEST: [u8; #manifest_len] = [#(#manifest),*];
+
 		// Main trampoline
 		#[no_mangle]
 		#[inline(never)]
-		pub extern "C" fn _main() -> ! {
-			let _ = #fn_name();
-			fuste::exit(fuste::ExitStatus::Success);
+		pub extern "C" fn _main(initial_stack: *const usize) -> ! {
+			unsafe { fuste::arguments::init(initial_stack) };
+			fuste::exit(fuste::Termination::report(#fn_name()));
 		}
 
 		// Panic handler
 		#[panic_handler]
-		fn panic(_info: &::core::panic::PanicInfo) -> ! {
-			fuste::exit(fuste::ExitStatus::Error);
+		fn panic(info: &::core::panic::PanicInfo) -> ! {
+			fuste::abort_with_panic(info);
 		}
 
 		// Preserve original user function
@@ -55,3 +137,75 @@ pub fn main(_attr: TokenStream, item: TokenStream) -> TokenStream {
 
 	TokenStream::from(expanded)
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn parse(attr: &str) -> syn::Result<Manifest> {
+		parse_manifest(attr.parse()?)
+	}
+
+	fn error(attr: &str) -> Option<String> {
+		parse(attr).err().map(|error| error.to_string())
+	}
+
+	#[test]
+	fn test_parse_manifest() -> syn::Result<()> {
+		assert_eq!(parse("")?, Manifest::default());
+		assert_eq!(
+			parse(
+				"memory_size = 0x10000, stack_size = 8192, ecalls = [exit, write, 0x100], \
+				 write_systems = [1, 3], channel_systems = [0x1d]"
+			)?,
+			Manifest {
+				memory_size: Some(0x10000),
+				stack_size: Some(8192),
+				ecalls: vec![Ecall::Exit.to_u32(), Ecall::Write.to_u32(), 0x100],
+				write_systems: vec![1, 3],
+				channel_systems: vec![0x1d],
+				..Default::default()
+			}
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_parse_manifest_ecall_names() -> syn::Result<()> {
+		let manifest = parse(
+			"ecalls = [exit, abort, return_data, read, write, open_channel, check_channel, \
+			 wait_channel, random, time]",
+		)?;
+
+		assert_eq!(
+			manifest.ecalls,
+			[
+				Ecall::Exit,
+				Ecall::Abort,
+				Ecall::ReturnData,
+				Ecall::Read,
+				Ecall::Write,
+				Ecall::OpenChannel,
+				Ecall::CheckChannel,
+				Ecall::WaitChannel,
+				Ecall::Random,
+				Ecall::Time,
+			]
+			.map(|ecall| ecall.to_u32())
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_parse_manifest_errors() {
+		assert_eq!(error("heap_size = 8192"), Some("unknown requirement".to_string()));
+		assert_eq!(error("stack_size = \"8192\""), Some("expected an integer".to_string()));
+		assert_eq!(error("stack_size = [8192]"), Some("expected an integer".to_string()));
+		assert_eq!(error("ecalls = [exec]"), Some("unknown ecall".to_string()));
+		assert_eq!(error("write_systems = 1"), Some("expected a list, e.g. [1, 2]".to_string()));
+		assert!(error("stack_size = 0x1_0000_0000").is_some());
+		assert!(error("stack_size 8192").is_some());
+	}
+}
@@ -1,2 +1,3 @@
 pub use fuste_channel::ops::{block_on_channel, block_on_channel_request};
-pub use fuste_std_output::{self as output, print, println, Stdout};
+pub use fuste_std_input::{self as input, Stdin};
+pub use fuste_std_output::{self as output, eprint, eprintln, print, println, Stderr, Stdout};
@@ -1,15 +1,24 @@
 #![no_std]
 
+pub use fuste_args::{self as arguments, args, env, Args};
 pub use fuste_channel::{self as channels, ChannelError, ChannelStatus, ChannelSystemId};
 pub use fuste_ecall::{self as ecalls, Ecall, EcallError, EcallStatus};
-pub use fuste_exit::{self as exits, exit, ExitError, ExitStatus};
+pub use fuste_exit::{
+	self as exits, abort, abort_with_panic, exit, exit_code, return_data, ExitStatus,
+	ReturnDataError,
+};
+pub use fuste_random::{self as random, fill_bytes, random_u32, random_u64, RandomError};
+pub use fuste_read::{self as readers, read, ReadError, ReadStatus, ReadStatusCode, ReadSystemId};
 pub use fuste_serial_channel::Bytes;
 pub use fuste_std_signer_stores::{signer_index::SignerIndex, SignerStoreSystem};
 pub use fuste_std_transaction::signer::signer_at_index;
+pub use fuste_time::{self as time, ClockKind, TimeError};
 pub use fuste_write::{
 	self as writers, write, WriteError, WriteStatus, WriteStatusCode, WriteSystemId,
 };
 pub mod io;
-pub use io::{print, println};
+pub use io::{eprint, eprintln, print, println};
 pub mod prelude;
+pub mod termination;
 pub use fuste_proc_macro::main;
+pub use termination::Termination;
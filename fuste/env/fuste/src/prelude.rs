@@ -1,38 +1,58 @@
 // The macros will be expanded at the call site where these are available
 
+/// The `.fuste.manifest` section [entry] emits, declaring the ABI version and no requirements.
+///
+/// It's laid out as `fuste_manifest` describes, with `fuste_manifest::ABI_VERSION`.
+#[doc(hidden)]
+pub const EMPTY_MANIFEST: [u8; 28] = [
+	b'F', b'M', b'A', b'N', // magic
+	1, 0, 0, 0, // ABI version
+	0, 0, 0, 0, // memory size
+	0, 0, 0, 0, // stack size
+	0, 0, 0, 0, // ECALL count
+	0, 0, 0, 0, // write system count
+	0, 0, 0, 0, // channel system count
+];
+
 #[macro_export]
 macro_rules! entry {
 	(
 		$(#[$attrs:meta])*
 		$vis:vis fn $name:ident() -> $ret:ty $body:block
 	) => {
-		// Generate the startup code
-		#[no_mangle]
-		fn _start() -> ! {
-			unsafe {
-				// set stack pointer
-				::core::arch::asm!(
-					"la sp, {stack}",
-					stack = sym _stack_end
-				);
-			}
-			_main();
-		}
+		// Generate the startup code, see fuste::main
+		::core::arch::global_asm!(
+			".section .text._start, \"ax\", @progbits",
+			".globl _start",
+			"_start:",
+			"mv a0, sp",
+			"bnez sp, 1f",
+			"la sp, {stack}",
+			"1:",
+			"tail {main}",
+			stack = sym _stack_end,
+			main = sym _main,
+		);
 
 		extern "C" {
 			static _stack_end: u32;
 		}
 
+		// An empty program manifest, so the host still checks the ABI version
+		#[used]
+		#[link_section = ".fuste.manifest"]
+		static __FUSTE_MANIFEST: [u8; 28] = $crate::prelude::EMPTY_MANIFEST;
+
 		#[no_mangle]
 		#[inline(never)]
-		pub extern "C" fn _main() -> ! {
-			let _ = $name();
-			$crate::exit($crate::ExitStatus::Success);
+		pub extern "C" fn _main(initial_stack: *const usize) -> ! {
+			unsafe { $crate::arguments::init(initial_stack) };
+			$crate::exit($crate::Termination::report($name()));
 		}
 
 		#[panic_handler]
-		fn panic(_info: &::core::panic::PanicInfo) -> ! {
-			$crate::exit($crate::ExitStatus::Error);
+		fn panic(info: &::core::panic::PanicInfo) -> ! {
+			$crate::abort_with_panic(info);
 		}
 
 		// Preserve the original function
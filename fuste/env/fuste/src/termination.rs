@@ -0,0 +1,46 @@
+use crate::{eprintln, ExitStatus};
+use core::fmt::Debug;
+
+/// What a program's `main` can return, and the status it exits with for it.
+///
+/// Like `std::process::Termination`, an `Err` is written to stderr and exits with
+/// [ExitStatus::Error].
+pub trait Termination {
+	fn report(self) -> ExitStatus;
+}
+
+impl Termination for () {
+	fn report(self) -> ExitStatus {
+		ExitStatus::Success
+	}
+}
+
+impl Termination for ExitStatus {
+	fn report(self) -> ExitStatus {
+		self
+	}
+}
+
+impl<T: Termination, E: Debug> Termination for Result<T, E> {
+	fn report(self) -> ExitStatus {
+		match self {
+			Ok(value) => value.report(),
+			Err(error) => {
+				eprintln!("Error: {error:?}");
+				ExitStatus::Error
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+pub mod tests {
+	use super::*;
+
+	#[test]
+	fn test_report() {
+		assert_eq!(().report(), ExitStatus::Success);
+		assert_eq!(Ok::<_, ()>(ExitStatus::Code(42)).report(), ExitStatus::Code(42));
+		assert_eq!(Err::<(), _>("failed").report(), ExitStatus::Error);
+	}
+}
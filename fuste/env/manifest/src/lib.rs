@@ -0,0 +1,175 @@
+//! The `.fuste.manifest` section, in which a program declares what it needs from the host.
+//!
+//! The section holds little-endian 32-bit words:
+//!
+//! ```text
+//! magic "FMAN", ABI version, memory size, stack size,
+//! ECALL count, ECALLs..., write system count, write systems...,
+//! channel system count, channel systems...
+//! ```
+//!
+//! A size of zero means the program didn't declare one. It's written by the `fuste::main`
+//! macro at compile time and read by the host, so this crate is only ever built for the host.
+use std::fmt::{self, Display};
+
+/// The name of the ELF section holding the manifest.
+pub const SECTION_NAME: &str = ".fuste.manifest";
+
+/// The first four bytes of the manifest.
+pub const MAGIC: [u8; 4] = *b"FMAN";
+
+/// The version of the interface between programs and the host.
+///
+/// It's bumped whenever an ECALL changes in a way existing programs would notice.
+pub const ABI_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ManifestError {
+	InvalidMagic,
+	/// The manifest ends before the field with the given name.
+	Truncated(&'static str),
+	/// Bytes are left over after the last field.
+	TrailingBytes(usize),
+}
+
+impl Display for ManifestError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			ManifestError::InvalidMagic => write!(f, "Manifest doesn't start with FMAN"),
+			ManifestError::Truncated(field) => write!(f, "Manifest ends before its {field}"),
+			ManifestError::TrailingBytes(count) => {
+				write!(f, "Manifest has {count} unexpected trailing bytes")
+			}
+		}
+	}
+}
+
+impl std::error::Error for ManifestError {}
+
+/// The requirements a program declares.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Manifest {
+	pub abi_version: u32,
+	/// The memory the program needs, in bytes.
+	pub memory_size: Option<u32>,
+	/// The stack the program needs, in bytes.
+	pub stack_size: Option<u32>,
+	/// The ECALL numbers the program makes, as passed in a7.
+	pub ecalls: Vec<u32>,
+	/// The write system IDs the program writes to.
+	pub write_systems: Vec<u32>,
+	/// The channel system IDs the program opens channels to.
+	pub channel_systems: Vec<u32>,
+}
+
+impl Default for Manifest {
+	fn default() -> Self {
+		Self {
+			abi_version: ABI_VERSION,
+			memory_size: None,
+			stack_size: None,
+			ecalls: Vec::new(),
+			write_systems: Vec::new(),
+			channel_systems: Vec::new(),
+		}
+	}
+}
+
+/// Reads the little-endian words of a manifest in order.
+struct Words<'a> {
+	bytes: &'a [u8],
+}
+
+impl Words<'_> {
+	fn next(&mut self, field: &'static str) -> Result<u32, ManifestError> {
+		let Some((word, rest)) = self.bytes.split_first_chunk::<4>() else {
+			return Err(ManifestError::Truncated(field));
+		};
+		self.bytes = rest;
+		Ok(u32::from_le_bytes(*word))
+	}
+
+	fn list(&mut self, field: &'static str) -> Result<Vec<u32>, ManifestError> {
+		let count = self.next(field)?;
+		(0..count).map(|_| self.next(field)).collect()
+	}
+}
+
+impl Manifest {
+	/// Encodes the manifest as the contents of its section.
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let mut words =
+			vec![self.abi_version, self.memory_size.unwrap_or(0), self.stack_size.unwrap_or(0)];
+		for list in [&self.ecalls, &self.write_systems, &self.channel_systems] {
+			words.push(list.len() as u32);
+			words.extend(list);
+		}
+
+		let mut bytes = MAGIC.to_vec();
+		bytes.extend(words.iter().flat_map(|word| word.to_le_bytes()));
+		bytes
+	}
+
+	/// Decodes the contents of a manifest section.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, ManifestError> {
+		let bytes = bytes.strip_prefix(&MAGIC).ok_or(ManifestError::InvalidMagic)?;
+		let mut words = Words { bytes };
+
+		let manifest = Self {
+			abi_version: words.next("ABI version")?,
+			memory_size: Some(words.next("memory size")?).filter(|size| *size != 0),
+			stack_size: Some(words.next("stack size")?).filter(|size| *size != 0),
+			ecalls: words.list("ECALLs")?,
+			write_systems: words.list("write systems")?,
+			channel_systems: words.list("channel systems")?,
+		};
+
+		if !words.bytes.is_empty() {
+			return Err(ManifestError::TrailingBytes(words.bytes.len()));
+		}
+
+		Ok(manifest)
+	}
+}
+
+#[cfg(test)]
+pub mod tests {
+	use super::*;
+
+	#[test]
+	fn test_round_trip() {
+		let manifest = Manifest {
+			memory_size: Some(0x10_0000),
+			ecalls: vec![93, 64, 33, 34],
+			write_systems: vec![1],
+			channel_systems: vec![0x1d, 0x516d],
+			..Default::default()
+		};
+
+		assert_eq!(Manifest::from_bytes(&manifest.to_bytes()), Ok(manifest));
+	}
+
+	#[test]
+	fn test_default_is_empty() {
+		let bytes = Manifest::default().to_bytes();
+		assert_eq!(bytes.len(), 4 + 6 * 4);
+		assert_eq!(Manifest::from_bytes(&bytes), Ok(Manifest::default()));
+		// The `fuste::entry!` macro emits these bytes as they are.
+		assert_eq!(bytes, b"FMAN\x01\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0");
+	}
+
+	#[test]
+	fn test_malformed() {
+		let bytes = Manifest { ecalls: vec![93], ..Default::default() }.to_bytes();
+
+		assert_eq!(Manifest::from_bytes(b"ELF!"), Err(ManifestError::InvalidMagic));
+		assert_eq!(
+			Manifest::from_bytes(&bytes[..bytes.len() - 4]),
+			Err(ManifestError::Truncated("channel systems"))
+		);
+		assert_eq!(
+			Manifest::from_bytes(&[bytes.as_slice(), &[0]].concat()),
+			Err(ManifestError::TrailingBytes(1))
+		);
+	}
+}
@@ -0,0 +1,39 @@
+//! Serves `getrandom` with the random ECALL, so crates built on it work on the `fuste` target.
+//!
+//! Enabling the `getrandom` feature registers [getrandom_custom] as `getrandom`'s custom backend.
+use crate::{fill_bytes, RandomError};
+use core::num::NonZeroU32;
+use getrandom::Error;
+
+#[cfg(target_family = "fuste")]
+getrandom::register_custom_getrandom!(getrandom_custom);
+
+/// Fills the buffer as [fill_bytes] does, reporting errors as `getrandom` does.
+pub fn getrandom_custom(buffer: &mut [u8]) -> Result<(), Error> {
+	fill_bytes(buffer).map_err(|error| getrandom_error(&error))
+}
+
+/// The [RandomError] as an [Error], with a code from the range `getrandom` keeps for custom
+/// backends.
+pub fn getrandom_error(error: &RandomError) -> Error {
+	let offset = match error {
+		RandomError::NotImplemented => return Error::UNSUPPORTED,
+		RandomError::Ignored => 1,
+		RandomError::Failure => 2,
+		RandomError::SystemError(_) => 3,
+		RandomError::InvalidStatusCode(_) => 4,
+	};
+	NonZeroU32::new(Error::CUSTOM_START + offset).map_or(Error::UNSUPPORTED, Error::from)
+}
+
+#[cfg(test)]
+pub mod tests {
+	use super::*;
+
+	#[test]
+	fn test_getrandom_error() {
+		assert_eq!(getrandom_error(&RandomError::NotImplemented), Error::UNSUPPORTED);
+		assert_eq!(getrandom_error(&RandomError::Ignored).code().get(), Error::CUSTOM_START + 1);
+		assert_eq!(getrandom_custom(&mut [0; 4]), Err(Error::UNSUPPORTED));
+	}
+}
@@ -0,0 +1,122 @@
+#![no_std]
+#![allow(unexpected_cfgs)]
+
+#[cfg(feature = "getrandom")]
+pub mod getrandom_backend;
+
+use core::fmt::{self, Display};
+#[cfg(target_family = "fuste")]
+use fuste_abi::random::{RandomAbi, RandomArgs, RandomReturns};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RandomStatusCode {
+	SystemError = -3,
+	Failure = -2,
+	Ignored = -1,
+	Success = 0,
+}
+
+impl Display for RandomStatusCode {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "Random status code: {}", self.clone().to_i32())
+	}
+}
+
+impl RandomStatusCode {
+	pub fn to_i32(self) -> i32 {
+		self as i32
+	}
+
+	pub fn try_from_i32(value: i32) -> Result<Self, RandomError> {
+		match value {
+			-3 => Ok(RandomStatusCode::SystemError),
+			-2 => Ok(RandomStatusCode::Failure),
+			-1 => Ok(RandomStatusCode::Ignored),
+			0 => Ok(RandomStatusCode::Success),
+			_ => Err(RandomError::InvalidStatusCode(value)),
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RandomError {
+	/// The host's source of randomness failed with its own status.
+	SystemError(i32),
+	Failure,
+	Ignored,
+	InvalidStatusCode(i32),
+	NotImplemented,
+}
+
+impl Display for RandomError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			RandomError::SystemError(status) => write!(f, "System error: {status}"),
+			RandomError::Failure => write!(f, "Failure"),
+			RandomError::Ignored => write!(f, "Ignored"),
+			RandomError::InvalidStatusCode(code) => write!(f, "Invalid status code: {code}"),
+			RandomError::NotImplemented => write!(f, "Not implemented"),
+		}
+	}
+}
+
+/// Fills the buffer with random bytes from the host.
+///
+/// Whether the bytes are reproducible is up to the host, e.g. a seeded stream when every run has
+/// to agree on them.
+pub fn fill_bytes(buffer: &mut [u8]) -> Result<(), RandomError> {
+	#[cfg(target_family = "fuste")]
+	{
+		let args = RandomArgs {
+			buffer_address: buffer.as_mut_ptr() as u32,
+			buffer_length: buffer.len() as u32,
+		};
+		// If this isn't reset, the system must have ignored the call.
+		let unserved =
+			RandomReturns { status: RandomStatusCode::Ignored.to_i32(), system_status: 0 };
+		let returns = fuste_abi::ecall::<RandomAbi>(&args, &unserved);
+
+		match RandomStatusCode::try_from_i32(returns.status)? {
+			RandomStatusCode::Success => Ok(()),
+			RandomStatusCode::SystemError => Err(RandomError::SystemError(returns.system_status)),
+			RandomStatusCode::Failure => Err(RandomError::Failure),
+			RandomStatusCode::Ignored => Err(RandomError::Ignored),
+		}
+	}
+
+	#[cfg(not(target_family = "fuste"))]
+	{
+		let _ = buffer;
+		Err(RandomError::NotImplemented)
+	}
+}
+
+pub fn random_u32() -> Result<u32, RandomError> {
+	let mut bytes = [0; 4];
+	fill_bytes(&mut bytes)?;
+	Ok(u32::from_le_bytes(bytes))
+}
+
+pub fn random_u64() -> Result<u64, RandomError> {
+	let mut bytes = [0; 8];
+	fill_bytes(&mut bytes)?;
+	Ok(u64::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+pub mod tests {
+	use super::*;
+
+	#[test]
+	fn test_random_status_code_try_from_i32() {
+		assert_eq!(RandomStatusCode::try_from_i32(0), Ok(RandomStatusCode::Success));
+		assert_eq!(RandomStatusCode::try_from_i32(-1), Ok(RandomStatusCode::Ignored));
+		assert_eq!(RandomStatusCode::try_from_i32(-3), Ok(RandomStatusCode::SystemError));
+		assert_eq!(RandomStatusCode::try_from_i32(-4), Err(RandomError::InvalidStatusCode(-4)));
+	}
+
+	#[test]
+	fn test_fill_bytes_off_target() {
+		assert_eq!(fill_bytes(&mut [0; 4]), Err(RandomError::NotImplemented));
+	}
+}
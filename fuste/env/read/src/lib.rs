@@ -0,0 +1,213 @@
+#![no_std]
+#![allow(unexpected_cfgs)]
+
+use core::fmt::{self, Display};
+#[cfg(target_family = "fuste")]
+use fuste_abi::read::{ReadAbi, ReadArgs, ReadReturns};
+
+/// The system ID is a 32-bit value that identifies the system to read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadSystemId(u32);
+
+impl ReadSystemId {
+	pub const fn constant(value: u32) -> Self {
+		Self(value)
+	}
+
+	pub fn new(value: u32) -> Self {
+		Self(value)
+	}
+
+	pub fn to_u32(self) -> u32 {
+		self.0
+	}
+
+	pub const fn to_const_u32(self) -> u32 {
+		self.0
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReadStatusCode {
+	SystemError = -4,
+	InvalidSystem = -3,
+	Failure = -2,
+	Ignored = -1,
+	Success = 0,
+}
+
+impl Display for ReadStatusCode {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "Read status code: {}", self.clone().to_i32())
+	}
+}
+
+impl ReadStatusCode {
+	pub fn to_i32(self) -> i32 {
+		self as i32
+	}
+
+	pub fn try_from_i32(value: i32) -> Result<Self, ReadError> {
+		match value {
+			-4 => Ok(ReadStatusCode::SystemError),
+			-3 => Ok(ReadStatusCode::InvalidSystem),
+			-2 => Ok(ReadStatusCode::Failure),
+			-1 => Ok(ReadStatusCode::Ignored),
+			0 => Ok(ReadStatusCode::Success),
+			_ => Err(ReadError::InvalidStatusCode(value)),
+		}
+	}
+
+	pub fn is_success(self) -> bool {
+		self == ReadStatusCode::Success
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadSystemStatus(i32);
+
+impl Display for ReadSystemStatus {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "Read system status: {}", self.clone().to_i32())
+	}
+}
+
+impl ReadSystemStatus {
+	pub fn new(value: i32) -> Self {
+		Self(value)
+	}
+
+	pub fn to_i32(self) -> i32 {
+		self.0
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadStatus {
+	size: u32,
+	code: ReadStatusCode,
+	system_status: ReadSystemStatus,
+}
+
+impl ReadStatus {
+	pub fn new(size: u32, code: ReadStatusCode, system_status: ReadSystemStatus) -> Self {
+		Self { size, code, system_status }
+	}
+
+	pub fn try_from_words(size: u32, code: i32, system_status: i32) -> Result<Self, ReadError> {
+		Ok(Self {
+			size,
+			code: ReadStatusCode::try_from_i32(code)?,
+			system_status: ReadSystemStatus::new(system_status),
+		})
+	}
+
+	/// The number of bytes read.
+	pub fn size(&self) -> u32 {
+		self.size
+	}
+
+	pub fn is_success(self) -> bool {
+		self.code.is_success()
+	}
+
+	pub fn ok(self) -> Result<ReadStatus, ReadError> {
+		match self.code {
+			ReadStatusCode::Success => Ok(self),
+			ReadStatusCode::SystemError => Err(ReadError::SystemError(self)),
+			ReadStatusCode::InvalidSystem => Err(ReadError::InvalidSystem(self)),
+			ReadStatusCode::Failure => Err(ReadError::Failure(self)),
+			ReadStatusCode::Ignored => Err(ReadError::Ignored(self)),
+		}
+	}
+}
+
+impl Display for ReadStatus {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"Read status: size={}, code={}, system_status={}",
+			self.size, self.code, self.system_status
+		)
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReadError {
+	SystemError(ReadStatus),
+	InvalidSystem(ReadStatus),
+	Failure(ReadStatus),
+	Ignored(ReadStatus),
+	InvalidStatusCode(i32),
+	/// The system reported reading more than the buffer holds.
+	BufferOverrun(ReadStatus),
+	NotImplemented,
+}
+
+impl Display for ReadError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			ReadError::SystemError(status) => write!(f, "System error: {status}"),
+			ReadError::InvalidSystem(status) => write!(f, "Invalid system: {status}"),
+			ReadError::Failure(status) => write!(f, "Failure: {status}"),
+			ReadError::Ignored(status) => write!(f, "Ignored: {status}"),
+			ReadError::InvalidStatusCode(code) => write!(f, "Invalid status code: {code}"),
+			ReadError::BufferOverrun(status) => write!(f, "Buffer overrun: {status}"),
+			ReadError::NotImplemented => write!(f, "Not implemented"),
+		}
+	}
+}
+
+/// Reads from the system into the buffer, returning the number of bytes read.
+///
+/// Reading 0 bytes into a nonempty buffer means the system has no more input.
+pub fn read(system_id: ReadSystemId, buffer: &mut [u8]) -> Result<usize, ReadError> {
+	#[cfg(target_family = "fuste")]
+	{
+		let args = ReadArgs {
+			system_id: system_id.to_u32(),
+			buffer_address: buffer.as_mut_ptr() as u32,
+			buffer_length: buffer.len() as u32,
+		};
+		// If this isn't reset, the system must have ignored the call.
+		let unserved =
+			ReadReturns { status: ReadStatusCode::Ignored.to_i32(), system_status: 0, size: 0 };
+		let returns = fuste_abi::ecall::<ReadAbi>(&args, &unserved);
+
+		let read_status =
+			ReadStatus::try_from_words(returns.size, returns.status, returns.system_status)?
+				.ok()?;
+		if read_status.size() as usize > buffer.len() {
+			return Err(ReadError::BufferOverrun(read_status));
+		}
+		Ok(read_status.size() as usize)
+	}
+
+	#[cfg(not(target_family = "fuste"))]
+	{
+		let _ = (system_id, buffer);
+		Err(ReadError::NotImplemented)
+	}
+}
+
+#[cfg(test)]
+pub mod tests {
+	use super::*;
+
+	#[test]
+	fn test_read_status_code_try_from_i32() {
+		assert_eq!(ReadStatusCode::try_from_i32(0), Ok(ReadStatusCode::Success));
+		assert_eq!(ReadStatusCode::try_from_i32(-1), Ok(ReadStatusCode::Ignored));
+		assert_eq!(ReadStatusCode::try_from_i32(-4), Ok(ReadStatusCode::SystemError));
+		assert_eq!(ReadStatusCode::try_from_i32(1), Err(ReadError::InvalidStatusCode(1)));
+	}
+
+	#[test]
+	fn test_read_status_ok() {
+		let status = ReadStatus::try_from_words(5, 0, 0);
+		assert_eq!(status.and_then(ReadStatus::ok).map(|status| status.size()), Ok(5));
+
+		let ignored = ReadStatus::new(0, ReadStatusCode::Ignored, ReadSystemStatus::new(0));
+		assert_eq!(ignored.clone().ok(), Err(ReadError::Ignored(ignored)));
+	}
+}
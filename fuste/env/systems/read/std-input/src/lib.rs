@@ -0,0 +1,31 @@
+#![no_std]
+
+use fuste_read::{read, ReadError, ReadSystemId};
+pub struct Stdin;
+
+impl Stdin {
+	pub const SYSTEM_ID: ReadSystemId = ReadSystemId::constant(0);
+
+	pub const fn to_const_u32() -> u32 {
+		Self::SYSTEM_ID.to_const_u32()
+	}
+
+	/// Reads the next of the program's input into the buffer, returning the number of bytes read.
+	///
+	/// Reading 0 bytes into a nonempty buffer means the input has ended.
+	pub fn read(&mut self, buffer: &mut [u8]) -> Result<usize, ReadError> {
+		read(Self::SYSTEM_ID, buffer)
+	}
+
+	/// Reads until the buffer is full or the input ends, returning the number of bytes read.
+	pub fn read_to_fill(&mut self, buffer: &mut [u8]) -> Result<usize, ReadError> {
+		let mut filled = 0;
+		while filled < buffer.len() {
+			match self.read(&mut buffer[filled..])? {
+				0 => break,
+				size => filled += size,
+			}
+		}
+		Ok(filled)
+	}
+}
@@ -18,6 +18,22 @@ impl Write for Stdout {
 	}
 }
 
+pub struct Stderr;
+
+impl Stderr {
+	pub const SYSTEM_ID: WriteSystemId = WriteSystemId::constant(2);
+
+	pub const fn to_const_u32() -> u32 {
+		Self::SYSTEM_ID.to_const_u32()
+	}
+}
+
+impl Write for Stderr {
+	fn write_str(&mut self, s: &str) -> fmt::Result {
+		write(Self::SYSTEM_ID, s.as_bytes()).map(|_| ()).map_err(|_| fmt::Error)
+	}
+}
+
 #[macro_export]
 macro_rules! print {
     ($($arg:tt)*) => ({
@@ -36,3 +52,22 @@ macro_rules! println {
         $crate::print!("\n");
     });
 }
+
+#[macro_export]
+macro_rules! eprint {
+    ($($arg:tt)*) => ({
+        use core::fmt::Write;
+        let _ = core::write!($crate::Stderr, $($arg)*);
+    });
+}
+
+#[macro_export]
+macro_rules! eprintln {
+    () => ({
+        $crate::eprint!("\n");
+    });
+    ($($arg:tt)*) => ({
+        $crate::eprint!($($arg)*);
+        $crate::eprint!("\n");
+    });
+}
@@ -0,0 +1,181 @@
+#![no_std]
+#![allow(unexpected_cfgs)]
+
+use core::fmt::{self, Display};
+use core::time::Duration;
+#[cfg(target_family = "fuste")]
+use fuste_abi::time::{TimeAbi, TimeReturns};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimeStatusCode {
+	SystemError = -2,
+	Ignored = -1,
+	Success = 0,
+}
+
+impl Display for TimeStatusCode {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "Time status code: {}", self.clone().to_i32())
+	}
+}
+
+impl TimeStatusCode {
+	pub fn to_i32(self) -> i32 {
+		self as i32
+	}
+
+	pub fn try_from_i32(value: i32) -> Result<Self, TimeError> {
+		match value {
+			-2 => Ok(TimeStatusCode::SystemError),
+			-1 => Ok(TimeStatusCode::Ignored),
+			0 => Ok(TimeStatusCode::Success),
+			_ => Err(TimeError::InvalidStatusCode(value)),
+		}
+	}
+}
+
+/// The kind of clock the host reads the time from.
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockKind {
+	/// The host's wall clock, since the Unix epoch
+	Wall = 0,
+	/// A time which doesn't pass, e.g. the timestamp of the transaction the program runs in
+	Fixed = 1,
+	/// A time which passes as the program executes instructions
+	Virtual = 2,
+}
+
+impl ClockKind {
+	pub fn to_u32(self) -> u32 {
+		self as u32
+	}
+
+	pub fn try_from_u32(value: u32) -> Result<Self, TimeError> {
+		match value {
+			0 => Ok(ClockKind::Wall),
+			1 => Ok(ClockKind::Fixed),
+			2 => Ok(ClockKind::Virtual),
+			_ => Err(TimeError::InvalidClock(value)),
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimeError {
+	SystemError,
+	Ignored,
+	InvalidStatusCode(i32),
+	InvalidClock(u32),
+	/// Time doesn't pass under a [ClockKind::Fixed] clock, so waiting on it would never end.
+	FixedClock,
+	NotImplemented,
+}
+
+impl Display for TimeError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			TimeError::SystemError => write!(f, "System error"),
+			TimeError::Ignored => write!(f, "Ignored"),
+			TimeError::InvalidStatusCode(code) => write!(f, "Invalid status code: {code}"),
+			TimeError::InvalidClock(clock) => write!(f, "Invalid clock: {clock}"),
+			TimeError::FixedClock => write!(f, "The clock is fixed"),
+			TimeError::NotImplemented => write!(f, "Not implemented"),
+		}
+	}
+}
+
+/// A reading of the host's clock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Time {
+	since_epoch: Duration,
+	clock: ClockKind,
+}
+
+impl Time {
+	pub fn new(since_epoch: Duration, clock: ClockKind) -> Self {
+		Self { since_epoch, clock }
+	}
+
+	pub fn try_from_words(status: i32, nanos: u64, clock: u32) -> Result<Self, TimeError> {
+		match TimeStatusCode::try_from_i32(status)? {
+			TimeStatusCode::Success => {
+				Ok(Self::new(Duration::from_nanos(nanos), ClockKind::try_from_u32(clock)?))
+			}
+			TimeStatusCode::SystemError => Err(TimeError::SystemError),
+			TimeStatusCode::Ignored => Err(TimeError::Ignored),
+		}
+	}
+
+	/// The time since the clock's epoch.
+	pub fn since_epoch(&self) -> Duration {
+		self.since_epoch
+	}
+
+	pub fn clock(&self) -> ClockKind {
+		self.clock
+	}
+}
+
+/// Reads the host's clock.
+pub fn read_clock() -> Result<Time, TimeError> {
+	#[cfg(target_family = "fuste")]
+	{
+		// If this isn't reset, the system must have ignored the call.
+		let unserved = TimeReturns { status: TimeStatusCode::Ignored.to_i32(), nanos: 0, clock: 0 };
+		let returns = fuste_abi::ecall::<TimeAbi>(&(), &unserved);
+		Time::try_from_words(returns.status, returns.nanos, returns.clock)
+	}
+
+	#[cfg(not(target_family = "fuste"))]
+	{
+		Err(TimeError::NotImplemented)
+	}
+}
+
+/// The time since the host's clock's epoch.
+pub fn now() -> Result<Duration, TimeError> {
+	read_clock().map(|time| time.since_epoch())
+}
+
+/// Waits until the duration has passed on the host's clock, reading it until it has.
+///
+/// Waiting on a [ClockKind::Fixed] clock returns [TimeError::FixedClock] rather than never
+/// returning.
+pub fn busy_wait(duration: Duration) -> Result<(), TimeError> {
+	let start = read_clock()?;
+	if duration.is_zero() {
+		return Ok(());
+	}
+	if start.clock() == ClockKind::Fixed {
+		return Err(TimeError::FixedClock);
+	}
+
+	// A wall clock can be set back, which counts as no time passing.
+	while now()?.saturating_sub(start.since_epoch()) < duration {
+		core::hint::spin_loop();
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+pub mod tests {
+	use super::*;
+
+	#[test]
+	fn test_time_try_from_words() {
+		assert_eq!(
+			Time::try_from_words(0, 1_500, 2),
+			Ok(Time::new(Duration::from_nanos(1_500), ClockKind::Virtual))
+		);
+		assert_eq!(Time::try_from_words(-1, 0, 0), Err(TimeError::Ignored));
+		assert_eq!(Time::try_from_words(0, 0, 3), Err(TimeError::InvalidClock(3)));
+		assert_eq!(Time::try_from_words(1, 0, 0), Err(TimeError::InvalidStatusCode(1)));
+	}
+
+	#[test]
+	fn test_now_off_target() {
+		assert_eq!(now(), Err(TimeError::NotImplemented));
+		assert_eq!(busy_wait(Duration::from_millis(1)), Err(TimeError::NotImplemented));
+	}
+}
@@ -2,7 +2,8 @@
 #![allow(unexpected_cfgs)]
 
 use core::fmt::{self, Display};
-use fuste_ecall::Ecall;
+#[cfg(target_family = "fuste")]
+use fuste_abi::write::{WriteAbi, WriteArgs, WriteReturns};
 
 /// The system ID is a 16-bit value that identifies the system to write to.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -133,45 +134,35 @@ pub enum WriteError {
 impl Display for WriteError {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		match self {
-			WriteError::SystemError(status) => write!(f, "System error: {}", status),
-			WriteError::InvalidSystem(status) => write!(f, "Invalid system: {}", status),
-			WriteError::Failure(status) => write!(f, "Failure: {}", status),
-			WriteError::Ignored(status) => write!(f, "Ignored: {}", status),
-			WriteError::InvalidStatusCode(code) => write!(f, "Invalid status code: {}", code),
+			WriteError::SystemError(status) => write!(f, "System error: {status}"),
+			WriteError::InvalidSystem(status) => write!(f, "Invalid system: {status}"),
+			WriteError::Failure(status) => write!(f, "Failure: {status}"),
+			WriteError::Ignored(status) => write!(f, "Ignored: {status}"),
+			WriteError::InvalidStatusCode(code) => write!(f, "Invalid status code: {code}"),
 			WriteError::NotImplemented => write!(f, "Not implemented"),
 		}
 	}
 }
 
-#[inline(never)]
-pub fn write(system_id: WriteSystemId, _buffer: &[u8]) -> Result<WriteStatus, WriteError> {
-	let _ecall = Ecall::Write.to_u32();
-	let _system_id = system_id.to_u32();
-	let _status: i32;
-	let _system_status: i32;
-	let _status_ignored = WriteStatusCode::Ignored.to_i32();
-
+pub fn write(system_id: WriteSystemId, buffer: &[u8]) -> Result<WriteStatus, WriteError> {
 	#[cfg(target_family = "fuste")]
 	{
-		unsafe {
-			core::arch::asm!(
-				"ecall",
-				in("a7") _ecall,                   // syscall number for write
-				in("a0") _system_id,            // file descriptor
-				in("a1") _buffer.as_ptr(),      // pointer to buffer
-				in("a2") _buffer.len(),         // length
-				in("a3") _status_ignored,       // if this isn't reset, the system must have ignored the call
-				lateout("a3") _status,           // the status of the operation
-				lateout("a4") _system_status,    // the system status of the operation
-			);
-
-			let write_status = WriteStatus::try_from_i32s(_status, _system_status)?;
-			write_status.ok()
-		}
+		let args = WriteArgs {
+			system_id: system_id.to_u32(),
+			buffer_address: buffer.as_ptr() as u32,
+			buffer_length: buffer.len() as u32,
+		};
+		// If this isn't reset, the system must have ignored the call.
+		let unserved = WriteReturns { status: WriteStatusCode::Ignored.to_i32(), system_status: 0 };
+		let returns = fuste_abi::ecall::<WriteAbi>(&args, &unserved);
+
+		let write_status = WriteStatus::try_from_i32s(returns.status, returns.system_status)?;
+		write_status.ok()
 	}
 
 	#[cfg(not(target_family = "fuste"))]
 	{
+		let _ = (system_id, buffer);
 		Err(WriteError::NotImplemented)
 	}
 }
@@ -0,0 +1,121 @@
+/*
+ * The C API of fuste-ffi, for running fuste programs in hosts which aren't written in Rust.
+ *
+ * Link against the fuste_ffi library built from fuste/ffi. The declarations mirror
+ * fuste/ffi/src/lib.rs, which documents each function in full.
+ */
+
+#ifndef FUSTE_H
+#define FUSTE_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* The memory of the machines created through the C API, in bytes. */
+#define FUSTE_MEMORY_SIZE (1024 * 1024 * 2)
+
+/* Returned by an ECALL callback to resume the program after the ECALL. */
+#define FUSTE_ECALL_CONTINUE 0
+/* Returned by an ECALL callback to exit the program with the status in a0. */
+#define FUSTE_ECALL_EXIT 1
+/* Returned by an ECALL callback to fault the machine. */
+#define FUSTE_ECALL_FAULT 2
+
+/* Left in a0 for the program by ECALLs without a callback, other than exit. */
+#define FUSTE_ECALL_NOT_IMPLEMENTED 2
+
+/* Whether a call through the C API succeeded. */
+typedef enum FusteStatus {
+	FUSTE_STATUS_OK = 0,
+	/* A pointer argument was null. */
+	FUSTE_STATUS_NULL_POINTER = 1,
+	/* The ELF file couldn't be loaded. */
+	FUSTE_STATUS_LOAD_FAILED = 2,
+	/* The memory access was outside of the machine's memory. */
+	FUSTE_STATUS_OUT_OF_BOUNDS = 3,
+	/* The register index was 32 or more. */
+	FUSTE_STATUS_INVALID_REGISTER = 4,
+	/* The loaded program has no symbol with the name, or no program was loaded. */
+	FUSTE_STATUS_SYMBOL_NOT_FOUND = 5,
+	/* A string argument wasn't valid UTF-8. */
+	FUSTE_STATUS_INVALID_STRING = 6,
+} FusteStatus;
+
+/* How a run came to an end. */
+typedef enum FusteTermination {
+	FUSTE_TERMINATION_EXITED = 0,
+	FUSTE_TERMINATION_TICKS_EXHAUSTED = 1,
+	FUSTE_TERMINATION_FAULT = 2,
+} FusteTermination;
+
+/* How a run ended and where it left the machine. */
+typedef struct FusteRunOutcome {
+	FusteTermination termination;
+	/* The status the program exited with, if it exited. */
+	uint32_t exit_status;
+	/* The number of ticks executed. */
+	uint32_t ticks;
+	/* The program counter when the run ended. */
+	uint32_t program_counter;
+} FusteRunOutcome;
+
+/* A machine, the callbacks serving its ECALLs and the program loaded into it. */
+typedef struct FusteRuntime FusteRuntime;
+
+/* The machine a runtime runs, lent to ECALL callbacks to read and write its registers and memory. */
+typedef struct FusteMachine FusteMachine;
+
+/* Serves an ECALL, returning one of the FUSTE_ECALL_* results. */
+typedef uint32_t (*FusteEcallCallback)(void *user_data, FusteMachine *machine);
+
+FusteRuntime *fuste_runtime_new(void);
+
+void fuste_runtime_free(FusteRuntime *runtime);
+
+FusteStatus fuste_runtime_load_elf(FusteRuntime *runtime, const uint8_t *bytes, size_t length);
+
+FusteStatus fuste_runtime_register_ecall(
+	FusteRuntime *runtime,
+	uint32_t ecall,
+	FusteEcallCallback callback,
+	void *user_data
+);
+
+/* A max_ticks of 0 runs without a limit. */
+FusteStatus fuste_runtime_run(FusteRuntime *runtime, uint32_t max_ticks, FusteRunOutcome *outcome);
+
+FusteMachine *fuste_runtime_machine(FusteRuntime *runtime);
+
+FusteStatus fuste_runtime_symbol(FusteRuntime *runtime, const char *name, uint32_t *address);
+
+const char *fuste_runtime_last_error(const FusteRuntime *runtime);
+
+FusteStatus fuste_machine_register(const FusteMachine *machine, uint32_t index, uint32_t *value);
+
+FusteStatus fuste_machine_set_register(FusteMachine *machine, uint32_t index, uint32_t value);
+
+uint32_t fuste_machine_program_counter(const FusteMachine *machine);
+
+FusteStatus fuste_machine_read_memory(
+	const FusteMachine *machine,
+	uint32_t address,
+	uint8_t *out,
+	size_t length
+);
+
+FusteStatus fuste_machine_write_memory(
+	FusteMachine *machine,
+	uint32_t address,
+	const uint8_t *bytes,
+	size_t length
+);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* FUSTE_H */
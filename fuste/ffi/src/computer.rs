@@ -0,0 +1,154 @@
+use crate::{FusteMachine, FUSTE_ECALL_CONTINUE, FUSTE_ECALL_EXIT, MEMORY_SIZE};
+use core::ffi::c_void;
+use fuste_ecall::{Ecall, EcallStatus};
+use fuste_ecall_dispatcher::{ecall_number, resume_with_status};
+use fuste_exit::ExitStatus;
+use fuste_exit_system::ExitSystem;
+use fuste_interrupt_handler::{EcallDispatcherOps, InterruptHandler, NoopEbreakDispatcher};
+use fuste_riscv_core::{
+	instructions::EcallInterrupt,
+	machine::{Machine, MachineError, MachineSystem},
+	plugins::rv32i_computer::Rv32iComputer,
+};
+use fuste_runtime::RuntimeComputer;
+use std::collections::HashMap;
+use std::ops::ControlFlow;
+
+/// An ECALL handler registered by the host.
+///
+/// The handler gets the user data it was registered with and the machine, and returns one of the
+/// `FUSTE_ECALL_*` results.
+pub type FusteEcallCallback =
+	Option<unsafe extern "C" fn(user_data: *mut c_void, machine: *mut FusteMachine) -> u32>;
+
+struct EcallCallback {
+	callback: unsafe extern "C" fn(*mut c_void, *mut FusteMachine) -> u32,
+	user_data: *mut c_void,
+}
+
+/// Dispatches ECALLs to the callbacks registered for their number in a7.
+///
+/// The exit ECALL is served by an [ExitSystem] unless a callback is registered for it, and any
+/// other ECALL without a callback returns [EcallStatus::NotImplemented] to the program.
+pub struct CallbackDispatcher {
+	callbacks: HashMap<u32, EcallCallback>,
+	exit: ExitSystem<MEMORY_SIZE>,
+}
+
+impl CallbackDispatcher {
+	pub fn new() -> Self {
+		Self { callbacks: HashMap::new(), exit: ExitSystem::new() }
+	}
+
+	/// Registers the callback for the ECALL, replacing any registered before.
+	///
+	/// A null callback unregisters the ECALL.
+	pub fn register(&mut self, ecall: u32, callback: FusteEcallCallback, user_data: *mut c_void) {
+		match callback {
+			Some(callback) => {
+				self.callbacks.insert(ecall, EcallCallback { callback, user_data });
+			}
+			None => {
+				self.callbacks.remove(&ecall);
+			}
+		}
+	}
+
+	/// Exits with the status in a0, as the exit ECALL does.
+	fn exit(
+		&mut self,
+		machine: &mut Machine<MEMORY_SIZE>,
+	) -> Result<ControlFlow<()>, MachineError> {
+		// The callback may have changed the registers since the trap.
+		machine.trap_registers();
+		self.exit.tick(machine)
+	}
+}
+
+impl Default for CallbackDispatcher {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl MachineSystem<MEMORY_SIZE> for CallbackDispatcher {
+	fn tick(
+		&mut self,
+		machine: &mut Machine<MEMORY_SIZE>,
+	) -> Result<ControlFlow<()>, MachineError> {
+		let ecall = ecall_number(machine);
+
+		let Some(EcallCallback { callback, user_data }) = self.callbacks.get(&ecall) else {
+			if ecall == Ecall::Exit.to_u32() {
+				return self.exit.tick(machine);
+			}
+			return resume_with_status(machine, EcallStatus::NotImplemented);
+		};
+
+		// SAFETY: the host promised when registering that the callback can be called with its
+		// user data, and the machine pointer is only valid for the length of the call.
+		let result = unsafe {
+			callback(*user_data, (machine as *mut Machine<MEMORY_SIZE>).cast::<FusteMachine>())
+		};
+
+		match result {
+			FUSTE_ECALL_CONTINUE => {
+				machine.registers_mut().program_counter_mut().increment();
+				Ok(ControlFlow::Continue(()))
+			}
+			FUSTE_ECALL_EXIT => self.exit(machine),
+			_ => Err(MachineError::SystemError("the ecall callback failed")),
+		}
+	}
+}
+
+impl EcallDispatcherOps<MEMORY_SIZE> for CallbackDispatcher {
+	#[inline(always)]
+	fn set_ecall_interrupt(&mut self, _interrupt: EcallInterrupt) -> Result<(), MachineError> {
+		Ok(())
+	}
+}
+
+/// The computer behind the C API, dispatching ECALLs to the host's callbacks.
+pub struct CallbackComputer {
+	pub inner: InterruptHandler<
+		MEMORY_SIZE,
+		Rv32iComputer,
+		CallbackDispatcher,
+		NoopEbreakDispatcher<MEMORY_SIZE>,
+	>,
+}
+
+impl CallbackComputer {
+	pub fn new() -> Self {
+		Self {
+			inner: InterruptHandler {
+				inner: Rv32iComputer,
+				ecall_dispatcher: CallbackDispatcher::new(),
+				ebreak_dispatcher: NoopEbreakDispatcher {},
+			},
+		}
+	}
+}
+
+impl Default for CallbackComputer {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl MachineSystem<MEMORY_SIZE> for CallbackComputer {
+	#[inline(always)]
+	fn tick(
+		&mut self,
+		machine: &mut Machine<MEMORY_SIZE>,
+	) -> Result<ControlFlow<()>, MachineError> {
+		self.inner.tick(machine)
+	}
+}
+
+impl RuntimeComputer<MEMORY_SIZE> for CallbackComputer {
+	fn exit_status(&self) -> ExitStatus {
+		self.inner.ecall_dispatcher.exit.syscall_status.clone()
+	}
+}
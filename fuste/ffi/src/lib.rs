@@ -0,0 +1,426 @@
+//! A C API around [fuste_runtime], for running fuste programs in hosts which aren't written in
+//! Rust.
+//!
+//! The API is declared in `include/fuste.h`. A host creates a runtime, loads an ELF file into it,
+//! registers its ECALLs as callbacks and runs it with a tick budget:
+//!
+//! ```c
+//! FusteRuntime *runtime = fuste_runtime_new();
+//! fuste_runtime_load_elf(runtime, bytes, length);
+//! fuste_runtime_register_ecall(runtime, 0x100, my_ecall, my_data);
+//!
+//! FusteRunOutcome outcome;
+//! fuste_runtime_run(runtime, 1000000, &outcome);
+//! fuste_runtime_free(runtime);
+//! ```
+//!
+//! Functions returning a [FusteStatus] report null pointers and out of bounds accesses instead of
+//! faulting, the message of the last failure can be read with [fuste_runtime_last_error].
+pub mod computer;
+
+pub use computer::{CallbackComputer, CallbackDispatcher, FusteEcallCallback};
+
+use core::ffi::{c_char, c_void, CStr};
+use fuste_riscv_core::machine::Machine;
+use fuste_riscv_elf::{program::LoadedProgram, Elf32Loader};
+use fuste_runtime::{Runtime, Termination, DEFAULT_MEMORY_SIZE};
+use std::ffi::CString;
+
+/// The memory of the machines created through the C API.
+pub const MEMORY_SIZE: usize = DEFAULT_MEMORY_SIZE;
+
+/// Returned by an ECALL callback to resume the program after the ECALL.
+pub const FUSTE_ECALL_CONTINUE: u32 = 0;
+/// Returned by an ECALL callback to exit the program with the status in a0.
+pub const FUSTE_ECALL_EXIT: u32 = 1;
+/// Returned by an ECALL callback to fault the machine.
+pub const FUSTE_ECALL_FAULT: u32 = 2;
+
+/// Left in a0 for the program by ECALLs without a callback, other than exit.
+pub const FUSTE_ECALL_NOT_IMPLEMENTED: u32 = 2;
+
+/// Whether a call through the C API succeeded.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FusteStatus {
+	Ok = 0,
+	/// A pointer argument was null.
+	NullPointer = 1,
+	/// The ELF file couldn't be loaded.
+	LoadFailed = 2,
+	/// The memory access was outside of the machine's memory.
+	OutOfBounds = 3,
+	/// The register index was 32 or more.
+	InvalidRegister = 4,
+	/// The loaded program has no symbol with the name, or no program was loaded.
+	SymbolNotFound = 5,
+	/// A string argument wasn't valid UTF-8.
+	InvalidString = 6,
+}
+
+/// How a run came to an end, see [fuste_runtime::Termination].
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FusteTermination {
+	Exited = 0,
+	TicksExhausted = 1,
+	Fault = 2,
+}
+
+/// How a run ended and where it left the machine.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct FusteRunOutcome {
+	pub termination: FusteTermination,
+	/// The status the program exited with, if it exited.
+	pub exit_status: u32,
+	/// The number of ticks executed.
+	pub ticks: u32,
+	/// The program counter when the run ended.
+	pub program_counter: u32,
+}
+
+/// The machine a runtime runs, lent to ECALL callbacks to read and write its registers and
+/// memory.
+#[repr(transparent)]
+pub struct FusteMachine(Machine<MEMORY_SIZE>);
+
+/// A machine, the callbacks serving its ECALLs and the program loaded into it.
+pub struct FusteRuntime {
+	runtime: Runtime<MEMORY_SIZE, CallbackComputer>,
+	program: Option<LoadedProgram>,
+	last_error: Option<CString>,
+}
+
+impl FusteRuntime {
+	fn set_last_error(&mut self, message: impl ToString) {
+		// Messages with interior nul bytes are cut short rather than dropped.
+		let message = message.to_string();
+		let message = message.split('\0').next().unwrap_or_default();
+		self.last_error = CString::new(message).ok();
+	}
+
+	fn fail(&mut self, status: FusteStatus, message: impl ToString) -> FusteStatus {
+		self.set_last_error(message);
+		status
+	}
+}
+
+/// Creates a runtime with an empty machine.
+///
+/// The runtime is freed with [fuste_runtime_free].
+#[no_mangle]
+pub extern "C" fn fuste_runtime_new() -> *mut FusteRuntime {
+	let runtime = FusteRuntime {
+		runtime: Runtime::new(CallbackComputer::new()),
+		program: None,
+		last_error: None,
+	};
+	Box::into_raw(Box::new(runtime))
+}
+
+/// Frees a runtime created with [fuste_runtime_new].
+///
+/// # Safety
+///
+/// The runtime must be null or have come from [fuste_runtime_new], and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn fuste_runtime_free(runtime: *mut FusteRuntime) {
+	if !runtime.is_null() {
+		// SAFETY: the runtime came from Box::into_raw in fuste_runtime_new.
+		drop(unsafe { Box::from_raw(runtime) });
+	}
+}
+
+/// Loads an ELF file held in memory, pointing the program counter at its `_start` symbol.
+///
+/// # Safety
+///
+/// The runtime must be valid and the bytes must be readable for the given length.
+#[no_mangle]
+pub unsafe extern "C" fn fuste_runtime_load_elf(
+	runtime: *mut FusteRuntime,
+	bytes: *const u8,
+	length: usize,
+) -> FusteStatus {
+	// SAFETY: the caller promised the runtime is valid.
+	let Some(runtime) = (unsafe { runtime.as_mut() }) else {
+		return FusteStatus::NullPointer;
+	};
+	if bytes.is_null() {
+		return FusteStatus::NullPointer;
+	}
+
+	// SAFETY: the caller promised the bytes are readable for the given length.
+	let bytes = unsafe { core::slice::from_raw_parts(bytes, length) };
+	match runtime.runtime.load_elf_bytes(&Elf32Loader::default(), bytes) {
+		Ok(program) => {
+			runtime.program = Some(program);
+			FusteStatus::Ok
+		}
+		Err(error) => runtime.fail(FusteStatus::LoadFailed, error),
+	}
+}
+
+/// Registers the callback serving the ECALL numbered `ecall`, as passed in a7.
+///
+/// The callback is called with `user_data` and the machine, and returns one of the
+/// `FUSTE_ECALL_*` results. It replaces any callback registered for the ECALL before, and a null
+/// callback unregisters it. Without a callback, the exit ECALL exits with the status in a0 and
+/// any other ECALL resumes the program with [FUSTE_ECALL_NOT_IMPLEMENTED] in a0.
+///
+/// # Safety
+///
+/// The runtime must be valid, and the callback must be safe to call with the user data for as
+/// long as it's registered.
+#[no_mangle]
+pub unsafe extern "C" fn fuste_runtime_register_ecall(
+	runtime: *mut FusteRuntime,
+	ecall: u32,
+	callback: FusteEcallCallback,
+	user_data: *mut c_void,
+) -> FusteStatus {
+	// SAFETY: the caller promised the runtime is valid.
+	let Some(runtime) = (unsafe { runtime.as_mut() }) else {
+		return FusteStatus::NullPointer;
+	};
+
+	runtime
+		.runtime
+		.computer
+		.inner
+		.ecall_dispatcher
+		.register(ecall, callback, user_data);
+	FusteStatus::Ok
+}
+
+/// Runs the loaded program until it exits, faults or has executed `max_ticks` instructions.
+///
+/// A `max_ticks` of 0 runs without a limit. The outcome is written to `outcome`, and a fault's
+/// message can be read with [fuste_runtime_last_error].
+///
+/// # Safety
+///
+/// The runtime must be valid and the outcome must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn fuste_runtime_run(
+	runtime: *mut FusteRuntime,
+	max_ticks: u32,
+	outcome: *mut FusteRunOutcome,
+) -> FusteStatus {
+	// SAFETY: the caller promised the runtime is valid.
+	let Some(runtime) = (unsafe { runtime.as_mut() }) else {
+		return FusteStatus::NullPointer;
+	};
+	// SAFETY: the caller promised the outcome is writable.
+	let Some(outcome) = (unsafe { outcome.as_mut() }) else {
+		return FusteStatus::NullPointer;
+	};
+
+	// Ticks are always counted, so that the outcome can report them.
+	runtime.runtime.max_ticks = Some(if max_ticks == 0 { u32::MAX } else { max_ticks });
+	let run = runtime.runtime.run();
+
+	let (termination, exit_status) = match &run.termination {
+		Termination::Exited(status) => (FusteTermination::Exited, status.clone().to_u32()),
+		Termination::TicksExhausted => (FusteTermination::TicksExhausted, 0),
+		Termination::Fault(error) => {
+			runtime.set_last_error(error);
+			(FusteTermination::Fault, 0)
+		}
+	};
+	*outcome = FusteRunOutcome {
+		termination,
+		exit_status,
+		ticks: run.ticks.map_or(0, |ticks| u32::try_from(ticks).unwrap_or(u32::MAX)),
+		program_counter: run.program_counter,
+	};
+
+	FusteStatus::Ok
+}
+
+/// The runtime's machine, to read and write its registers and memory between runs.
+///
+/// # Safety
+///
+/// The runtime must be valid. The machine is only valid as long as the runtime is.
+#[no_mangle]
+pub unsafe extern "C" fn fuste_runtime_machine(runtime: *mut FusteRuntime) -> *mut FusteMachine {
+	// SAFETY: the caller promised the runtime is valid.
+	match unsafe { runtime.as_mut() } {
+		Some(runtime) => {
+			(runtime.runtime.machine_mut() as *mut Machine<MEMORY_SIZE>).cast::<FusteMachine>()
+		}
+		None => core::ptr::null_mut(),
+	}
+}
+
+/// Looks up the address of a symbol in the loaded program.
+///
+/// # Safety
+///
+/// The runtime must be valid, the name a nul-terminated string and the address writable.
+#[no_mangle]
+pub unsafe extern "C" fn fuste_runtime_symbol(
+	runtime: *mut FusteRuntime,
+	name: *const c_char,
+	address: *mut u32,
+) -> FusteStatus {
+	// SAFETY: the caller promised the runtime is valid.
+	let Some(runtime) = (unsafe { runtime.as_mut() }) else {
+		return FusteStatus::NullPointer;
+	};
+	if name.is_null() {
+		return FusteStatus::NullPointer;
+	}
+	// SAFETY: the caller promised the address is writable.
+	let Some(address) = (unsafe { address.as_mut() }) else {
+		return FusteStatus::NullPointer;
+	};
+
+	// SAFETY: the caller promised the name is nul-terminated.
+	let Ok(name) = unsafe { CStr::from_ptr(name) }.to_str() else {
+		return FusteStatus::InvalidString;
+	};
+	match runtime.program.as_ref().and_then(|program| program.symbol(name)) {
+		Some(symbol) => {
+			*address = symbol.address;
+			FusteStatus::Ok
+		}
+		None => runtime.fail(FusteStatus::SymbolNotFound, format!("no symbol is named {name}")),
+	}
+}
+
+/// The message of the last failed call or fault, or null if there was none.
+///
+/// # Safety
+///
+/// The runtime must be valid. The message is only valid until the next call with the runtime.
+#[no_mangle]
+pub unsafe extern "C" fn fuste_runtime_last_error(runtime: *const FusteRuntime) -> *const c_char {
+	// SAFETY: the caller promised the runtime is valid.
+	match unsafe { runtime.as_ref() }.and_then(|runtime| runtime.last_error.as_ref()) {
+		Some(message) => message.as_ptr(),
+		None => core::ptr::null(),
+	}
+}
+
+/// Reads the general purpose register at the index, x0 to x31.
+///
+/// # Safety
+///
+/// The machine must be valid and the value writable.
+#[no_mangle]
+pub unsafe extern "C" fn fuste_machine_register(
+	machine: *const FusteMachine,
+	index: u32,
+	value: *mut u32,
+) -> FusteStatus {
+	// SAFETY: the caller promised the machine is valid and the value writable.
+	let (Some(machine), Some(value)) = (unsafe { machine.as_ref() }, unsafe { value.as_mut() })
+	else {
+		return FusteStatus::NullPointer;
+	};
+	if index >= 32 {
+		return FusteStatus::InvalidRegister;
+	}
+
+	*value = machine.0.registers().get(index as usize);
+	FusteStatus::Ok
+}
+
+/// Writes the general purpose register at the index, x0 to x31. Writes to x0 are ignored.
+///
+/// # Safety
+///
+/// The machine must be valid.
+#[no_mangle]
+pub unsafe extern "C" fn fuste_machine_set_register(
+	machine: *mut FusteMachine,
+	index: u32,
+	value: u32,
+) -> FusteStatus {
+	// SAFETY: the caller promised the machine is valid.
+	let Some(machine) = (unsafe { machine.as_mut() }) else {
+		return FusteStatus::NullPointer;
+	};
+	if index >= 32 {
+		return FusteStatus::InvalidRegister;
+	}
+
+	machine.0.registers_mut().set(index as u8, value);
+	FusteStatus::Ok
+}
+
+/// The machine's program counter. During an ECALL callback, this is the address of the ECALL.
+///
+/// # Safety
+///
+/// The machine must be valid.
+#[no_mangle]
+pub unsafe extern "C" fn fuste_machine_program_counter(machine: *const FusteMachine) -> u32 {
+	// SAFETY: the caller promised the machine is valid.
+	unsafe { machine.as_ref() }.map_or(0, |machine| machine.0.registers().program_counter())
+}
+
+/// Copies `length` bytes of the machine's memory starting at `address` into `out`.
+///
+/// # Safety
+///
+/// The machine must be valid and `out` writable for the given length.
+#[no_mangle]
+pub unsafe extern "C" fn fuste_machine_read_memory(
+	machine: *const FusteMachine,
+	address: u32,
+	out: *mut u8,
+	length: usize,
+) -> FusteStatus {
+	// SAFETY: the caller promised the machine is valid.
+	let Some(machine) = (unsafe { machine.as_ref() }) else {
+		return FusteStatus::NullPointer;
+	};
+	if out.is_null() {
+		return FusteStatus::NullPointer;
+	}
+
+	let Ok(length) = u32::try_from(length) else {
+		return FusteStatus::OutOfBounds;
+	};
+	match machine.0.memory().read_bytes(address, length) {
+		Ok(bytes) => {
+			// SAFETY: the caller promised out is writable for the length, and it can't overlap
+			// the machine's memory, which only the runtime hands out.
+			unsafe { core::ptr::copy_nonoverlapping(bytes.as_ptr(), out, bytes.len()) };
+			FusteStatus::Ok
+		}
+		Err(_) => FusteStatus::OutOfBounds,
+	}
+}
+
+/// Copies `length` bytes from `bytes` into the machine's memory starting at `address`.
+///
+/// # Safety
+///
+/// The machine must be valid and `bytes` readable for the given length.
+#[no_mangle]
+pub unsafe extern "C" fn fuste_machine_write_memory(
+	machine: *mut FusteMachine,
+	address: u32,
+	bytes: *const u8,
+	length: usize,
+) -> FusteStatus {
+	// SAFETY: the caller promised the machine is valid.
+	let Some(machine) = (unsafe { machine.as_mut() }) else {
+		return FusteStatus::NullPointer;
+	};
+	if bytes.is_null() {
+		return FusteStatus::NullPointer;
+	}
+
+	// SAFETY: the caller promised the bytes are readable for the given length.
+	let bytes = unsafe { core::slice::from_raw_parts(bytes, length) };
+	match machine.0.memory_mut().write_bytes(address, bytes) {
+		Ok(()) => FusteStatus::Ok,
+		Err(_) => FusteStatus::OutOfBounds,
+	}
+}
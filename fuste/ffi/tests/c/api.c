@@ -0,0 +1,146 @@
+/*
+ * Drives the C API with guest.elf, which asks the host to add two numbers with ECALL 0x100,
+ * stores the sum at the `result` symbol and exits successfully.
+ *
+ * Usage: api <path to guest.elf>
+ */
+
+#include "fuste.h"
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#define ADD_ECALL 0x100
+#define A0 10
+#define A1 11
+
+#define CHECK(condition)                                                                           \
+	do {                                                                                           \
+		if (!(condition)) {                                                                        \
+			fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, #condition);          \
+			exit(1);                                                                               \
+		}                                                                                          \
+	} while (0)
+
+static uint32_t add(void *user_data, FusteMachine *machine) {
+	uint32_t a, b;
+	if (fuste_machine_register(machine, A0, &a) != FUSTE_STATUS_OK
+		|| fuste_machine_register(machine, A1, &b) != FUSTE_STATUS_OK) {
+		return FUSTE_ECALL_FAULT;
+	}
+	if (fuste_machine_set_register(machine, A0, a + b) != FUSTE_STATUS_OK) {
+		return FUSTE_ECALL_FAULT;
+	}
+	*(int *)user_data += 1;
+	return FUSTE_ECALL_CONTINUE;
+}
+
+static uint8_t *read_file(const char *path, size_t *length) {
+	FILE *file = fopen(path, "rb");
+	CHECK(file != NULL);
+	CHECK(fseek(file, 0, SEEK_END) == 0);
+	long size = ftell(file);
+	CHECK(size > 0);
+	CHECK(fseek(file, 0, SEEK_SET) == 0);
+
+	uint8_t *bytes = malloc((size_t)size);
+	CHECK(bytes != NULL);
+	CHECK(fread(bytes, 1, (size_t)size, file) == (size_t)size);
+	fclose(file);
+
+	*length = (size_t)size;
+	return bytes;
+}
+
+static FusteRuntime *load(const uint8_t *elf, size_t length) {
+	FusteRuntime *runtime = fuste_runtime_new();
+	CHECK(runtime != NULL);
+	CHECK(fuste_runtime_load_elf(runtime, elf, length) == FUSTE_STATUS_OK);
+	return runtime;
+}
+
+static void test_ecall_callback(const uint8_t *elf, size_t length) {
+	FusteRuntime *runtime = load(elf, length);
+	int calls = 0;
+	CHECK(fuste_runtime_register_ecall(runtime, ADD_ECALL, add, &calls) == FUSTE_STATUS_OK);
+
+	FusteRunOutcome outcome;
+	CHECK(fuste_runtime_run(runtime, 1000, &outcome) == FUSTE_STATUS_OK);
+	CHECK(outcome.termination == FUSTE_TERMINATION_EXITED);
+	CHECK(outcome.exit_status == 0);
+	CHECK(outcome.ticks == 10);
+	CHECK(calls == 1);
+
+	uint32_t address;
+	CHECK(fuste_runtime_symbol(runtime, "result", &address) == FUSTE_STATUS_OK);
+	uint8_t result[4];
+	FusteMachine *machine = fuste_runtime_machine(runtime);
+	CHECK(fuste_machine_read_memory(machine, address, result, sizeof(result)) == FUSTE_STATUS_OK);
+	CHECK(result[0] == 42 && result[1] == 0 && result[2] == 0 && result[3] == 0);
+
+	fuste_runtime_free(runtime);
+}
+
+static void test_tick_budget(const uint8_t *elf, size_t length) {
+	FusteRuntime *runtime = load(elf, length);
+
+	FusteRunOutcome outcome;
+	CHECK(fuste_runtime_run(runtime, 2, &outcome) == FUSTE_STATUS_OK);
+	CHECK(outcome.termination == FUSTE_TERMINATION_TICKS_EXHAUSTED);
+	CHECK(outcome.ticks == 2);
+
+	fuste_runtime_free(runtime);
+}
+
+static void test_unregistered_ecall(const uint8_t *elf, size_t length) {
+	FusteRuntime *runtime = load(elf, length);
+
+	FusteRunOutcome outcome;
+	CHECK(fuste_runtime_run(runtime, 0, &outcome) == FUSTE_STATUS_OK);
+	CHECK(outcome.termination == FUSTE_TERMINATION_EXITED);
+	CHECK(outcome.exit_status == 0);
+
+	/* The guest stores the FUSTE_ECALL_NOT_IMPLEMENTED status left in a0 as its result. */
+	uint32_t address;
+	CHECK(fuste_runtime_symbol(runtime, "result", &address) == FUSTE_STATUS_OK);
+	uint8_t result[4];
+	FusteMachine *machine = fuste_runtime_machine(runtime);
+	CHECK(fuste_machine_read_memory(machine, address, result, sizeof(result)) == FUSTE_STATUS_OK);
+	CHECK(result[0] == FUSTE_ECALL_NOT_IMPLEMENTED && result[1] == 0);
+
+	fuste_runtime_free(runtime);
+}
+
+static void test_errors(const uint8_t *elf, size_t length) {
+	FusteRuntime *runtime = fuste_runtime_new();
+	const uint8_t garbage[] = { 1, 2, 3, 4 };
+	CHECK(fuste_runtime_load_elf(runtime, garbage, sizeof(garbage)) == FUSTE_STATUS_LOAD_FAILED);
+	CHECK(fuste_runtime_last_error(runtime) != NULL);
+	fuste_runtime_free(runtime);
+
+	runtime = load(elf, length);
+	FusteMachine *machine = fuste_runtime_machine(runtime);
+	uint32_t value;
+	uint8_t byte = 0;
+	CHECK(fuste_machine_register(machine, 32, &value) == FUSTE_STATUS_INVALID_REGISTER);
+	CHECK(fuste_machine_write_memory(machine, FUSTE_MEMORY_SIZE, &byte, 1)
+		  == FUSTE_STATUS_OUT_OF_BOUNDS);
+	CHECK(fuste_runtime_symbol(runtime, "missing", &value) == FUSTE_STATUS_SYMBOL_NOT_FOUND);
+	CHECK(fuste_runtime_run(NULL, 0, NULL) == FUSTE_STATUS_NULL_POINTER);
+	fuste_runtime_free(runtime);
+}
+
+int main(int argc, char **argv) {
+	CHECK(argc == 2);
+	size_t length;
+	uint8_t *elf = read_file(argv[1], &length);
+
+	test_ecall_callback(elf, length);
+	test_tick_budget(elf, length);
+	test_unregistered_ecall(elf, length);
+	test_errors(elf, length);
+
+	free(elf);
+	return 0;
+}
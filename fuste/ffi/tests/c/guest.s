@@ -0,0 +1,24 @@
+# The guest driven by api.c, rebuild guest.elf with
+#
+#   llvm-mc -triple=riscv32 -mattr=-c -filetype=obj guest.s -o guest.o
+#   rust-lld -flavor gnu -T ../../../tests/toolchain/linker.lld guest.o -o guest.elf
+
+	.globl _start
+	.section .text
+_start:
+	# ask the host to add a0 and a1
+	li a7, 0x100
+	li a0, 20
+	li a1, 22
+	ecall
+	la t0, result
+	sw a0, 0(t0)
+	# exit successfully
+	li a7, 93
+	li a0, 0
+	ecall
+
+	.section .data
+	.globl result
+result:
+	.word 0
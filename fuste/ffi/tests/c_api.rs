@@ -0,0 +1,61 @@
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The directory cargo builds the fuste_ffi library into, next to the test binary.
+fn library_dir() -> Result<PathBuf, Box<dyn Error>> {
+	let test_binary = env::current_exe()?;
+	let library_dir = test_binary.parent().ok_or("the test binary should be in a directory")?;
+	Ok(library_dir.to_path_buf())
+}
+
+#[test]
+fn test_c_api() -> Result<(), Box<dyn Error>> {
+	let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+	let library_dir = library_dir()?;
+	let program = Path::new(env!("CARGO_TARGET_TMPDIR")).join("fuste-ffi-api");
+
+	let compiler = env::var("CC").unwrap_or_else(|_| "cc".to_string());
+	let status = Command::new(compiler)
+		.arg(crate_dir.join("tests/c/api.c"))
+		.arg("-I")
+		.arg(crate_dir.join("include"))
+		.arg("-L")
+		.arg(&library_dir)
+		.arg(format!("-Wl,-rpath,{}", library_dir.display()))
+		.arg("-lfuste_ffi")
+		.arg("-o")
+		.arg(&program)
+		.status()?;
+	assert!(status.success(), "failed to compile api.c");
+
+	let output = Command::new(&program).arg(crate_dir.join("tests/c/guest.elf")).output()?;
+	assert!(output.status.success(), "api.c failed: {}", String::from_utf8_lossy(&output.stderr));
+
+	Ok(())
+}
+
+/// The header is written by hand, so check it declares everything the library exports.
+#[test]
+fn test_header_declares_exports() -> Result<(), Box<dyn Error>> {
+	let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+	let source = fs::read_to_string(crate_dir.join("src/lib.rs"))?;
+	let header = fs::read_to_string(crate_dir.join("include/fuste.h"))?;
+
+	let exports = source
+		.lines()
+		.filter_map(|line| line.split_once("extern \"C\" fn "))
+		.filter_map(|(_, rest)| rest.split_once('('))
+		.map(|(name, _)| name);
+
+	let mut count = 0;
+	for name in exports {
+		assert!(header.contains(&format!("{name}(")), "fuste.h doesn't declare {name}");
+		count += 1;
+	}
+	assert_eq!(count, 13);
+
+	Ok(())
+}
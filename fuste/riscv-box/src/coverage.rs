@@ -0,0 +1,24 @@
+pub mod elf;
+
+use crate::run::elf::report::RunReport;
+use clap::Subcommand;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CoverageError {
+	#[error("Encountered an error while collecting coverage of an ELF program: {0}")]
+	ElfError(#[from] elf::ElfError),
+}
+
+#[derive(Subcommand)]
+pub enum Coverage {
+	/// Load and run a RISC-V ELF file and report its coverage
+	Elf(elf::Elf),
+}
+
+impl Coverage {
+	pub async fn execute(&self) -> Result<RunReport, CoverageError> {
+		match self {
+			Coverage::Elf(elf) => elf.execute().await.map_err(CoverageError::ElfError),
+		}
+	}
+}
@@ -0,0 +1,98 @@
+use crate::run::elf::{
+	observe::Observer, report::RunReport, BoxComputer, ElfError as RunElfError, ElfLoaderArgs,
+	RunArgs, BOX_MEMORY_SIZE,
+};
+use clap::Parser;
+use fuste_coverage::{Coverage, CoverageSystem};
+use fuste_riscv_core::plugins::rv32i_computer::Rv32iComputer;
+use fuste_riscv_elf::program::LoadedProgram;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+impl<Computer: BoxComputer> Observer for CoverageSystem<BOX_MEMORY_SIZE, Computer> {
+	type Computer = Computer;
+
+	fn computer(&self) -> &Computer {
+		&self.computer
+	}
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ElfError {
+	#[error("Encountered an error while running the program: {0}")]
+	RunError(#[from] RunElfError),
+	#[error("Encountered an error while writing the coverage report: {0}")]
+	CoverageError(#[from] fuste_coverage::CoverageError),
+	#[error("Encountered an error while creating the coverage output: {0}")]
+	Io(#[from] std::io::Error),
+}
+
+#[derive(Parser)]
+#[clap(rename_all = "kebab-case")]
+#[group(skip)]
+pub struct Elf {
+	#[clap(flatten)]
+	pub run: RunArgs,
+	#[clap(flatten)]
+	pub loader: ElfLoaderArgs,
+	/// Write an lcov tracefile to the given path
+	#[clap(long)]
+	pub lcov: Option<PathBuf>,
+}
+
+impl Elf {
+	pub fn write_coverage(
+		&self,
+		coverage: &Coverage,
+		program: &LoadedProgram,
+	) -> Result<(), ElfError> {
+		let instructions = coverage.instruction_summary(&program.sections);
+		println!(
+			"instructions: {}/{} ({:.2}%)",
+			instructions.executed,
+			instructions.total,
+			instructions.percent()
+		);
+
+		let Some(lines) = &program.lines else {
+			println!("lines: no DWARF line information, build the guest with debug info");
+			return Ok(());
+		};
+
+		let line_coverage = coverage.line_coverage(lines, &program.symbols);
+		let (found, hit) = (line_coverage.lines_found(), line_coverage.lines_hit());
+		let percent = if found == 0 { 0.0 } else { hit as f64 * 100.0 / found as f64 };
+		println!("lines: {hit}/{found} ({percent:.2}%)");
+
+		if let Some(path) = &self.lcov {
+			let mut writer = BufWriter::new(File::create(path)?);
+			line_coverage.write_lcov(&mut writer)?;
+			writer.flush()?;
+		}
+
+		Ok(())
+	}
+
+	pub fn cover<Computer: BoxComputer>(
+		&self,
+		run: &RunArgs,
+		computer: impl FnOnce() -> Result<Computer, RunElfError>,
+	) -> Result<RunReport, ElfError> {
+		let observed = run.run_observed(&self.loader, computer, CoverageSystem::new)?;
+
+		// Coverage up to a failure is still reported, since it shows how far the program got.
+		self.write_coverage(&observed.observer.coverage, &observed.program)?;
+		Ok(observed.report?)
+	}
+
+	pub async fn execute(&self) -> Result<RunReport, ElfError> {
+		let run = self.run.configured()?;
+
+		if run.ecalls {
+			self.cover(&run, || run.ecall_computer())
+		} else {
+			self.cover(&run, || Ok(Rv32iComputer))
+		}
+	}
+}
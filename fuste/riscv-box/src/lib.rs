@@ -1,7 +1,12 @@
+#[cfg(feature = "coverage")]
+pub mod coverage;
+#[cfg(feature = "profile")]
+pub mod profile;
 pub mod run;
 
 use clap::Parser;
 use clap_markdown_ext::Markdown;
+use std::process::ExitCode;
 
 #[derive(Debug, thiserror::Error)]
 pub enum FuboxError {
@@ -9,6 +14,12 @@ pub enum FuboxError {
 	MarkdownError(#[from] anyhow::Error),
 	#[error("Encountered an error while running the program: {0}")]
 	RunError(#[from] run::RunError),
+	#[cfg(feature = "profile")]
+	#[error("Encountered an error while profiling the program: {0}")]
+	ProfileError(#[from] profile::ProfileError),
+	#[cfg(feature = "coverage")]
+	#[error("Encountered an error while collecting coverage of the program: {0}")]
+	CoverageError(#[from] coverage::CoverageError),
 }
 
 #[derive(Parser)]
@@ -20,19 +31,34 @@ pub enum Fubox {
 	/// Run a RISC-V program in the box
 	#[clap(subcommand)]
 	Run(run::Run),
+	/// Run a RISC-V program in the box and profile where it spends its ticks
+	#[cfg(feature = "profile")]
+	#[clap(subcommand)]
+	Profile(profile::Profile),
+	/// Run a RISC-V program in the box and report which instructions and source lines it executed
+	#[cfg(feature = "coverage")]
+	#[clap(subcommand)]
+	Coverage(coverage::Coverage),
 }
 
 impl Fubox {
-	pub async fn execute(&self) -> Result<(), FuboxError> {
-		match self {
+	/// Executes the command, returning the exit code for the process.
+	///
+	/// Runs, profiles and coverage runs all exit with the program's status, see
+	/// [run::elf::report::RunReport::exit_code].
+	pub async fn execute(&self) -> Result<ExitCode, FuboxError> {
+		let report = match self {
 			Fubox::Markdown(markdown) => {
 				markdown.execute::<Self>().await?;
+				return Ok(ExitCode::SUCCESS);
 			}
-			Fubox::Run(run) => {
-				run.execute().await?;
-			}
-		}
+			Fubox::Run(run) => run.execute().await?,
+			#[cfg(feature = "profile")]
+			Fubox::Profile(profile) => profile.execute().await?,
+			#[cfg(feature = "coverage")]
+			Fubox::Coverage(coverage) => coverage.execute().await?,
+		};
 
-		Ok(())
+		Ok(report.process_exit_code())
 	}
 }
@@ -1,14 +1,16 @@
 use clap::Parser;
-use fubox::{Fubox, FuboxError};
+use fubox::run::elf::report::ERROR_EXIT_CODE;
+use fubox::Fubox;
+use std::process::ExitCode;
 
 #[tokio::main]
-async fn main() -> Result<(), FuboxError> {
+async fn main() -> ExitCode {
 	let fubox = Fubox::parse();
 	match fubox.execute().await {
-		Ok(()) => Ok(()),
+		Ok(exit_code) => exit_code,
 		Err(e) => {
 			eprintln!("Error: {}", e);
-			Ok(())
+			ExitCode::from(ERROR_EXIT_CODE)
 		}
 	}
 }
@@ -0,0 +1,24 @@
+pub mod elf;
+
+use crate::run::elf::report::RunReport;
+use clap::Subcommand;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ProfileError {
+	#[error("Encountered an error while profiling an ELF program: {0}")]
+	ElfError(#[from] elf::ElfError),
+}
+
+#[derive(Subcommand)]
+pub enum Profile {
+	/// Load, run and profile a RISC-V ELF file
+	Elf(elf::Elf),
+}
+
+impl Profile {
+	pub async fn execute(&self) -> Result<RunReport, ProfileError> {
+		match self {
+			Profile::Elf(elf) => elf.execute().await.map_err(ProfileError::ElfError),
+		}
+	}
+}
@@ -0,0 +1,107 @@
+use crate::run::elf::{
+	observe::Observer, report::RunReport, BoxComputer, ElfError as RunElfError, ElfLoaderArgs,
+	RunArgs, BOX_MEMORY_SIZE,
+};
+use clap::Parser;
+use fuste_profile::{Profile, ProfileSystem};
+use fuste_riscv_core::plugins::rv32i_computer::Rv32iComputer;
+use fuste_riscv_elf::program::LoadedProgram;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+impl<Computer: BoxComputer> Observer for ProfileSystem<BOX_MEMORY_SIZE, Computer> {
+	type Computer = Computer;
+
+	fn computer(&self) -> &Computer {
+		&self.computer
+	}
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ElfError {
+	#[error("Encountered an error while running the program: {0}")]
+	RunError(#[from] RunElfError),
+	#[error("Encountered an error while writing the profile: {0}")]
+	ProfileError(#[from] fuste_profile::ProfileError),
+	#[error("Encountered an error while creating the profile output: {0}")]
+	Io(#[from] std::io::Error),
+}
+
+#[derive(Parser)]
+#[clap(rename_all = "kebab-case")]
+#[group(skip)]
+pub struct Elf {
+	#[clap(flatten)]
+	pub run: RunArgs,
+	#[clap(flatten)]
+	pub loader: ElfLoaderArgs,
+	/// Write the flat profile to the given path instead of stdout
+	#[clap(long)]
+	pub flat: Option<PathBuf>,
+	/// Write folded stacks for flamegraph tools to the given path
+	#[clap(long)]
+	pub folded: Option<PathBuf>,
+	/// Write a pprof profile to the given path
+	#[clap(long)]
+	pub pprof: Option<PathBuf>,
+}
+
+impl Elf {
+	fn create(path: &Path) -> Result<BufWriter<File>, ElfError> {
+		Ok(BufWriter::new(File::create(path)?))
+	}
+
+	pub fn write_profile(
+		&self,
+		profile: &Profile,
+		program: &LoadedProgram,
+	) -> Result<(), ElfError> {
+		let symbols = &program.symbols;
+
+		match &self.flat {
+			Some(path) => {
+				let mut writer = Self::create(path)?;
+				profile.write_flat(symbols, &mut writer)?;
+				writer.flush()?;
+			}
+			None => profile.write_flat(symbols, &mut std::io::stdout().lock())?,
+		}
+
+		if let Some(path) = &self.folded {
+			let mut writer = Self::create(path)?;
+			profile.write_folded(symbols, &mut writer)?;
+			writer.flush()?;
+		}
+
+		if let Some(path) = &self.pprof {
+			let mut writer = Self::create(path)?;
+			profile.write_pprof(symbols, &mut writer)?;
+			writer.flush()?;
+		}
+
+		Ok(())
+	}
+
+	pub fn profile<Computer: BoxComputer>(
+		&self,
+		run: &RunArgs,
+		computer: impl FnOnce() -> Result<Computer, RunElfError>,
+	) -> Result<RunReport, ElfError> {
+		let observed = run.run_observed(&self.loader, computer, ProfileSystem::new)?;
+
+		// The profile is written even if the run fails, since that is often when it's needed.
+		self.write_profile(&observed.observer.profile, &observed.program)?;
+		Ok(observed.report?)
+	}
+
+	pub async fn execute(&self) -> Result<RunReport, ElfError> {
+		let run = self.run.configured()?;
+
+		if run.ecalls {
+			self.profile(&run, || run.ecall_computer())
+		} else {
+			self.profile(&run, || Ok(Rv32iComputer))
+		}
+	}
+}
@@ -1,23 +1,59 @@
+pub mod bin;
 pub mod elf;
+pub mod hex;
 
 use clap::Subcommand;
+use elf::report::RunReport;
 
 #[derive(Debug, thiserror::Error)]
 pub enum RunError {
 	#[error("Encountered an error while loading and running an ELF program: {0}")]
 	ElfError(#[from] elf::ElfError),
+	#[error("Encountered an error while loading and running a raw binary: {0}")]
+	BinError(#[from] bin::BinError),
+	#[error("Encountered an error while loading and running a hex file: {0}")]
+	HexError(#[from] hex::HexError),
 }
 
 #[derive(Subcommand)]
 pub enum Run {
 	/// Load and run a RISC-V ELF file
-	Elf(elf::Elf),
+	Elf(elf::ElfFile),
+	/// Load and run a raw binary image at a given address
+	Bin(bin::Bin),
+	/// Load and run an Intel HEX or Motorola S-record file
+	Hex(hex::Hex),
 }
 
 impl Run {
-	pub async fn execute(&self) -> Result<(), RunError> {
+	pub async fn execute(&self) -> Result<RunReport, RunError> {
 		match self {
 			Run::Elf(elf) => elf.execute().await.map_err(RunError::ElfError),
+			Run::Bin(bin) => bin.execute().await.map_err(RunError::BinError),
+			Run::Hex(hex) => hex.execute().await.map_err(RunError::HexError),
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use crate::Fubox;
+	use clap::Parser;
+
+	fn parses(args: &[&str]) -> bool {
+		Fubox::try_parse_from([&["fubox", "run"], args].concat()).is_ok()
+	}
+
+	#[test]
+	fn test_elf_loader_flags_only_apply_to_elf_files() {
+		let elf_loader_flags = ["--base-address", "0x1000", "--entrypoint-symbol-name", "main"];
+
+		assert!(parses(&[&["elf", "--path", "a.elf"], &elf_loader_flags[..]].concat()));
+		assert!(parses(&["bin", "--path", "a.bin", "--load-addr", "0x1000"]));
+		assert!(!parses(
+			&[&["bin", "--path", "a.bin", "--load-addr", "0x1000"], &elf_loader_flags[..2]]
+				.concat()
+		));
+		assert!(!parses(&[&["hex", "--path", "a.hex"], &elf_loader_flags[2..]].concat()));
+	}
+}
@@ -0,0 +1,40 @@
+use crate::run::elf::report::RunReport;
+use crate::run::elf::{parse_address, ElfError as RunElfError, RunArgs};
+use clap::Parser;
+use fuste_riscv_elf::binary::BinaryLoader;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BinError {
+	#[error("Encountered an error while running the program: {0}")]
+	RunError(#[from] RunElfError),
+}
+
+#[derive(Parser)]
+#[clap(rename_all = "kebab-case")]
+#[group(skip)]
+pub struct Bin {
+	#[clap(flatten)]
+	pub run: RunArgs,
+	/// The address to copy the binary to, e.g. 0x1000
+	#[clap(long, value_parser = parse_address)]
+	pub load_addr: u32,
+	/// The address to start executing at, defaults to the load address
+	#[clap(long, value_parser = parse_address)]
+	pub entry: Option<u32>,
+}
+
+impl Bin {
+	/// The loader configured by the arguments.
+	pub fn loader(&self) -> Result<BinaryLoader, BinError> {
+		Ok(BinaryLoader::new(self.load_addr)
+			.with_entrypoint(self.entry.unwrap_or(self.load_addr))
+			.with_arguments(self.run.program_arguments()?))
+	}
+
+	pub async fn execute(&self) -> Result<RunReport, BinError> {
+		let run = self.run.configured()?;
+		let loader = self.loader()?;
+
+		Ok(run.run_program(|machine| loader.load_bin(machine, &self.run.path))?)
+	}
+}
@@ -1,86 +1,137 @@
-use clap::Parser;
-use fuste_ecall_dispatcher::{EcallDispatcher, NoopDispatcher};
-use fuste_exit::ExitStatus;
+pub mod config;
+#[cfg(any(feature = "profile", feature = "coverage"))]
+pub mod observe;
+pub mod report;
+pub mod systems;
+#[cfg(feature = "trace")]
+pub mod trace;
+
+use clap::{Args, Parser};
+use config::RunConfig;
+use fuste_clock_system::{Clock, ClockSystem};
 use fuste_exit_system::ExitSystem;
-use fuste_interrupt_handler::{InterruptHandler, NoopEbreakDispatcher};
-use fuste_lilbug::LilBugComputer;
 use fuste_lilbug::LilBugSystem;
 use fuste_riscv_core::{
 	instructions::Rv32iInstruction,
 	machine::{Machine, MachineError, MachineSystem},
 	plugins::rv32i_computer::Rv32iComputer,
 };
-use fuste_riscv_elf::{Elf32Loader, ElfLoaderError};
-use fuste_std_output_system::StdOutputSystem;
-use fuste_tick_handler::TickHandler;
+use fuste_riscv_elf::{
+	arguments::{AuxiliaryVector, ProgramArguments},
+	program::LoadedProgram,
+	Elf32Loader, ElfLoaderError,
+};
+use fuste_runtime::{
+	run_system, run_system_counting_ticks, run_system_with_ticks, EcallComputer, RunOutcome,
+	Runtime, RuntimeComputer, SharedSystem, SystemRun, Termination, DEFAULT_MEMORY_SIZE,
+};
+use fuste_std_input_system::StdInputSystem;
+use fuste_std_output_system::{StdOutputSystem, WriteTarget};
+use fuste_std_random_system::{fill_from_seed, seed_from_u64, StdRandomSystem};
+use report::{ReportFormat, RunReport};
+use std::fs::File;
 use std::ops::ControlFlow;
 use std::path::PathBuf;
-
-pub struct EcallMachine {
-	pub inner: InterruptHandler<
-		BOX_MEMORY_SIZE,
-		Rv32iComputer,
-		EcallDispatcher<
-			BOX_MEMORY_SIZE,
-			ExitSystem<BOX_MEMORY_SIZE>,
-			Option<StdOutputSystem<BOX_MEMORY_SIZE>>,
-			NoopDispatcher<BOX_MEMORY_SIZE>,
-			NoopDispatcher<BOX_MEMORY_SIZE>,
-		>,
-		NoopEbreakDispatcher<BOX_MEMORY_SIZE>,
-	>,
+use systems::{BoxStdTransaction, ChannelSystemKind, ClockKind, SignerStoreKind, WriteSystemKind};
+
+/// The computer fubox runs programs with when ECALLs are supported.
+pub type BoxEcallComputer = EcallComputer<
+	BOX_MEMORY_SIZE,
+	ExitSystem<BOX_MEMORY_SIZE>,
+	Option<StdOutputSystem<BOX_MEMORY_SIZE>>,
+	Option<StdInputSystem<BOX_MEMORY_SIZE>>,
+	Option<SharedSystem<BoxStdTransaction>>,
+	Option<SharedSystem<BoxStdTransaction>>,
+	StdRandomSystem<BOX_MEMORY_SIZE>,
+	ClockSystem<BOX_MEMORY_SIZE>,
+>;
+
+pub const BOX_MEMORY_SIZE: usize = DEFAULT_MEMORY_SIZE;
+
+/// A computer fubox runs programs with.
+pub trait BoxComputer: RuntimeComputer<BOX_MEMORY_SIZE> {
+	/// The number of bytes the program wrote to stdout.
+	fn stdout_bytes(&self) -> usize;
 }
 
-impl MachineSystem<BOX_MEMORY_SIZE> for EcallMachine {
-	#[inline(always)]
-	fn tick(
-		&mut self,
-		machine: &mut Machine<BOX_MEMORY_SIZE>,
-	) -> Result<ControlFlow<()>, MachineError> {
-		self.inner.tick(machine)
+impl BoxComputer for Rv32iComputer {
+	fn stdout_bytes(&self) -> usize {
+		0
 	}
 }
 
-impl LilBugComputer<BOX_MEMORY_SIZE> for EcallMachine {
-	fn exit_status(&self) -> ExitStatus {
-		self.inner.ecall_dispatcher.exit_dispatcher.exit_status()
+impl BoxComputer for BoxEcallComputer {
+	fn stdout_bytes(&self) -> usize {
+		self.ecall_dispatcher
+			.write_dispatcher
+			.as_ref()
+			.map_or(0, |system| system.bytes_written)
 	}
 }
 
-pub struct NoEcallMachine {
-	pub inner: Rv32iComputer,
+#[derive(Debug, thiserror::Error)]
+pub enum ElfError {
+	#[error("Encountered an error while loading the program: {0}")]
+	LoaderError(#[from] ElfLoaderError),
+	#[error("Encountered an error while running the machine: {0}")]
+	MachineError(#[from] MachineError),
+	#[error("Encountered an error while loading the run profile: {0}")]
+	ConfigError(#[from] config::ConfigError),
+	#[error("The memory size {0} differs from the {BOX_MEMORY_SIZE} bytes the box is built with")]
+	MemorySizeMismatch(u32),
+	#[error("The program's manifest can't be satisfied: {0}")]
+	UnsatisfiedManifest(String),
+	#[error("Failed to write the run report: {0}")]
+	ReportError(#[from] serde_json::Error),
+	#[error("Failed to create the write file {0}: {1}")]
+	WriteFileError(PathBuf, std::io::Error),
+	#[error("Failed to open the stdin file {0}: {1}")]
+	StdinFileError(PathBuf, std::io::Error),
+	#[error("Failed to generate random bytes for the program: {0}")]
+	RandomError(#[from] getrandom::Error),
+	#[cfg(feature = "trace")]
+	#[error("Encountered an error while setting up the trace: {0}")]
+	TraceError(#[from] fuste_trace::TraceError),
 }
 
-impl MachineSystem<BOX_MEMORY_SIZE> for NoEcallMachine {
-	#[inline(always)]
-	fn tick(
-		&mut self,
-		machine: &mut Machine<BOX_MEMORY_SIZE>,
-	) -> Result<ControlFlow<()>, MachineError> {
-		self.inner.tick(machine)
+/// Parses an address in decimal or, with a `0x` prefix, hexadecimal.
+pub fn parse_address(value: &str) -> Result<u32, std::num::ParseIntError> {
+	match value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+		Some(hex) => u32::from_str_radix(hex, 16),
+		None => value.parse(),
 	}
 }
 
-impl LilBugComputer<BOX_MEMORY_SIZE> for NoEcallMachine {
-	fn exit_status(&self) -> ExitStatus {
-		ExitStatus::Unsupported
-	}
+/// Parses an environment variable given as `NAME=VALUE`.
+pub fn parse_env_var(value: &str) -> Result<(String, String), String> {
+	value
+		.split_once('=')
+		.map(|(name, value)| (name.to_string(), value.to_string()))
+		.ok_or_else(|| format!("expected NAME=VALUE, got \"{value}\""))
 }
 
-const BOX_MEMORY_SIZE: usize = 1024 * 1024 * 2; // 1MB
-
-#[derive(Debug, thiserror::Error)]
-pub enum ElfError {
-	#[error("Encountered an error while loading the ELF file: {0}")]
-	LoaderError(#[from] ElfLoaderError),
-	#[error("Encountered an error while running the machine: {0}")]
-	MachineError(#[from] MachineError),
+/// Parses a write file given as `SYSTEM_ID=PATH`.
+pub fn parse_write_file(value: &str) -> Result<(u32, PathBuf), String> {
+	let (system_id, path) = value
+		.split_once('=')
+		.ok_or_else(|| format!("expected SYSTEM_ID=PATH, got \"{value}\""))?;
+	let system_id = parse_address(system_id)
+		.map_err(|error| format!("invalid system ID {system_id}: {error}"))?;
+	Ok((system_id, PathBuf::from(path)))
 }
 
-#[derive(Parser)]
+/// The page size reported to programs in their auxiliary vector.
+pub const PAGE_SIZE: u32 = 4096;
+
+/// The nonce of the seeded ChaCha20 stream the auxiliary vector's random bytes come from, apart
+/// from the random ECALL's stream so they aren't the first bytes the program draws.
+const AUXV_RANDOM_NONCE: [u8; 12] = [1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+
+/// The options for running a program, shared by ELF, raw binary and hex files.
+#[derive(Clone, Parser)]
 #[clap(rename_all = "kebab-case")]
-pub struct Elf {
-	/// The path to the ELF file to run
+pub struct RunArgs {
+	/// The path to the program to run
 	#[clap(long)]
 	pub path: PathBuf,
 	/// The number of ticks to run the machine for
@@ -98,18 +149,110 @@ pub struct Elf {
 	/// Whether to log the registers at the end of the execution
 	#[clap(long)]
 	pub log_registers_at_end: bool,
-	/// The name of the entrypoint symbol to load
-	#[clap(long, default_value = "_start")]
-	pub entrypoint_symbol_name: String,
 	/// Whether to support ecalls
 	#[clap(long, default_value = "true")]
 	pub ecalls: bool,
-	/// Whether to support std-output
+	/// Whether to serve writes to stdout and stderr, e.g. --std-output false, by default unless the
+	/// run profile's write systems leave stdout out
+	#[clap(long, num_args = 0..=1, default_missing_value = "true")]
+	pub std_output: Option<bool>,
+	/// Write what the program writes to a system ID to a file, e.g. --write-file 3=out.bin
+	#[clap(long = "write-file", value_parser = parse_write_file)]
+	pub write_files: Vec<(u32, PathBuf)>,
+	/// Whether to serve reads from stdin
 	#[clap(long, default_value = "true")]
-	pub std_output: bool,
+	pub std_input: bool,
+	/// Serve reads from stdin with the contents of the file instead of the host's stdin
+	#[clap(long)]
+	pub stdin_file: Option<PathBuf>,
 	/// Whether to log the exit status
 	#[clap(long)]
 	pub log_exit_status: bool,
+	/// A TOML or JSON run profile selecting the memory, ticks and systems to run with
+	#[clap(long)]
+	pub config: Option<PathBuf>,
+	/// The memory the program runs with in bytes. The box's memory is fixed when it's built, so this
+	/// must equal it; any other size is an error
+	#[clap(long)]
+	pub memory_size: Option<u32>,
+	/// A channel system to serve, may be given more than once
+	#[clap(long = "channel-system", value_enum)]
+	pub channel_systems: Vec<ChannelSystemKind>,
+	/// The backend of the standard transaction system's signer store
+	#[clap(long, value_enum)]
+	pub signer_store: Option<SignerStoreKind>,
+	/// Serve the random ECALL and the auxiliary vector's random bytes with ChaCha20 streams from
+	/// the seed, so runs are reproducible, instead of the host's randomness
+	#[clap(long)]
+	pub seed: Option<u64>,
+	/// The clock to serve the time ECALL with
+	#[clap(long, value_enum)]
+	pub clock: Option<ClockKind>,
+	/// The time of the fixed clock, or the start of the virtual clock, in nanoseconds since the
+	/// Unix epoch
+	#[clap(long)]
+	pub timestamp: Option<u64>,
+	/// Write a report of the run to stderr once it ends
+	#[clap(long, value_enum)]
+	pub report: Option<ReportFormat>,
+	/// Run the program even if its manifest asks for systems that aren't configured
+	#[clap(long)]
+	pub ignore_manifest: bool,
+	/// An environment variable to pass to the program, e.g. --env NAME=VALUE
+	#[clap(long = "env", value_parser = parse_env_var)]
+	pub env: Vec<(String, String)>,
+	/// The arguments to pass to the program, after `--`
+	#[clap(last = true)]
+	pub args: Vec<String>,
+	#[cfg(feature = "trace")]
+	#[clap(flatten)]
+	pub trace: trace::TraceArgs,
+}
+
+/// The options which only apply to ELF files, kept out of [RunArgs] so the raw binary and hex file
+/// commands don't accept them.
+#[derive(Clone, Args)]
+#[clap(rename_all = "kebab-case")]
+pub struct ElfLoaderArgs {
+	/// The name of the entrypoint symbol to load
+	#[clap(long, default_value = "_start")]
+	pub entrypoint_symbol_name: String,
+	/// The address to offset the segments and entrypoint of the ELF file by, e.g. 0x1000
+	#[clap(long, default_value = "0", value_parser = parse_address)]
+	pub base_address: u32,
+}
+
+impl ElfLoaderArgs {
+	/// The loader configured by the arguments, passing the program the arguments of the run.
+	pub fn loader(&self, run: &RunArgs) -> Result<Elf32Loader, ElfError> {
+		Ok(Elf32Loader::new(self.entrypoint_symbol_name.clone())
+			.with_base_address(self.base_address)
+			.with_arguments(run.program_arguments()?))
+	}
+}
+
+/// Loads and runs an ELF file.
+#[derive(Parser)]
+#[clap(rename_all = "kebab-case")]
+#[group(skip)]
+pub struct ElfFile {
+	#[clap(flatten)]
+	pub run: RunArgs,
+	#[clap(flatten)]
+	pub loader: ElfLoaderArgs,
+}
+
+impl ElfFile {
+	pub async fn execute(&self) -> Result<RunReport, ElfError> {
+		let run = self.run.configured()?;
+		let loader = self.loader.loader(&run)?;
+
+		// The internals of this function perform the task of
+		// composition.
+		//
+		// The inner loop is monomorphized.
+		run.run_program(|machine| loader.load_elf(machine, &run.path))
+	}
 }
 
 pub struct DebugSystem {
@@ -144,7 +287,7 @@ impl MachineSystem<BOX_MEMORY_SIZE> for DebugSystem {
 	}
 }
 
-impl Elf {
+impl RunArgs {
 	pub fn is_debug(&self) -> bool {
 		self.log_program_counter
 			|| self.log_instructions
@@ -153,36 +296,22 @@ impl Elf {
 			|| self.log_exit_status
 	}
 
-	pub fn ticks<Computer: MachineSystem<BOX_MEMORY_SIZE>>(
-		&self,
-		computer: Computer,
-	) -> Result<TickHandler<BOX_MEMORY_SIZE, Computer>, ElfError> {
-		let tick_handler = TickHandler {
-			inner: computer,
-			current_tick: 0,
-			max_ticks: self.ticks.unwrap_or(u32::MAX),
-		};
-
-		Ok(tick_handler)
-	}
-
+	/// Runs the machine, counting ticks only when they're limited or reported.
 	pub fn maybe_run_ticks<Computer: MachineSystem<BOX_MEMORY_SIZE>>(
 		&self,
 		machine: &mut Machine<BOX_MEMORY_SIZE>,
-		mut computer: Computer,
-	) -> Result<(), ElfError> {
-		if self.ticks.is_some() {
-			let mut tick_handler = self.ticks(computer)?;
-			machine.run(&mut tick_handler)?;
-		} else {
-			machine.run(&mut computer)?;
+		computer: Computer,
+	) -> Result<SystemRun, ElfError> {
+		match (self.ticks, self.report) {
+			(Some(max_ticks), _) => Ok(run_system_with_ticks(machine, computer, max_ticks)),
+			(None, Some(_)) => Ok(run_system_counting_ticks(machine, computer)),
+			(None, None) => Ok(run_system(machine, computer)),
 		}
-
-		Ok(())
 	}
 
-	pub fn lilbug<Computer: LilBugComputer<BOX_MEMORY_SIZE>>(
+	pub fn lilbug<Computer: RuntimeComputer<BOX_MEMORY_SIZE>>(
 		&self,
+		program: &LoadedProgram,
 		computer: Computer,
 	) -> Result<LilBugSystem<BOX_MEMORY_SIZE, Computer>, ElfError> {
 		let lilbug_system = LilBugSystem {
@@ -192,108 +321,564 @@ impl Elf {
 			log_registers: self.log_registers,
 			log_registers_at_end: self.log_registers_at_end,
 			log_exit_status: self.log_exit_status,
+			symbols: program.symbols.clone(),
 		};
 
 		Ok(lilbug_system)
 	}
 
-	pub fn maybe_run_lilbug<Computer: LilBugComputer<BOX_MEMORY_SIZE>>(
+	pub fn maybe_run_lilbug<Computer: RuntimeComputer<BOX_MEMORY_SIZE>>(
 		&self,
 		machine: &mut Machine<BOX_MEMORY_SIZE>,
+		program: &LoadedProgram,
 		computer: Computer,
-	) -> Result<(), ElfError> {
+	) -> Result<SystemRun, ElfError> {
 		if self.is_debug() {
-			let lilbug_system = self.lilbug(computer)?;
-			self.maybe_run_ticks(machine, lilbug_system)?;
+			let lilbug_system = self.lilbug(program, computer)?;
+			self.maybe_run_trace(machine, program, lilbug_system)
 		} else {
-			self.maybe_run_ticks(machine, computer)?;
+			self.maybe_run_trace(machine, program, computer)
 		}
+	}
 
-		Ok(())
+	#[cfg(feature = "trace")]
+	pub fn maybe_run_trace<Computer: MachineSystem<BOX_MEMORY_SIZE>>(
+		&self,
+		machine: &mut Machine<BOX_MEMORY_SIZE>,
+		program: &LoadedProgram,
+		computer: Computer,
+	) -> Result<SystemRun, ElfError> {
+		match &self.trace.trace {
+			Some(trace_path) => {
+				let trace_system = self.trace.trace(trace_path, &program.symbols, computer)?;
+				self.maybe_run_ticks(machine, trace_system)
+			}
+			None => self.maybe_run_ticks(machine, computer),
+		}
 	}
 
-	pub fn run_ecall_machine(
+	#[cfg(not(feature = "trace"))]
+	pub fn maybe_run_trace<Computer: MachineSystem<BOX_MEMORY_SIZE>>(
 		&self,
 		machine: &mut Machine<BOX_MEMORY_SIZE>,
-	) -> Result<(), ElfError> {
-		let inner = InterruptHandler::<
-			BOX_MEMORY_SIZE,
-			Rv32iComputer,
-			EcallDispatcher<
-				BOX_MEMORY_SIZE,
-				ExitSystem<BOX_MEMORY_SIZE>,
-				Option<StdOutputSystem<BOX_MEMORY_SIZE>>,
-				NoopDispatcher<BOX_MEMORY_SIZE>,
-				NoopDispatcher<BOX_MEMORY_SIZE>,
-			>,
-			NoopEbreakDispatcher<BOX_MEMORY_SIZE>,
-		> {
-			inner: Rv32iComputer,
-			ecall_dispatcher: EcallDispatcher {
-				exit_dispatcher: ExitSystem::new(),
-				write_dispatcher: if self.std_output {
-					Some(StdOutputSystem::<BOX_MEMORY_SIZE>)
-				} else {
-					None
-				},
-				open_channel_dispatcher: NoopDispatcher {},
-				check_channel_dispatcher: NoopDispatcher {},
-			},
-			ebreak_dispatcher: NoopEbreakDispatcher {},
-		};
+		_program: &LoadedProgram,
+		computer: Computer,
+	) -> Result<SystemRun, ElfError> {
+		self.maybe_run_ticks(machine, computer)
+	}
 
-		let ecall_machine = EcallMachine { inner };
+	/// Applies the run profile given with `--config`, if any.
+	///
+	/// Options given on the command line take precedence over the profile.
+	pub fn configured(&self) -> Result<Self, ElfError> {
+		let mut run = self.clone();
+
+		if let Some(path) = &self.config {
+			let config = RunConfig::load(path)?;
+			run.memory_size = self.memory_size.or(config.memory_size);
+			run.ticks = self.ticks.or(config.ticks);
+			if let (None, Some(write_systems)) = (self.std_output, &config.write_systems) {
+				run.std_output = Some(write_systems.contains(&WriteSystemKind::Stdout));
+			}
+			if self.channel_systems.is_empty() {
+				run.channel_systems = config.channel_systems;
+			}
+			run.signer_store = self.signer_store.or(config.signer_store);
+			run.seed = self.seed.or(config.seed);
+			run.clock = self.clock.or(config.clock);
+			run.timestamp = self.timestamp.or(config.timestamp);
+		}
 
-		self.maybe_run_lilbug(machine, ecall_machine)?;
+		if let Some(memory_size) = run.memory_size {
+			if memory_size as usize != BOX_MEMORY_SIZE {
+				return Err(ElfError::MemorySizeMismatch(memory_size));
+			}
+		}
 
-		Ok(())
+		Ok(run)
+	}
+
+	/// The standard transaction system, if it was selected.
+	pub fn std_transaction(&self) -> Option<SharedSystem<BoxStdTransaction>> {
+		self.channel_systems.contains(&ChannelSystemKind::StdTransaction).then(|| {
+			let backend = self.signer_store.unwrap_or_default().backend();
+			SharedSystem::new(BoxStdTransaction::new(backend))
+		})
+	}
+
+	/// The write system, serving stdout and stderr and any `--write-file`s.
+	pub fn std_output(&self) -> Result<Option<StdOutputSystem<BOX_MEMORY_SIZE>>, ElfError> {
+		let write_files = self
+			.write_files
+			.iter()
+			.map(|(system_id, path)| {
+				let file = File::create(path)
+					.map_err(|error| ElfError::WriteFileError(path.clone(), error))?;
+				Ok((*system_id, WriteTarget::File(file)))
+			})
+			.collect::<Result<Vec<_>, ElfError>>()?;
+
+		Ok(self.write_system(write_files))
 	}
 
-	pub fn run_noop_ecall_machine(
+	/// The write system serving stdout and stderr, if enabled, and the given write file targets.
+	fn write_system(
 		&self,
-		machine: &mut Machine<BOX_MEMORY_SIZE>,
-	) -> Result<(), ElfError> {
-		let noop_ecall_machine = NoEcallMachine { inner: Rv32iComputer };
+		write_files: Vec<(u32, WriteTarget)>,
+	) -> Option<StdOutputSystem<BOX_MEMORY_SIZE>> {
+		let std_output = self.std_output.unwrap_or(true);
+		if !std_output && write_files.is_empty() {
+			return None;
+		}
+
+		let system = if std_output { StdOutputSystem::new() } else { StdOutputSystem::empty() };
+		Some(
+			write_files
+				.into_iter()
+				.fold(system, |system, (system_id, target)| system.with_target(system_id, target)),
+		)
+	}
+
+	/// The stdin system, serving the `--stdin-file` if one was given.
+	pub fn std_input(&self) -> Result<Option<StdInputSystem<BOX_MEMORY_SIZE>>, ElfError> {
+		if !self.std_input {
+			return Ok(None);
+		}
+
+		match &self.stdin_file {
+			Some(path) => StdInputSystem::from_file(path)
+				.map(Some)
+				.map_err(|error| ElfError::StdinFileError(path.clone(), error)),
+			None => Ok(Some(StdInputSystem::stdin())),
+		}
+	}
+
+	/// The random system, seeded with the `--seed` if one was given.
+	pub fn std_random(&self) -> StdRandomSystem<BOX_MEMORY_SIZE> {
+		match self.seed {
+			Some(seed) => StdRandomSystem::seeded_from_u64(seed),
+			None => StdRandomSystem::os(),
+		}
+	}
+
+	/// The clock system, telling the time with the `--clock` from the `--timestamp`.
+	pub fn std_clock(&self) -> ClockSystem<BOX_MEMORY_SIZE> {
+		let timestamp = self.timestamp.unwrap_or_default();
+		ClockSystem::new(match self.clock.unwrap_or_default() {
+			ClockKind::Wall => Clock::Wall,
+			ClockKind::Fixed => Clock::Fixed(timestamp),
+			ClockKind::Virtual => Clock::Virtual { start: timestamp, nanos_per_instruction: 1 },
+		})
+	}
+
+	pub fn ecall_computer(&self) -> Result<BoxEcallComputer, ElfError> {
+		Ok(self.ecall_computer_with(self.std_output()?, self.std_input()?))
+	}
 
-		self.maybe_run_lilbug(machine, noop_ecall_machine)?;
+	/// The ECALL computer serving the configured systems, without creating the write files or
+	/// opening the stdin file, so programs can be checked against it before the host is touched.
+	pub fn unopened_ecall_computer(&self) -> BoxEcallComputer {
+		let write_files = self
+			.write_files
+			.iter()
+			.map(|(system_id, _)| (*system_id, WriteTarget::Buffer(Vec::new())))
+			.collect();
+		let std_input = self.std_input.then(|| StdInputSystem::from_bytes(Vec::new()));
+
+		self.ecall_computer_with(self.write_system(write_files), std_input)
+	}
+
+	fn ecall_computer_with(
+		&self,
+		std_output: Option<StdOutputSystem<BOX_MEMORY_SIZE>>,
+		std_input: Option<StdInputSystem<BOX_MEMORY_SIZE>>,
+	) -> BoxEcallComputer {
+		Runtime::<BOX_MEMORY_SIZE>::builder()
+			.with_exit()
+			.with_write_system(std_output)
+			.with_read_system(std_input)
+			.with_channel_system(self.std_transaction())
+			.with_random_system(self.std_random())
+			.with_time_system(self.std_clock())
+			.build_computer()
+	}
+
+	/// Runs the program loaded into the runtime through the configured pipeline.
+	pub fn run_runtime<Computer: RuntimeComputer<BOX_MEMORY_SIZE>>(
+		&self,
+		runtime: &mut Runtime<BOX_MEMORY_SIZE, Computer>,
+		program: &LoadedProgram,
+	) -> Result<RunOutcome, ElfError> {
+		let run = self.maybe_run_lilbug(&mut runtime.machine, program, &mut runtime.computer)?;
+		Ok(runtime.outcome(run))
+	}
+
+	/// Checks the requirements in the program's manifest against the configured systems.
+	///
+	/// Nothing on the host is touched, so programs can be checked before their systems are made.
+	pub fn check_manifest(&self, program: &LoadedProgram) -> Result<(), ElfError> {
+		let Some(manifest) = &program.manifest else {
+			return Ok(());
+		};
+		if self.ignore_manifest {
+			return Ok(());
+		}
+		let unsatisfied = |message: String| Err(ElfError::UnsatisfiedManifest(message));
+
+		if manifest.abi_version != fuste_manifest::ABI_VERSION {
+			return unsatisfied(format!(
+				"the program targets ABI version {}, but version {} is implemented",
+				manifest.abi_version,
+				fuste_manifest::ABI_VERSION
+			));
+		}
+
+		if let Some(memory_size) = manifest.memory_size {
+			if memory_size as usize > BOX_MEMORY_SIZE {
+				return unsatisfied(format!(
+					"the program needs {memory_size} bytes of memory, but only {BOX_MEMORY_SIZE} are available"
+				));
+			}
+		}
+
+		if let (Some(stack_size), Some(start), Some(end)) =
+			(manifest.stack_size, program.symbol("_stack_start"), program.symbol("_stack_end"))
+		{
+			let available = end.address.saturating_sub(start.address);
+			if stack_size > available {
+				return unsatisfied(format!(
+					"the program needs {stack_size} bytes of stack, but its linker script reserves {available}"
+				));
+			}
+		}
+
+		let ecall_computer = self.ecalls.then(|| self.unopened_ecall_computer());
+		let dispatcher = ecall_computer.as_ref().map(|computer| &computer.ecall_dispatcher);
+
+		for ecall in &manifest.ecalls {
+			if !dispatcher.is_some_and(|dispatcher| dispatcher.supports_ecall_number(*ecall)) {
+				return unsatisfied(format!("ECALL {ecall} isn't supported"));
+			}
+		}
+
+		for system_id in &manifest.write_systems {
+			if !dispatcher.is_some_and(|dispatcher| dispatcher.supports_write_system(*system_id)) {
+				return unsatisfied(format!("write system 0x{system_id:x} isn't available"));
+			}
+		}
+
+		for system_id in &manifest.channel_systems {
+			if !dispatcher.is_some_and(|dispatcher| dispatcher.supports_channel_system(*system_id))
+			{
+				return unsatisfied(format!("channel system 0x{system_id:x} isn't available"));
+			}
+		}
 
 		Ok(())
 	}
 
-	pub fn maybe_run_ecall_machine(
+	/// Loads and runs the program, then logs and reports how the run ended.
+	pub fn run_program(
 		&self,
-		machine: &mut Machine<BOX_MEMORY_SIZE>,
-	) -> Result<(), ElfError> {
+		load: impl FnOnce(&mut Machine<BOX_MEMORY_SIZE>) -> Result<LoadedProgram, ElfLoaderError>,
+	) -> Result<RunReport, ElfError> {
+		// The program is checked against its manifest before its systems are made, so a program
+		// which is turned away leaves the write files untouched.
+		let mut runtime = Runtime::new(Rv32iComputer);
+		let program = load(runtime.machine_mut())?;
+		self.check_manifest(&program)?;
+
 		// Note we use inner construction because we don't want to
 		// wrap in an enum and have lots of inner matching
 		// on the branches for every tick.
 		if self.ecalls {
-			self.run_ecall_machine(machine)?;
+			let mut runtime = runtime.with_computer(self.ecall_computer()?);
+			self.run_loaded(&mut runtime, &program)
 		} else {
-			self.run_noop_ecall_machine(machine)?;
+			self.run_loaded(&mut runtime, &program)
+		}
+	}
+
+	/// Runs the program loaded into the runtime, then logs and reports how the run ended.
+	pub fn run_loaded<Computer: BoxComputer>(
+		&self,
+		runtime: &mut Runtime<BOX_MEMORY_SIZE, Computer>,
+		program: &LoadedProgram,
+	) -> Result<RunReport, ElfError> {
+		let outcome = self.run_runtime(runtime, program)?;
+		let report = RunReport::new(outcome, runtime.computer.stdout_bytes());
+		self.finish(program, &report)?;
+
+		Ok(report)
+	}
+
+	/// Logs a fault or the message the program aborted with, and writes the report, if one was
+	/// asked for.
+	pub fn finish(&self, program: &LoadedProgram, report: &RunReport) -> Result<(), ElfError> {
+		if let Some(abort_message) = &report.outcome.abort_message {
+			eprintln!("{abort_message}");
+		}
+		if let Termination::Fault(error) = &report.outcome.termination {
+			eprintln!(
+				"Error: The machine faulted at {}: {error}",
+				program.symbolize(report.outcome.program_counter)
+			);
+		}
+
+		match self.report {
+			Some(ReportFormat::Json) => eprintln!("{}", report.to_json()?),
+			None => (),
 		}
 
 		Ok(())
 	}
 
-	pub async fn execute(&self) -> Result<(), ElfError> {
-		// Initialize the machine and loader
-		let loader = Elf32Loader::new(self.entrypoint_symbol_name.clone());
-		let mut machine = Machine::<BOX_MEMORY_SIZE>::new();
+	/// The arguments, environment and auxiliary vector laid out on the program's stack.
+	///
+	/// The program name is the path of the ELF file. With a `--seed` the auxiliary vector's random
+	/// bytes come from the seed too, so the whole run is reproducible.
+	pub fn program_arguments(&self) -> Result<ProgramArguments, ElfError> {
+		let mut args = vec![self.path.display().to_string()];
+		args.extend(self.args.iter().cloned());
+
+		let mut random_bytes = [0; 16];
+		match self.seed {
+			Some(seed) => fill_from_seed(seed_from_u64(seed), AUXV_RANDOM_NONCE, &mut random_bytes),
+			None => getrandom::getrandom(&mut random_bytes)?,
+		}
+
+		Ok(ProgramArguments::new(args, self.env.clone())
+			.with_auxv(AuxiliaryVector { page_size: PAGE_SIZE, random_bytes }))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use fuste_manifest::Manifest;
+	use fuste_riscv_elf::symbols::{Symbol, SymbolTable};
+	use std::error::Error;
+	use std::fs;
 
-		// Load the ELF file into the machine
-		loader.load_elf(&mut machine, &self.path)?;
+	/// The random ECALL, served in every run with ECALLs.
+	const RANDOM_ECALL: u32 = 278;
 
-		// Note we use inner construction because we don't want to
-		// wrap in an enum and have lots of inner matching
-		// on the branches for every tick.
-		//
-		// The internals of this function perform the task of
-		// composition.
-		//
-		// The inner loop is monomorphized.
-		self.maybe_run_ecall_machine(&mut machine)?;
+	/// The transaction signer channel the standard transaction system serves.
+	const SIGNER_CHANNEL_SYSTEM: u32 = 0x516d;
+
+	fn parse(args: &[&str]) -> Result<RunArgs, clap::Error> {
+		RunArgs::try_parse_from([&["elf", "--path", "program.elf"], args].concat())
+	}
+
+	fn program(manifest: Manifest) -> LoadedProgram {
+		LoadedProgram { manifest: Some(manifest), ..Default::default() }
+	}
+
+	fn unsatisfied(run: &RunArgs, manifest: Manifest) -> Option<String> {
+		match run.check_manifest(&program(manifest)) {
+			Err(ElfError::UnsatisfiedManifest(message)) => Some(message),
+			_ => None,
+		}
+	}
+
+	#[test]
+	fn test_check_manifest_abi_version() -> Result<(), clap::Error> {
+		let run = parse(&[])?;
+		let manifest =
+			Manifest { abi_version: fuste_manifest::ABI_VERSION + 1, ..Default::default() };
+
+		assert!(run.check_manifest(&LoadedProgram::default()).is_ok());
+		assert!(run.check_manifest(&program(Manifest::default())).is_ok());
+		assert_eq!(
+			unsatisfied(&run, manifest),
+			Some(format!(
+				"the program targets ABI version {}, but version {} is implemented",
+				fuste_manifest::ABI_VERSION + 1,
+				fuste_manifest::ABI_VERSION
+			))
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn test_check_manifest_memory_size() -> Result<(), clap::Error> {
+		let memory =
+			|size: usize| Manifest { memory_size: Some(size as u32), ..Default::default() };
+
+		let run = parse(&[])?;
+		assert!(run.check_manifest(&program(memory(BOX_MEMORY_SIZE))).is_ok());
+		assert_eq!(
+			unsatisfied(&run, memory(BOX_MEMORY_SIZE + 1)),
+			Some(format!(
+				"the program needs {} bytes of memory, but only {BOX_MEMORY_SIZE} are available",
+				BOX_MEMORY_SIZE + 1
+			))
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn test_check_manifest_stack_size() -> Result<(), clap::Error> {
+		let run = parse(&[])?;
+		let symbol = |name: &str, address| Symbol { name: name.to_string(), address, size: 0 };
+		let stack = |size| LoadedProgram {
+			symbols: SymbolTable::new(vec![
+				symbol("_stack_start", 0x1000),
+				symbol("_stack_end", 0x2000),
+			]),
+			manifest: Some(Manifest { stack_size: Some(size), ..Default::default() }),
+			..Default::default()
+		};
+
+		assert!(run.check_manifest(&stack(0x1000)).is_ok());
+		assert!(matches!(
+			run.check_manifest(&stack(0x1001)),
+			Err(ElfError::UnsatisfiedManifest(message))
+				if message == "the program needs 4097 bytes of stack, but its linker script reserves 4096"
+		));
+		// Without the linker script's symbols the stack can't be checked.
+		assert!(run
+			.check_manifest(&program(Manifest { stack_size: Some(0x1001), ..Default::default() }))
+			.is_ok());
+		Ok(())
+	}
+
+	#[test]
+	fn test_check_manifest_ecalls() -> Result<(), clap::Error> {
+		let ecalls = |ecalls| Manifest { ecalls, ..Default::default() };
+
+		let mut run = parse(&[])?;
+		assert!(run.check_manifest(&program(ecalls(vec![RANDOM_ECALL]))).is_ok());
+		assert_eq!(
+			unsatisfied(&run, ecalls(vec![RANDOM_ECALL, 0x999])),
+			Some("ECALL 2457 isn't supported".to_string())
+		);
+
+		run.ecalls = false;
+		assert_eq!(
+			unsatisfied(&run, ecalls(vec![RANDOM_ECALL])),
+			Some("ECALL 278 isn't supported".to_string())
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn test_check_manifest_write_systems() -> Result<(), Box<dyn Error>> {
+		let write_systems = |write_systems| Manifest { write_systems, ..Default::default() };
+		let path = std::env::temp_dir().join(format!("fubox-unopened-{}", std::process::id()));
+
+		let run = parse(&[])?;
+		assert!(run.check_manifest(&program(write_systems(vec![1, 2]))).is_ok());
+		assert_eq!(
+			unsatisfied(&run, write_systems(vec![3])),
+			Some("write system 0x3 isn't available".to_string())
+		);
+
+		let run =
+			parse(&["--std-output", "false", "--write-file", &format!("3={}", path.display())])?;
+		assert!(run.check_manifest(&program(write_systems(vec![3]))).is_ok());
+		assert!(unsatisfied(&run, write_systems(vec![1])).is_some());
+		// Checking doesn't create the write file.
+		assert!(!path.exists());
+		Ok(())
+	}
+
+	#[test]
+	fn test_check_manifest_channel_systems() -> Result<(), clap::Error> {
+		let manifest =
+			|| Manifest { channel_systems: vec![SIGNER_CHANNEL_SYSTEM], ..Default::default() };
+
+		let run = parse(&[])?;
+		assert_eq!(
+			unsatisfied(&run, manifest()),
+			Some("channel system 0x516d isn't available".to_string())
+		);
+
+		let run = parse(&["--channel-system", "std-transaction"])?;
+		assert!(run.check_manifest(&program(manifest())).is_ok());
+		Ok(())
+	}
+
+	#[test]
+	fn test_configured_prefers_the_command_line() -> Result<(), Box<dyn Error>> {
+		let profile = config::tests::TempProfile::new(
+			"precedence.toml",
+			&format!("memory_size = {BOX_MEMORY_SIZE}\nticks = 100\nwrite_systems = []\nseed = 7"),
+		)?;
+		let config = format!("--config={}", profile.0.display());
+
+		let run = parse(&[&config])?.configured()?;
+		assert_eq!(run.memory_size, Some(BOX_MEMORY_SIZE as u32));
+		assert_eq!(run.ticks, Some(100));
+		assert_eq!(run.std_output, Some(false));
+		assert_eq!(run.seed, Some(7));
+
+		let run = parse(&[&config, "--ticks", "5", "--std-output", "--seed", "9"])?.configured()?;
+		assert_eq!(run.memory_size, Some(BOX_MEMORY_SIZE as u32));
+		assert_eq!(run.ticks, Some(5));
+		assert_eq!(run.std_output, Some(true));
+		assert_eq!(run.seed, Some(9));
+		Ok(())
+	}
+
+	#[test]
+	fn test_configured_rejects_memory_other_than_the_box() -> Result<(), clap::Error> {
+		for memory_size in [4096, BOX_MEMORY_SIZE + 1] {
+			let run = parse(&["--memory-size", &memory_size.to_string()])?;
+			assert!(matches!(run.configured(), Err(ElfError::MemorySizeMismatch(_))));
+		}
+
+		let run = parse(&["--memory-size", &BOX_MEMORY_SIZE.to_string()])?;
+		assert!(run.configured().is_ok());
+		Ok(())
+	}
+
+	#[test]
+	fn test_seeded_auxv_random_bytes() -> Result<(), Box<dyn Error>> {
+		let random_bytes = |args: &[&str]| -> Result<[u8; 16], Box<dyn Error>> {
+			let arguments = parse(args)?.program_arguments()?;
+			Ok(arguments.auxv.ok_or("no auxiliary vector")?.random_bytes)
+		};
+
+		assert_eq!(random_bytes(&["--seed", "7"])?, random_bytes(&["--seed", "7"])?);
+		assert_ne!(random_bytes(&["--seed", "7"])?, random_bytes(&["--seed", "8"])?);
+
+		// They're apart from the bytes the program draws with the random ECALL.
+		let mut drawn = [0; 16];
+		fill_from_seed(seed_from_u64(7), [0; 12], &mut drawn);
+		assert_ne!(random_bytes(&["--seed", "7"])?, drawn);
+		Ok(())
+	}
+
+	#[test]
+	fn test_ignore_manifest() -> Result<(), clap::Error> {
+		let run = parse(&["--ignore-manifest"])?;
+		let manifest = Manifest {
+			abi_version: fuste_manifest::ABI_VERSION + 1,
+			memory_size: Some(BOX_MEMORY_SIZE as u32 + 1),
+			ecalls: vec![0x999],
+			write_systems: vec![3],
+			channel_systems: vec![SIGNER_CHANNEL_SYSTEM],
+			..Default::default()
+		};
+
+		assert!(run.check_manifest(&program(manifest)).is_ok());
+		Ok(())
+	}
+
+	#[test]
+	fn test_rejected_program_leaves_write_files_untouched() -> Result<(), Box<dyn Error>> {
+		let path = std::env::temp_dir().join(format!("fubox-write-file-{}", std::process::id()));
+		fs::write(&path, "kept")?;
+		let run = parse(&["--write-file", &format!("3={}", path.display())])?;
+		let manifest = Manifest { channel_systems: vec![0x10], ..Default::default() };
+
+		let result = run.run_program(|_| Ok(program(manifest)));
+		let contents = fs::read_to_string(&path);
+		fs::remove_file(&path)?;
 
+		assert!(matches!(result, Err(ElfError::UnsatisfiedManifest(_))));
+		assert_eq!(contents?, "kept");
 		Ok(())
 	}
 }
@@ -0,0 +1,169 @@
+use super::systems::{ChannelSystemKind, ClockKind, SignerStoreKind, WriteSystemKind};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+	#[error("Failed to read the run profile: {0}")]
+	Io(#[from] std::io::Error),
+	#[error("Failed to parse the run profile as TOML: {0}")]
+	Toml(#[from] toml::de::Error),
+	#[error("Failed to parse the run profile as JSON: {0}")]
+	Json(#[from] serde_json::Error),
+	#[error("The run profile {0} should be a .toml or .json file")]
+	UnknownFormat(PathBuf),
+}
+
+/// A run profile selecting the systems fubox assembles, e.g.
+///
+/// ```toml
+/// memory_size = 2097152
+/// ticks = 100000
+/// write_systems = ["stdout"]
+/// channel_systems = ["std-transaction"]
+/// signer_store = "memory"
+/// seed = 7
+/// clock = "fixed"
+/// timestamp = 1700000000000000000
+/// ```
+///
+/// Options given on the command line take precedence over the profile.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RunConfig {
+	/// The memory the program runs with, in bytes, which must equal the box's memory.
+	pub memory_size: Option<u32>,
+	/// The number of ticks to run the machine for.
+	pub ticks: Option<u32>,
+	/// The write systems to serve, unless `--std-output` is given.
+	pub write_systems: Option<Vec<WriteSystemKind>>,
+	/// The channel systems to serve.
+	#[serde(default)]
+	pub channel_systems: Vec<ChannelSystemKind>,
+	/// The backend of the standard transaction system's signer store.
+	pub signer_store: Option<SignerStoreKind>,
+	/// The seed of the random system's stream, which otherwise draws from the host.
+	pub seed: Option<u64>,
+	/// The clock the time ECALL is served with.
+	pub clock: Option<ClockKind>,
+	/// The time of the fixed clock, or the start of the virtual clock, in nanoseconds.
+	pub timestamp: Option<u64>,
+}
+
+impl RunConfig {
+	/// Reads a run profile, telling TOML and JSON apart by the file extension.
+	pub fn load(path: &Path) -> Result<Self, ConfigError> {
+		let text = fs::read_to_string(path)?;
+		match path.extension().and_then(|extension| extension.to_str()) {
+			Some("toml") => Ok(toml::from_str(&text)?),
+			Some("json") => Ok(serde_json::from_str(&text)?),
+			_ => Err(ConfigError::UnknownFormat(path.to_path_buf())),
+		}
+	}
+}
+
+#[cfg(test)]
+pub mod tests {
+	use super::*;
+
+	/// Writes the profile to a file of its own with the extension, removed once dropped.
+	pub struct TempProfile(pub PathBuf);
+
+	impl TempProfile {
+		pub fn new(name: &str, contents: &str) -> std::io::Result<Self> {
+			let path =
+				std::env::temp_dir().join(format!("fubox-profile-{}-{name}", std::process::id()));
+			fs::write(&path, contents)?;
+			Ok(Self(path))
+		}
+	}
+
+	impl Drop for TempProfile {
+		fn drop(&mut self) {
+			let _ = fs::remove_file(&self.0);
+		}
+	}
+
+	fn expected() -> RunConfig {
+		RunConfig {
+			memory_size: Some(4096),
+			ticks: Some(100),
+			write_systems: Some(vec![WriteSystemKind::Stdout]),
+			channel_systems: vec![ChannelSystemKind::StdTransaction],
+			signer_store: Some(SignerStoreKind::Memory),
+			seed: Some(7),
+			clock: Some(ClockKind::Fixed),
+			timestamp: Some(1_700_000_000_000_000_000),
+		}
+	}
+
+	#[test]
+	fn test_load_toml() -> Result<(), Box<dyn std::error::Error>> {
+		let profile = TempProfile::new(
+			"load.toml",
+			r#"
+				memory_size = 4096
+				ticks = 100
+				write_systems = ["stdout"]
+				channel_systems = ["std-transaction"]
+				signer_store = "memory"
+				seed = 7
+				clock = "fixed"
+				timestamp = 1700000000000000000
+			"#,
+		)?;
+
+		assert_eq!(RunConfig::load(&profile.0)?, expected());
+		Ok(())
+	}
+
+	#[test]
+	fn test_load_json() -> Result<(), Box<dyn std::error::Error>> {
+		let profile = TempProfile::new(
+			"load.json",
+			r#"{
+				"memory_size": 4096,
+				"ticks": 100,
+				"write_systems": ["stdout"],
+				"channel_systems": ["std-transaction"],
+				"signer_store": "memory",
+				"seed": 7,
+				"clock": "fixed",
+				"timestamp": 1700000000000000000
+			}"#,
+		)?;
+
+		assert_eq!(RunConfig::load(&profile.0)?, expected());
+		Ok(())
+	}
+
+	#[test]
+	fn test_load_empty() -> Result<(), Box<dyn std::error::Error>> {
+		let profile = TempProfile::new("empty.toml", "")?;
+
+		assert_eq!(RunConfig::load(&profile.0)?, RunConfig::default());
+		Ok(())
+	}
+
+	#[test]
+	fn test_load_unknown_extension() -> std::io::Result<()> {
+		let profile = TempProfile::new("unknown.yaml", "ticks: 100")?;
+
+		assert!(matches!(
+			RunConfig::load(&profile.0),
+			Err(ConfigError::UnknownFormat(path)) if path == profile.0
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn test_load_rejects_unknown_fields() -> std::io::Result<()> {
+		let toml = TempProfile::new("unknown-field.toml", "tick = 100")?;
+		let json = TempProfile::new("unknown-field.json", r#"{ "tick": 100 }"#)?;
+
+		assert!(matches!(RunConfig::load(&toml.0), Err(ConfigError::Toml(_))));
+		assert!(matches!(RunConfig::load(&json.0), Err(ConfigError::Json(_))));
+		Ok(())
+	}
+}
@@ -0,0 +1,83 @@
+use super::report::RunReport;
+use super::{BoxComputer, ElfError, ElfLoaderArgs, RunArgs, BOX_MEMORY_SIZE};
+use fuste_exit::ExitStatus;
+use fuste_riscv_core::{
+	machine::{Machine, MachineError, MachineSystem},
+	plugins::rv32i_computer::Rv32iComputer,
+};
+use fuste_riscv_elf::program::LoadedProgram;
+use fuste_runtime::{Runtime, RuntimeComputer};
+use std::ops::ControlFlow;
+
+/// A system wrapped around the program's computer to watch it run, e.g. to profile it.
+pub trait Observer: MachineSystem<BOX_MEMORY_SIZE> {
+	type Computer: BoxComputer;
+
+	/// The computer being watched.
+	fn computer(&self) -> &Self::Computer;
+}
+
+/// Lends an [Observer] to the run pipeline as the program's computer.
+pub struct Observed<O: Observer>(pub O);
+
+impl<O: Observer> MachineSystem<BOX_MEMORY_SIZE> for Observed<O> {
+	#[inline(always)]
+	fn tick(
+		&mut self,
+		machine: &mut Machine<BOX_MEMORY_SIZE>,
+	) -> Result<ControlFlow<()>, MachineError> {
+		self.0.tick(machine)
+	}
+}
+
+impl<O: Observer> RuntimeComputer<BOX_MEMORY_SIZE> for Observed<O> {
+	fn exit_status(&self) -> ExitStatus {
+		self.0.computer().exit_status()
+	}
+
+	fn abort_message(&self) -> Option<String> {
+		self.0.computer().abort_message()
+	}
+
+	fn return_data(&self) -> Option<Vec<u8>> {
+		self.0.computer().return_data()
+	}
+}
+
+impl<O: Observer> BoxComputer for Observed<O> {
+	fn stdout_bytes(&self) -> usize {
+		self.0.computer().stdout_bytes()
+	}
+}
+
+/// A run watched by an [Observer].
+pub struct ObservedRun<O> {
+	pub program: LoadedProgram,
+	pub observer: O,
+	/// How the run ended, or the error which ended it early.
+	pub report: Result<RunReport, ElfError>,
+}
+
+impl RunArgs {
+	/// Loads the ELF file and runs it with an observer wrapped around its computer.
+	///
+	/// The observer is handed back even if the run fails, so what it saw up to the failure can
+	/// still be written. As in [RunArgs::run_program], the program is checked against its manifest
+	/// before its systems are made.
+	pub fn run_observed<Computer: BoxComputer, O: Observer<Computer = Computer>>(
+		&self,
+		loader: &ElfLoaderArgs,
+		computer: impl FnOnce() -> Result<Computer, ElfError>,
+		observe: impl FnOnce(Computer) -> O,
+	) -> Result<ObservedRun<O>, ElfError> {
+		let loader = loader.loader(self)?;
+		let mut runtime = Runtime::new(Rv32iComputer);
+		let program = loader.load_elf(runtime.machine_mut(), &self.path)?;
+		self.check_manifest(&program)?;
+
+		let mut runtime = runtime.with_computer(Observed(observe(computer()?)));
+		let report = self.run_loaded(&mut runtime, &program);
+
+		Ok(ObservedRun { program, observer: runtime.computer.0, report })
+	}
+}
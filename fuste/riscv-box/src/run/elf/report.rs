@@ -0,0 +1,164 @@
+use clap::ValueEnum;
+use fuste_exit::ExitStatus;
+use fuste_riscv_core::machine::MachineError;
+use fuste_runtime::{RunOutcome, Termination};
+use serde::Serialize;
+use std::process::ExitCode;
+
+/// The process exit code when the program used up its ticks without exiting.
+pub const TICKS_EXHAUSTED_EXIT_CODE: u8 = 124;
+/// The process exit code when the machine faulted while running the program.
+pub const FAULT_EXIT_CODE: u8 = 125;
+/// The process exit code when fubox itself failed, e.g. to load the program.
+pub const ERROR_EXIT_CODE: u8 = 126;
+
+/// The formats a run report can be written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+	Json,
+}
+
+/// What happened during a run, as reported by `--report` and the process exit code.
+#[derive(Debug)]
+pub struct RunReport {
+	pub outcome: RunOutcome,
+	/// The number of bytes the program wrote to stdout.
+	pub stdout_bytes: usize,
+}
+
+#[derive(Serialize)]
+struct JsonFault {
+	kind: &'static str,
+	message: String,
+}
+
+#[derive(Serialize)]
+struct JsonReport {
+	termination: &'static str,
+	exit_status: Option<&'static str>,
+	/// The program's exit code, which `exit_code` only holds when it fits in a byte and isn't
+	/// one of fubox's own codes.
+	status_code: Option<u32>,
+	exit_code: u8,
+	ticks: Option<u64>,
+	program_counter: u32,
+	fault: Option<JsonFault>,
+	abort_message: Option<String>,
+	/// The data the program handed back, in hexadecimal.
+	return_data: Option<String>,
+	stdout_bytes: usize,
+}
+
+fn exit_status_name(status: &ExitStatus) -> &'static str {
+	match status {
+		ExitStatus::Success => "success",
+		ExitStatus::Error => "error",
+		ExitStatus::Terminated => "terminated",
+		ExitStatus::Unsupported => "unsupported",
+		ExitStatus::Code(_) => "code",
+	}
+}
+
+fn hex(bytes: &[u8]) -> String {
+	bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+impl RunReport {
+	pub fn new(outcome: RunOutcome, stdout_bytes: usize) -> Self {
+		Self { outcome, stdout_bytes }
+	}
+
+	/// The process exit code for the run.
+	///
+	/// An exited program's status is its exit code, so success is 0. Running out of ticks,
+	/// faulting and failing in fubox have their own codes, 124 to 126, so a program exiting with
+	/// one of those, or with a code which doesn't fit in a process exit code, exits as an error
+	/// rather than being mistaken for them or wrapping around, e.g. to 0.
+	pub fn exit_code(&self) -> u8 {
+		match &self.outcome.termination {
+			Termination::Exited(status) => match u8::try_from(status.clone().to_u32()) {
+				Ok(code) if !(TICKS_EXHAUSTED_EXIT_CODE..=ERROR_EXIT_CODE).contains(&code) => code,
+				_ => ExitStatus::Error.to_u32() as u8,
+			},
+			Termination::TicksExhausted => TICKS_EXHAUSTED_EXIT_CODE,
+			Termination::Fault(_) => FAULT_EXIT_CODE,
+		}
+	}
+
+	pub fn process_exit_code(&self) -> ExitCode {
+		ExitCode::from(self.exit_code())
+	}
+
+	pub fn to_json(&self) -> Result<String, serde_json::Error> {
+		let (termination, exit_status, fault) = match &self.outcome.termination {
+			Termination::Exited(status) => ("exited", Some(exit_status_name(status)), None),
+			Termination::TicksExhausted => ("ticks_exhausted", None, None),
+			Termination::Fault(error) => {
+				let kind = match error {
+					MachineError::MemoryError(_) => "memory",
+					MachineError::InstructionError(_) => "instruction",
+					MachineError::SystemError(_) => "system",
+				};
+				("fault", None, Some(JsonFault { kind, message: error.to_string() }))
+			}
+		};
+
+		let report = JsonReport {
+			termination,
+			exit_status,
+			status_code: match &self.outcome.termination {
+				Termination::Exited(status) => Some(status.clone().to_u32()),
+				_ => None,
+			},
+			exit_code: self.exit_code(),
+			ticks: self.outcome.ticks,
+			program_counter: self.outcome.program_counter,
+			fault,
+			abort_message: self.outcome.abort_message.clone(),
+			return_data: self.outcome.return_data.as_deref().map(hex),
+			stdout_bytes: self.stdout_bytes,
+		};
+		serde_json::to_string(&report)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn report(termination: Termination) -> RunReport {
+		let outcome = RunOutcome {
+			termination,
+			ticks: None,
+			program_counter: 0,
+			abort_message: None,
+			return_data: None,
+		};
+		RunReport::new(outcome, 0)
+	}
+
+	fn exited_with(code: u32) -> u8 {
+		report(Termination::Exited(ExitStatus::from_u32(code))).exit_code()
+	}
+
+	#[test]
+	fn test_exit_code() {
+		assert_eq!(exited_with(0), 0);
+		assert_eq!(exited_with(42), 42);
+		assert_eq!(exited_with(123), 123);
+		assert_eq!(exited_with(127), 127);
+		assert_eq!(exited_with(256), 1);
+		assert_eq!(report(Termination::TicksExhausted).exit_code(), TICKS_EXHAUSTED_EXIT_CODE);
+		assert_eq!(
+			report(Termination::Fault(MachineError::SystemError("fault"))).exit_code(),
+			FAULT_EXIT_CODE
+		);
+	}
+
+	#[test]
+	fn test_exit_code_reserves_fubox_codes() {
+		assert_eq!(exited_with(TICKS_EXHAUSTED_EXIT_CODE.into()), 1);
+		assert_eq!(exited_with(FAULT_EXIT_CODE.into()), 1);
+		assert_eq!(exited_with(ERROR_EXIT_CODE.into()), 1);
+	}
+}
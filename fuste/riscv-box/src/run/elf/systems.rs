@@ -0,0 +1,104 @@
+use clap::ValueEnum;
+use fuste_std_transaction_system::{
+	containers::{HartIndex, UserSignerIndex},
+	signer_stores::{
+		MemorySignerStoreBackend, NoopSignerStoreBackend, SignerStoreBackend,
+		SignerStoreBackendError,
+	},
+	std_transaction::StdTransaction,
+};
+use serde::Deserialize;
+
+/// The write systems that can be selected for a run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WriteSystemKind {
+	/// Writes to the host's stdout and stderr
+	Stdout,
+}
+
+/// The channel systems that can be selected for a run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChannelSystemKind {
+	/// The standard transaction system, serving the transaction signer channels
+	StdTransaction,
+}
+
+/// The clocks the time ECALL can be served with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ClockKind {
+	/// The host's wall clock
+	#[default]
+	Wall,
+	/// Always the `--timestamp`, like a transaction's timestamp
+	Fixed,
+	/// Starts at the `--timestamp` and passes a nanosecond for each instruction executed
+	Virtual,
+}
+
+/// The signer store backends that can be selected for the standard transaction system.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SignerStoreKind {
+	/// Stores nothing and reads nothing back
+	#[default]
+	Noop,
+	/// Keeps stored values in memory for the length of the run
+	Memory,
+}
+
+impl SignerStoreKind {
+	pub fn backend(self) -> BoxSignerStore {
+		match self {
+			SignerStoreKind::Noop => BoxSignerStore::Noop(NoopSignerStoreBackend),
+			SignerStoreKind::Memory => BoxSignerStore::Memory(MemorySignerStoreBackend::default()),
+		}
+	}
+}
+
+/// The signer store backend chosen for a run.
+#[derive(Debug)]
+pub enum BoxSignerStore {
+	Noop(NoopSignerStoreBackend),
+	Memory(MemorySignerStoreBackend),
+}
+
+impl SignerStoreBackend for BoxSignerStore {
+	fn write(
+		&self,
+		hart_index: &HartIndex,
+		user_signer_index: &UserSignerIndex,
+		type_bytes: &[u8],
+		value_bytes: &[u8],
+	) -> Result<(), SignerStoreBackendError> {
+		match self {
+			BoxSignerStore::Noop(backend) => {
+				backend.write(hart_index, user_signer_index, type_bytes, value_bytes)
+			}
+			BoxSignerStore::Memory(backend) => {
+				backend.write(hart_index, user_signer_index, type_bytes, value_bytes)
+			}
+		}
+	}
+
+	fn read(
+		&self,
+		hart_index: &HartIndex,
+		user_signer_index: &UserSignerIndex,
+		type_bytes: &[u8],
+	) -> Result<Option<Vec<u8>>, SignerStoreBackendError> {
+		match self {
+			BoxSignerStore::Noop(backend) => {
+				backend.read(hart_index, user_signer_index, type_bytes)
+			}
+			BoxSignerStore::Memory(backend) => {
+				backend.read(hart_index, user_signer_index, type_bytes)
+			}
+		}
+	}
+}
+
+/// The standard transaction system with the sizes used by `SignerStoreSystem::canonical`.
+pub type BoxStdTransaction = StdTransaction<32, 32, 16, 128, 16384, BoxSignerStore>;
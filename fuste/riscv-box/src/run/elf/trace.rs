@@ -0,0 +1,78 @@
+use super::parse_address;
+use clap::{Args, ValueEnum};
+use fuste_riscv_core::machine::MachineSystem;
+use fuste_riscv_elf::symbols::SymbolTable;
+use fuste_trace::{BinarySink, JsonLinesSink, TraceError, TraceFilter, TraceSink, TraceSystem};
+use std::fs::File;
+use std::io::BufWriter;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+#[clap(rename_all = "kebab-case")]
+pub enum TraceFormat {
+	/// One JSON object per instruction
+	JsonLines,
+	/// Compact little-endian records
+	Binary,
+}
+
+#[derive(Clone, Args)]
+#[clap(rename_all = "kebab-case")]
+pub struct TraceArgs {
+	/// Write an execution trace to the given path
+	#[clap(long)]
+	pub trace: Option<PathBuf>,
+	/// The format of the execution trace
+	#[clap(long, value_enum, default_value = "json-lines")]
+	pub trace_format: TraceFormat,
+	/// Only trace program counters in the given range, e.g. 0x1000..0x1100
+	#[clap(long, value_parser = parse_pc_range)]
+	pub trace_pc_range: Vec<Range<u32>>,
+	/// Only trace program counters within the given symbol
+	#[clap(long)]
+	pub trace_symbol: Vec<String>,
+}
+
+fn parse_pc_range(value: &str) -> Result<Range<u32>, String> {
+	let (start, end) = value
+		.split_once("..")
+		.ok_or_else(|| format!("expected START..END, got \"{value}\""))?;
+	let parse = |address: &str| {
+		parse_address(address).map_err(|e| format!("invalid address \"{address}\": {e}"))
+	};
+	Ok(parse(start)?..parse(end)?)
+}
+
+impl TraceArgs {
+	pub fn filter(&self, symbols: &SymbolTable) -> Result<TraceFilter, TraceError> {
+		let mut filter = TraceFilter::all();
+		for range in &self.trace_pc_range {
+			filter = filter.with_pc_range(range.clone());
+		}
+
+		for name in &self.trace_symbol {
+			filter = filter.with_symbol(symbols, name)?;
+		}
+
+		Ok(filter)
+	}
+
+	pub fn sink(&self, trace_path: &Path) -> Result<Box<dyn TraceSink>, TraceError> {
+		let writer = BufWriter::new(File::create(trace_path)?);
+		Ok(match self.trace_format {
+			TraceFormat::JsonLines => Box::new(JsonLinesSink::new(writer)),
+			TraceFormat::Binary => Box::new(BinarySink::new(writer)),
+		})
+	}
+
+	/// Wraps the computer in a [TraceSystem] writing to the configured trace path.
+	pub fn trace<const MEMORY_SIZE: usize, Computer: MachineSystem<MEMORY_SIZE>>(
+		&self,
+		trace_path: &Path,
+		symbols: &SymbolTable,
+		computer: Computer,
+	) -> Result<TraceSystem<MEMORY_SIZE, Computer, Box<dyn TraceSink>>, TraceError> {
+		Ok(TraceSystem::new(computer, self.sink(trace_path)?, self.filter(symbols)?))
+	}
+}
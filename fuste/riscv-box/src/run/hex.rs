@@ -0,0 +1,39 @@
+use crate::run::elf::report::RunReport;
+use crate::run::elf::{parse_address, ElfError as RunElfError, RunArgs};
+use clap::Parser;
+use fuste_riscv_elf::hex::HexLoader;
+
+#[derive(Debug, thiserror::Error)]
+pub enum HexError {
+	#[error("Encountered an error while running the program: {0}")]
+	RunError(#[from] RunElfError),
+}
+
+#[derive(Parser)]
+#[clap(rename_all = "kebab-case")]
+#[group(skip)]
+pub struct Hex {
+	#[clap(flatten)]
+	pub run: RunArgs,
+	/// The address to start executing at, defaults to the file's start address record
+	#[clap(long, value_parser = parse_address)]
+	pub entry: Option<u32>,
+}
+
+impl Hex {
+	/// The loader configured by the arguments.
+	pub fn loader(&self) -> Result<HexLoader, HexError> {
+		let loader = HexLoader::new().with_arguments(self.run.program_arguments()?);
+		Ok(match self.entry {
+			Some(entry) => loader.with_entrypoint(entry),
+			None => loader,
+		})
+	}
+
+	pub async fn execute(&self) -> Result<RunReport, HexError> {
+		let run = self.run.configured()?;
+		let loader = self.loader()?;
+
+		Ok(run.run_program(|machine| loader.load_hex(machine, &self.run.path))?)
+	}
+}
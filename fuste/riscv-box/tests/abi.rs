@@ -0,0 +1,321 @@
+//! Runs guests built against the `fuste` environment through `fubox`, checking the ECALLs they
+//! make are served as `fuste-abi` lays them out.
+//!
+//! The guests are vendored under `tests/abi`. Whenever a source they're built from changes, rebuild
+//! them in the same commit, from `fuste/tests/toolchain`, with
+//!
+//! ```sh
+//! cargo build --release -p abi-conformance
+//! cp target/riscv32i-ramate-fuste-elf/release/abi-conformance ../../riscv-box/tests/abi/abi-conformance.elf
+//! ```
+//!
+//! then record the sources they were built from with
+//! `FUSTE_BLESS_GUESTS=1 cargo test -p fubox --test abi test_guests_are_built_from_their_sources`.
+//! That test fails while a guest is older than its sources.
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+fn guest(name: &str) -> PathBuf {
+	Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/abi").join(name)
+}
+
+/// The files outside `fuste/env` which the guests are built from, relative to `fuste/tests/toolchain`.
+const TOOLCHAIN_SOURCES: [&str; 5] = [
+	"abi-conformance",
+	"Cargo.toml",
+	".cargo/config.toml",
+	"riscv32i-ramate-fuste-elf.json",
+	"linker.lld",
+];
+
+/// Collects the files under the path, skipping build output.
+fn source_files(path: &Path, files: &mut Vec<PathBuf>) -> Result<(), Box<dyn Error>> {
+	if path.is_file() {
+		files.push(path.to_path_buf());
+		return Ok(());
+	}
+	for entry in fs::read_dir(path)? {
+		let path = entry?.path();
+		if path.file_name().is_some_and(|name| name != "target") {
+			source_files(&path, files)?;
+		}
+	}
+	Ok(())
+}
+
+/// A 64-bit FNV-1a hash of the guests' sources, keyed by their paths relative to `fuste`.
+fn sources_fingerprint() -> Result<String, Box<dyn Error>> {
+	let fuste = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+	let mut files = Vec::new();
+	source_files(&fuste.join("env"), &mut files)?;
+	for source in TOOLCHAIN_SOURCES {
+		source_files(&fuste.join("tests/toolchain").join(source), &mut files)?;
+	}
+
+	let mut keyed = files
+		.into_iter()
+		.map(|file| {
+			let key = file.strip_prefix(&fuste)?.to_string_lossy().replace('\\', "/");
+			Ok((key, file))
+		})
+		.collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+	keyed.sort();
+
+	let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+	let mut feed = |bytes: &[u8]| {
+		for byte in bytes {
+			hash = (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3);
+		}
+	};
+	for (key, file) in keyed {
+		let contents = fs::read(file)?;
+		feed(key.as_bytes());
+		feed(&[0]);
+		feed(&(contents.len() as u64).to_le_bytes());
+		feed(&contents);
+	}
+	Ok(format!("{hash:016x}"))
+}
+
+#[test]
+fn test_guests_are_built_from_their_sources() -> Result<(), Box<dyn Error>> {
+	let recorded = guest("sources.fingerprint");
+	let fingerprint = sources_fingerprint()?;
+	if std::env::var_os("FUSTE_BLESS_GUESTS").is_some() {
+		fs::write(&recorded, format!("{fingerprint}\n"))?;
+	}
+
+	assert_eq!(
+		fs::read_to_string(&recorded)?.trim(),
+		fingerprint,
+		"the guests under tests/abi are older than their sources; rebuild them as this file's \
+		 documentation describes"
+	);
+	Ok(())
+}
+
+fn fubox(args: &[&str]) -> Result<Output, Box<dyn Error>> {
+	Ok(Command::new(env!("CARGO_BIN_EXE_fubox")).args(args).output()?)
+}
+
+#[test]
+fn test_write_and_exit() -> Result<(), Box<dyn Error>> {
+	let path = guest("abi-conformance.elf");
+	let output = fubox(&["run", "elf", "--path", &path.to_string_lossy()])?;
+
+	// Without a channel system nothing serves the channel ECALL, so it reads as ignored, and the
+	// test's stdin is empty.
+	assert_eq!(
+		String::from_utf8_lossy(&output.stdout),
+		"write: ok\ninvalid write: ok\nchannel: ignored\nchannel wait: ignored\nread: \nreturn data: ok\nrandom: ok\ntime: ok\n"
+	);
+	assert_eq!(String::from_utf8_lossy(&output.stderr), "stderr: ok\n");
+	// The guest exits with `ExitStatus::Terminated`.
+	assert_eq!(output.status.code(), Some(2), "{}", String::from_utf8_lossy(&output.stderr));
+
+	Ok(())
+}
+
+#[test]
+fn test_channel() -> Result<(), Box<dyn Error>> {
+	let path = guest("abi-conformance.elf");
+	let output = fubox(&[
+		"run",
+		"elf",
+		"--path",
+		&path.to_string_lossy(),
+		"--channel-system",
+		"std-transaction",
+	])?;
+
+	assert_eq!(
+		String::from_utf8_lossy(&output.stdout),
+		"write: ok\ninvalid write: ok\nchannel: ok\nchannel wait: ok\nread: \nreturn data: ok\nrandom: ok\ntime: ok\n"
+	);
+	assert_eq!(output.status.code(), Some(2), "{}", String::from_utf8_lossy(&output.stderr));
+
+	Ok(())
+}
+
+#[test]
+fn test_stdin_file() -> Result<(), Box<dyn Error>> {
+	let path = guest("abi-conformance.elf");
+	let stdin_file = guest("stdin.txt");
+	let output = fubox(&[
+		"run",
+		"elf",
+		"--path",
+		&path.to_string_lossy(),
+		"--stdin-file",
+		&stdin_file.to_string_lossy(),
+	])?;
+
+	assert_eq!(
+		String::from_utf8_lossy(&output.stdout),
+		"write: ok\ninvalid write: ok\nchannel: ignored\nchannel wait: ignored\nread: test vector\nreturn data: ok\nrandom: ok\ntime: ok\n"
+	);
+	assert_eq!(output.status.code(), Some(2), "{}", String::from_utf8_lossy(&output.stderr));
+
+	Ok(())
+}
+
+#[test]
+fn test_write_file() -> Result<(), Box<dyn Error>> {
+	let path = guest("abi-conformance.elf");
+	let write_file = Path::new(env!("CARGO_TARGET_TMPDIR")).join("abi-conformance-stderr");
+	let output = fubox(&[
+		"run",
+		"elf",
+		"--path",
+		&path.to_string_lossy(),
+		"--write-file",
+		&format!("2={}", write_file.display()),
+	])?;
+
+	// The file replaces the host's stderr for the guest's writes to it.
+	assert_eq!(String::from_utf8_lossy(&output.stderr), "");
+	assert_eq!(fs::read_to_string(&write_file)?, "stderr: ok\n");
+	assert_eq!(output.status.code(), Some(2));
+
+	Ok(())
+}
+
+#[test]
+fn test_panic() -> Result<(), Box<dyn Error>> {
+	let path = guest("abi-conformance.elf");
+	let output = fubox(&["run", "elf", "--path", &path.to_string_lossy(), "--", "panic"])?;
+
+	assert_eq!(
+		String::from_utf8_lossy(&output.stderr),
+		"panicked at abi-conformance/src/main.rs:37: asked to panic\n"
+	);
+	// A panic exits with `ExitStatus::Error`.
+	assert_eq!(output.status.code(), Some(1));
+
+	Ok(())
+}
+
+#[test]
+fn test_error_from_main() -> Result<(), Box<dyn Error>> {
+	let path = guest("abi-conformance.elf");
+	let output = fubox(&["run", "elf", "--path", &path.to_string_lossy(), "--", "error"])?;
+
+	assert_eq!(String::from_utf8_lossy(&output.stderr), "Error: \"asked to fail\"\n");
+	assert_eq!(output.status.code(), Some(1));
+
+	Ok(())
+}
+
+#[test]
+fn test_exit_code() -> Result<(), Box<dyn Error>> {
+	let path = guest("abi-conformance.elf");
+	let output = fubox(&["run", "elf", "--path", &path.to_string_lossy(), "--", "code"])?;
+
+	assert_eq!(output.status.code(), Some(42), "{}", String::from_utf8_lossy(&output.stderr));
+
+	Ok(())
+}
+
+#[test]
+fn test_return_data() -> Result<(), Box<dyn Error>> {
+	let path = guest("abi-conformance.elf");
+	let output = fubox(&["run", "elf", "--path", &path.to_string_lossy(), "--report", "json"])?;
+
+	// The report is the last line of stderr, after the guest's own writes to it.
+	let stderr = String::from_utf8_lossy(&output.stderr);
+	let report: serde_json::Value =
+		serde_json::from_str(stderr.lines().last().ok_or("no report")?)?;
+	assert_eq!(report["return_data"], hex_string(b"abi: ok"));
+	assert_eq!(report["status_code"], 2);
+
+	Ok(())
+}
+
+fn hex_string(bytes: &[u8]) -> String {
+	bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[test]
+fn test_seeded_random() -> Result<(), Box<dyn Error>> {
+	let path = guest("abi-conformance.elf");
+	let path = path.to_string_lossy();
+	let random = |seed: &str| -> Result<String, Box<dyn Error>> {
+		let output = fubox(&["run", "elf", "--path", &path, "--seed", seed, "--", "random"])?;
+		assert_eq!(output.status.code(), Some(0), "{}", String::from_utf8_lossy(&output.stderr));
+		Ok(String::from_utf8(output.stdout)?)
+	};
+
+	// Runs with the same seed draw the same bytes.
+	assert_eq!(random("7")?, random("7")?);
+	assert_ne!(random("7")?, random("8")?);
+
+	Ok(())
+}
+
+#[test]
+fn test_clocks() -> Result<(), Box<dyn Error>> {
+	let path = guest("abi-conformance.elf");
+	let path = path.to_string_lossy();
+	let time = |clock: &str| -> Result<Vec<String>, Box<dyn Error>> {
+		let output = fubox(&[
+			"run",
+			"elf",
+			"--path",
+			&path,
+			"--clock",
+			clock,
+			"--timestamp",
+			"1000",
+			"--",
+			"time",
+		])?;
+		assert_eq!(output.status.code(), Some(0), "{}", String::from_utf8_lossy(&output.stderr));
+		Ok(String::from_utf8(output.stdout)?.lines().map(str::to_string).collect())
+	};
+
+	// A fixed clock reads the timestamp however long the program waits.
+	assert_eq!(time("fixed")?, ["time: fixed 00000000000003e8", "elapsed: 0000000000000000"]);
+
+	// A virtual clock passes with the instructions executed, so it reads the same on every run.
+	let lines = time("virtual")?;
+	assert_eq!(lines, time("virtual")?);
+	let elapsed = lines[1].strip_prefix("elapsed: ").ok_or("no elapsed time")?;
+	assert!(u64::from_str_radix(elapsed, 16)? >= 1000);
+
+	Ok(())
+}
+
+#[test]
+#[cfg(feature = "coverage")]
+fn test_coverage_is_reported_when_the_run_fails() -> Result<(), Box<dyn Error>> {
+	let path = guest("abi-conformance.elf");
+	// The trace can't be created, so the run fails before the program starts.
+	let trace = std::env::temp_dir().join("fubox-missing-directory").join("trace.jsonl");
+	let output = fubox(&[
+		"coverage",
+		"elf",
+		"--path",
+		&path.to_string_lossy(),
+		"--trace",
+		&trace.to_string_lossy(),
+	])?;
+
+	assert_ne!(output.status.code(), Some(0));
+	assert!(String::from_utf8(output.stdout)?.starts_with("instructions: 0/"));
+
+	Ok(())
+}
+
+#[test]
+#[cfg(all(feature = "profile", feature = "coverage"))]
+fn test_profile_and_coverage_exit_with_the_program() -> Result<(), Box<dyn Error>> {
+	let path = guest("abi-conformance.elf");
+	for command in ["profile", "coverage"] {
+		let output = fubox(&[command, "elf", "--path", &path.to_string_lossy(), "--", "code"])?;
+		assert_eq!(output.status.code(), Some(42), "{command}");
+	}
+
+	Ok(())
+}
@@ -0,0 +1,14 @@
+#![no_main]
+
+use fuste_riscv_reference::diff::check;
+use libfuzzer_sys::fuzz_target;
+
+/// Small enough to copy per run, large enough for register values to land inside it.
+const MEMORY_SIZE: usize = 256;
+
+fuzz_target!(|input: (u32, [u32; 32], u32, Vec<u8>)| {
+	let (word, registers, pc, memory) = input;
+	if let Err(mismatch) = check::<MEMORY_SIZE>(word, registers, pc, &memory) {
+		panic!("{mismatch}");
+	}
+});
@@ -0,0 +1,10 @@
+#![no_main]
+
+use fuste_riscv_reference::diff::check_round_trip;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|word: u32| {
+	if let Err(mismatch) = check_round_trip::<1024>(word) {
+		panic!("{mismatch}");
+	}
+});
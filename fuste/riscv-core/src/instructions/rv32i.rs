@@ -99,8 +99,14 @@ impl<const MEMORY_SIZE: usize> Rv32iInstruction<MEMORY_SIZE> {
 			Auipc::OPCODE => Ok(Rv32iInstruction::Auipc(Auipc::from_word(word))),
 			// J format is just JAL
 			Jal::OPCODE => Ok(Rv32iInstruction::Jal(Jal::from_word(word))),
-			// JALR has its own opcode
-			Jalr::OPCODE => Ok(Rv32iInstruction::Jalr(Jalr::from_word(word))),
+			// JALR has its own opcode, but still reserves the other funct3 values
+			Jalr::OPCODE => {
+				let i = base::i::I::from_word(word);
+				match i.funct3() {
+					Jalr::FUNCT3 => Ok(Rv32iInstruction::Jalr(Jalr::new(i))),
+					_ => Err(Rv32iInstructionError::InvalidInstruction(word)),
+				}
+			}
 			// B format shares an opcode
 			B::OPCODE => {
 				// For B-type instructions, we need to check funct3
@@ -149,14 +155,18 @@ impl<const MEMORY_SIZE: usize> Rv32iInstruction<MEMORY_SIZE> {
 					Xori::FUNCT3 => Ok(Rv32iInstruction::Xori(Xori::new(i))),
 					Ori::FUNCT3 => Ok(Rv32iInstruction::Ori(Ori::new(i))),
 					Andi::FUNCT3 => Ok(Rv32iInstruction::Andi(Andi::new(i))),
-					Slli::FUNCT3 => Ok(Rv32iInstruction::Slli(Slli::new(i))),
+					// Only one funct7 is defined for SLLI, the other values are reserved
+					Slli::FUNCT3 => match i.funct7() {
+						Slli::FUNCT7 => Ok(Rv32iInstruction::Slli(Slli::new(i))),
+						_ => Err(Rv32iInstructionError::InvalidInstruction(word)),
+					},
 					// For SRLI and SRAI, both have funct3=101, distinguished by funct7
 					Srli::FUNCT3 => {
 						// Check if it's SRAI (funct7=0100000) or SRLI (funct7=0000000)
 						// For I format, funct7 is in bits [31:25] of the immediate field
 						match i.funct7() {
 							Srai::FUNCT7 => Ok(Rv32iInstruction::Srai(Srai::new(i))),
-							Srl::FUNCT7 => Ok(Rv32iInstruction::Srli(Srli::new(i))),
+							Srli::FUNCT7 => Ok(Rv32iInstruction::Srli(Srli::new(i))),
 							_ => Err(Rv32iInstructionError::InvalidInstruction(word)),
 						}
 					}
@@ -181,14 +191,25 @@ impl<const MEMORY_SIZE: usize> Rv32iInstruction<MEMORY_SIZE> {
 					_ => Err(Rv32iInstructionError::InvalidInstruction(word)),
 				}
 			}
-			// Fence has its own opcode
-			Fence::OPCODE => Ok(Rv32iInstruction::Fence(Fence::from_word(word))),
+			// Fence has its own opcode, the other funct3 values belong to extensions
+			Fence::OPCODE => {
+				let i = base::i::I::from_word(word);
+				match i.funct3() {
+					Fence::FUNCT3 => Ok(Rv32iInstruction::Fence(Fence::new(i))),
+					_ => Err(Rv32iInstructionError::InvalidInstruction(word)),
+				}
+			}
 			// Environment instructions have their own structure
 			Ecall::OPCODE => {
+				// Both are fully fixed words, the other encodings belong to Zicsr and privileged modes
 				let i = base::i::I::from_word(word);
-				match i.imm() {
-					Ecall::IMM => Ok(Rv32iInstruction::Ecall(Ecall::new(i))),
-					Ebreak::IMM => Ok(Rv32iInstruction::Ebreak(Ebreak::new(i))),
+				match (i.rd(), i.funct3(), i.rs1(), i.imm()) {
+					(Ecall::RD, Ecall::FUNCT3, Ecall::RS1, Ecall::IMM) => {
+						Ok(Rv32iInstruction::Ecall(Ecall::new(i)))
+					}
+					(Ebreak::RD, Ebreak::FUNCT3, Ebreak::RS1, Ebreak::IMM) => {
+						Ok(Rv32iInstruction::Ebreak(Ebreak::new(i)))
+					}
 					_ => Err(Rv32iInstructionError::InvalidInstruction(word)),
 				}
 			}
@@ -350,3 +371,39 @@ impl<const MEMORY_SIZE: usize> Display for Rv32iInstruction<MEMORY_SIZE> {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn decode(word: u32) -> Result<Rv32iInstruction<1024>, Rv32iInstructionError> {
+		Rv32iInstruction::<1024>::from_word(word)
+	}
+
+	#[test]
+	fn test_decodes_srai_and_srli() -> Result<(), Rv32iInstructionError> {
+		assert!(matches!(decode(0x4030_d113)?, Rv32iInstruction::Srai(_))); // srai x2, x1, 3
+		assert!(matches!(decode(0x0030_d113)?, Rv32iInstruction::Srli(_))); // srli x2, x1, 3
+		assert!(decode(0x2030_d113).is_err());
+		assert!(decode(0x4030_9113).is_err()); // slli with the srai funct7
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_rejects_reserved_encodings() {
+		assert!(decode(0x0000_90e7).is_err()); // jalr with funct3 = 1
+		assert!(decode(0x0000_100f).is_err()); // fence.i is not part of RV32I
+		assert!(decode(0x0000_00f3).is_err()); // ecall with rd = 1
+		assert!(decode(0x0000_1073).is_err()); // csrrw x0, 0, x0
+	}
+
+	#[test]
+	fn test_round_trips_fixed_words() -> Result<(), Rv32iInstructionError> {
+		for word in [0x0000_0073, 0x0010_0073, 0x0ff0_000f, 0xfe20_ae23, 0xfe00_0ce3, 0x8000_006f] {
+			assert_eq!(decode(word)?.to_word(), word);
+		}
+
+		Ok(())
+	}
+}
@@ -36,7 +36,7 @@ impl B {
 			// bits [19:15]
 			rs1: ((word & 0b0000_0000_0000_1111_1000_0000_0000_0000) >> 15) as u8,
 			// bits [24:20]
-			rs2: ((word & 0b0000_0000_1111_1000_0000_0000_0000_0000) >> 20) as u8,
+			rs2: ((word & 0b0000_0001_1111_0000_0000_0000_0000_0000) >> 20) as u8,
 			// bits [31:25] and [11:8] and [7] - B-type immediate reconstruction
 			imm: {
 				let imm_12 = (word & 0b1000_0000_0000_0000_0000_0000_0000_0000) >> 19; // bit 31 -> imm[12]
@@ -107,8 +107,8 @@ impl B {
 	pub fn word_imm(&self) -> u32 {
 		// B-type immediate reconstruction
 		let imm = self.imm as u32;
-		let imm_11 = (imm & 0b1000_0000_0000_0000) >> 11; // bit 11
-		let imm_4_1 = (imm & 0b0000_0000_0000_1111) >> 1; // bits [4:1]
+		let imm_11 = (imm & 0b1000_0000_0000) >> 11; // bit 11
+		let imm_4_1 = (imm & 0b0000_0001_1110) >> 1; // bits [4:1]
 		let imm_10_5 = (imm & 0b0000_0111_1110_0000) >> 5; // bits [10:5]
 		let imm_12 = (imm & 0b0001_0000_0000_0000) >> 12; // bit 12
 
@@ -174,4 +174,17 @@ mod tests {
 		let b = B::from_word(word);
 		assert_eq!(b.imm(), expected_imm);
 	}
+
+	#[test]
+	fn test_roundtrip_immediates() {
+		for imm in [-4096, -2048, -8, -2, 2, 30, 2046, 2048, 4094] {
+			let b = B::new(0b001, 3, 17, imm); // BNE x3, x17, imm
+			let word = b.to_word(B::OPCODE);
+			let decoded = B::from_word(word);
+
+			assert_eq!(decoded.imm(), imm);
+			assert_eq!(decoded.rs2(), 17);
+			assert_eq!(decoded.to_word(B::OPCODE), word);
+		}
+	}
 }
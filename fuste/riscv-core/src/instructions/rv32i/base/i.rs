@@ -119,13 +119,13 @@ impl I {
 	#[inline(always)]
 	pub fn funct7(&self) -> u8 {
 		// For shift instructions, the funct7 is the upper 7 bits of imm which is 12 bits in total
-		(self.imm & 0b1111_111__0_0000) as u8
+		((self.imm & 0b1111_111__0_0000) >> 5) as u8
 	}
 
 	#[inline(always)]
 	pub fn fence_fm(&self) -> u8 {
 		// For fence instructions, the fence_fm is the upper 4 bits of imm which is 12 bits in total
-		(self.imm & 0b1111__0_0000_0000) as u8
+		((self.imm & 0b1111_0000_0000) >> 8) as u8
 	}
 
 	#[inline(always)]
@@ -222,4 +222,14 @@ mod tests {
 
 		assert_eq!(i.shamt(), 1);
 	}
+
+	#[test]
+	fn test_funct7_extraction() {
+		//              |f7         |shamt  |rs1     |f3   |rd   |opcode
+		let word = 0b010_0000__0_0011__0_0001___101__00010__0010011; // SRAI x2, x1, 3
+		let i = I::from_word(word);
+
+		assert_eq!(i.funct7(), 0b0100000);
+		assert_eq!(i.shamt(), 3);
+	}
 }
@@ -14,6 +14,9 @@ pub struct Ecall(I);
 impl Ecall {
 	pub const OPCODE: u32 = 0b1110011;
 	pub const IMM: i32 = 0;
+	pub const FUNCT3: u8 = 0;
+	pub const RD: u8 = 0;
+	pub const RS1: u8 = 0;
 	pub const INSTRUCTION_NAME: &'static str = "ecall";
 
 	#[inline(always)]
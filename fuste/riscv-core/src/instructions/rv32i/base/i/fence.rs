@@ -11,6 +11,7 @@ pub struct Fence(I);
 
 impl Fence {
 	pub const OPCODE: u32 = 0b0001111;
+	pub const FUNCT3: u8 = 0b000;
 	pub const INSTRUCTION_NAME: &'static str = "fence";
 
 	#[inline(always)]
@@ -113,10 +114,28 @@ impl<const MEMORY_SIZE: usize> ExecutableInstruction<MEMORY_SIZE> for Fence {
 	/// In the synchronous [Machine] this memory ordering is determined by the plugins.
 	/// There is no true coprocessing, and all instructions are executed in a reasonable order.
 	#[inline(always)]
-	fn execute(
-		self,
-		_machine: &mut Machine<MEMORY_SIZE>,
-	) -> Result<(), ExecutableInstructionError> {
+	fn execute(self, machine: &mut Machine<MEMORY_SIZE>) -> Result<(), ExecutableInstructionError> {
+		// Increment program counter by 4 (word size)
+		machine.registers_mut().program_counter_mut().increment();
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_fence_increments_program_counter() -> Result<(), ExecutableInstructionError> {
+		let mut machine = Machine::<1024>::new();
+
+		let instruction = Fence::new(I::new(0, 0, 0, 0b0000_1111_1111));
+		instruction.execute(&mut machine)?;
+
+		// Check PC was incremented by 4
+		assert_eq!(machine.registers().program_counter(), 4);
+
 		Ok(())
 	}
 }
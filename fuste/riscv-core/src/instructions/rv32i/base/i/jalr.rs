@@ -73,7 +73,7 @@ impl<const MEMORY_SIZE: usize> ExecutableInstruction<MEMORY_SIZE> for Jalr {
 
 		// Save return address (current PC + 4) in destination register
 		let current_pc = registers.program_counter();
-		registers.set(rd, current_pc + 4);
+		registers.set(rd, current_pc.wrapping_add(4));
 
 		// Set PC to target address
 		registers.program_counter_mut().set(target_addr);
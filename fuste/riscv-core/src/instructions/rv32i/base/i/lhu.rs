@@ -71,7 +71,7 @@ impl<const MEMORY_SIZE: usize> ExecutableInstruction<MEMORY_SIZE> for Lhu {
 
 		// Load halfword from memory (2 bytes, little-endian)
 		let byte0 = machine.memory().read_byte(eff_addr)?;
-		let byte1 = machine.memory().read_byte(eff_addr + 1)?;
+		let byte1 = machine.memory().read_byte(eff_addr.wrapping_add(1))?;
 		let halfword_value = (byte1 as u16) << 8 | (byte0 as u16);
 
 		let registers = machine.registers_mut();
@@ -12,6 +12,7 @@ pub struct Slli(I);
 impl Slli {
 	pub const OPCODE: u32 = 0b0010011;
 	pub const FUNCT3: u8 = 0b001;
+	pub const FUNCT7: u8 = 0b0000000;
 	pub const INSTRUCTION_NAME: &'static str = "slli";
 
 	#[inline(always)]
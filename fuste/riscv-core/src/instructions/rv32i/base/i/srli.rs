@@ -13,6 +13,7 @@ impl Srli {
 	pub const INSTRUCTION_NAME: &'static str = "srli";
 	pub const OPCODE: u32 = 0b0010011;
 	pub const FUNCT3: u8 = 0b101;
+	pub const FUNCT7: u8 = 0b0000000; // SRLI is distinguished from SRAI by funct7
 
 	#[inline(always)]
 	pub fn new(i: I) -> Self {
@@ -61,10 +61,10 @@ impl<const MEMORY_SIZE: usize> ExecutableInstruction<MEMORY_SIZE> for Jal {
 		let current_pc = registers.program_counter();
 
 		// Store return address (PC + 4) in destination register
-		registers.set(rd, current_pc + 4);
+		registers.set(rd, current_pc.wrapping_add(4));
 
 		// Calculate jump target: PC + offset
-		let target_pc = (current_pc as i32 + self.imm()) as u32;
+		let target_pc = current_pc.wrapping_add(self.imm() as u32);
 
 		// Update program counter to jump target
 		registers.program_counter_mut().set(target_pc);
@@ -153,4 +153,18 @@ mod tests {
 
 		Ok(())
 	}
+
+	#[test]
+	fn test_jal_wraps_around_the_address_space() -> Result<(), ExecutableInstructionError> {
+		let mut machine = Machine::<1024>::new();
+		machine.registers_mut().program_counter_set(0xFFFF_FFFC);
+
+		let instruction = Jal::new(J::new(1, 8));
+		instruction.execute(&mut machine)?;
+
+		assert_eq!(machine.registers().get(1), 0);
+		assert_eq!(machine.registers().program_counter(), 4);
+
+		Ok(())
+	}
 }
@@ -36,7 +36,7 @@ impl R {
 			// bits [19:15]
 			rs1: ((word & 0b0000_0000_0000_1111_1000_0000_0000_0000) >> 15) as u8,
 			// bits [24:20]
-			rs2: ((word & 0b0000_0000_1111_1000_0000_0000_0000_0000) >> 20) as u8,
+			rs2: ((word & 0b0000_0001_1111_0000_0000_0000_0000_0000) >> 20) as u8,
 			// bits [31:25]
 			funct7: ((word & 0b1111_1110_0000_0000_0000_0000_0000_0000) >> 25) as u8,
 		}
@@ -169,4 +169,16 @@ mod tests {
 		assert_eq!(r.rs2(), 0);
 		assert_eq!(r.funct7(), 0);
 	}
+
+	#[test]
+	fn test_field_extraction_high_rs2() {
+		//              |f7      |rs2  |rs1  |f3 |rd   |opcode |
+		let word = 0b0100000_10001_00100_000_00011_0110011; // SUB x3, x4, x17
+		let r = R::from_word(word);
+
+		assert_eq!(r.rs1(), 4);
+		assert_eq!(r.rs2(), 17);
+		assert_eq!(r.funct7(), 0b0100000);
+		assert_eq!(r.to_word(R::OPCODE), word);
+	}
 }
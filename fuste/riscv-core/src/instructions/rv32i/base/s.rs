@@ -26,7 +26,7 @@ impl S {
 			// bits [19:15]
 			rs1: ((word & 0b0000_0000_0000_1111_1000_0000_0000_0000) >> 15) as u8,
 			// bits [24:20]
-			rs2: ((word & 0b0000_0000_1111_1000_0000_0000_0000_0000) >> 20) as u8,
+			rs2: ((word & 0b0000_0001_1111_0000_0000_0000_0000_0000) >> 20) as u8,
 			// bits [31:25] and [11:7] - S-type immediate reconstruction
 			imm: {
 				let imm_11_5 = (word & 0b1111_1110_0000_0000_0000_0000_0000_0000) >> 20; // bits [31:25] -> imm[11:5]
@@ -145,4 +145,15 @@ mod tests {
 		let s = S::from_word(word);
 		assert_eq!(s.imm(), 1);
 	}
+
+	#[test]
+	fn test_roundtrip_high_rs2() {
+		let s = S::new(0b010, 2, 31, -4); // SW x31, -4(x2)
+		let word = s.to_word(S::OPCODE);
+		let decoded = S::from_word(word);
+
+		assert_eq!(decoded.rs2(), 31);
+		assert_eq!(decoded.imm(), -4);
+		assert_eq!(decoded.to_word(S::OPCODE), word);
+	}
 }
@@ -1,5 +1,7 @@
 #![no_std]
 
+extern crate alloc;
+
 pub mod instructions;
 #[cfg(debug_assertions)]
 pub mod log;
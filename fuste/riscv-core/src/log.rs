@@ -16,15 +16,40 @@ impl<const N: usize> RingBuffer<N> {
 		self.head = self.head.wrapping_add(1);
 	}
 
+	/// The number of bytes currently held by the buffer.
+	pub fn len(&self) -> usize {
+		self.head.min(N)
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.head == 0
+	}
+
+	/// Returns the contents of the buffer as two slices, oldest bytes first.
+	///
+	/// The second slice is only non-empty once the buffer has wrapped.
+	pub fn as_slices(&self) -> (&[u8], &[u8]) {
+		if self.head <= N {
+			(&self.buf[..self.head], &[])
+		} else {
+			let idx = self.head % N;
+			(&self.buf[idx..], &self.buf[..idx])
+		}
+	}
+
 	pub fn dump(&self, mut f: impl FnMut(u8)) {
-		for i in 0..N {
-			let idx = (self.head + i) % N;
-			f(self.buf[idx]);
+		let (older, newer) = self.as_slices();
+		for &b in older.iter().chain(newer) {
+			f(b);
 		}
 	}
 
-	pub fn as_str(&self) -> Result<&str, core::str::Utf8Error> {
-		core::str::from_utf8(&self.buf)
+	/// Returns the contents of the buffer as two strings, oldest first.
+	///
+	/// See [RingBuffer::as_slices].
+	pub fn as_strs(&self) -> Result<(&str, &str), core::str::Utf8Error> {
+		let (older, newer) = self.as_slices();
+		Ok((core::str::from_utf8(older)?, core::str::from_utf8(newer)?))
 	}
 }
 
@@ -36,3 +61,38 @@ impl<const N: usize> Write for RingBuffer<N> {
 		Ok(())
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_ring_buffer_before_wrap() -> Result<(), core::str::Utf8Error> {
+		let mut log = RingBuffer::<8>::new();
+		b"abc".iter().for_each(|&b| log.push_byte(b));
+
+		assert_eq!(log.len(), 3);
+		assert_eq!(log.as_strs()?, ("abc", ""));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_ring_buffer_after_wrap_is_ordered() -> Result<(), core::str::Utf8Error> {
+		let mut log = RingBuffer::<8>::new();
+		b"abcdefghij".iter().for_each(|&b| log.push_byte(b));
+
+		assert_eq!(log.len(), 8);
+		assert_eq!(log.as_strs()?, ("cdefgh", "ij"));
+
+		let mut dumped = [0u8; 8];
+		let mut i = 0;
+		log.dump(|b| {
+			dumped[i] = b;
+			i += 1;
+		});
+		assert_eq!(&dumped, b"cdefghij");
+
+		Ok(())
+	}
+}
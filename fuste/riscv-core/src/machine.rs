@@ -2,12 +2,16 @@ pub mod memory;
 pub use memory::Memory;
 pub mod registers;
 use crate::instructions::ExecutableInstructionError;
+#[cfg(debug_assertions)]
 use crate::log::RingBuffer;
+use alloc::alloc::{alloc_zeroed, handle_alloc_error, Layout};
+use alloc::boxed::Box;
 use core::error::Error;
 use core::fmt::{self, Display};
 pub use registers::Registers;
 pub mod csrs;
 use core::ops::ControlFlow;
+use core::ptr::addr_of_mut;
 pub use csrs::Csrs;
 
 /// The machine is the memory layout against which the plugins operate.
@@ -15,6 +19,8 @@ pub struct Machine<const MEMORY_SIZE: usize> {
 	memory: Memory<MEMORY_SIZE>,
 	registers: Registers,
 	csrs: Csrs,
+	/// The number of instructions the machine has executed, as RISC-V's `instret` counts them.
+	instructions_retired: u64,
 	#[cfg(debug_assertions)]
 	log: RingBuffer<4096>,
 }
@@ -44,6 +50,19 @@ impl<const MEMORY_SIZE: usize, T: MachineSystem<MEMORY_SIZE>> MachineSystem<MEMO
 	}
 }
 
+/// A borrowed [MachineSystem] ticks the system it borrows, so its state can be read after a run.
+impl<const MEMORY_SIZE: usize, T: MachineSystem<MEMORY_SIZE> + ?Sized> MachineSystem<MEMORY_SIZE>
+	for &mut T
+{
+	#[inline(always)]
+	fn tick(
+		&mut self,
+		machine: &mut Machine<MEMORY_SIZE>,
+	) -> Result<ControlFlow<()>, MachineError> {
+		(**self).tick(machine)
+	}
+}
+
 impl<const MEMORY_SIZE: usize> Machine<MEMORY_SIZE> {
 	/// Creates a new machine instance with all memory and registers initialized to zero.
 	pub fn new() -> Self {
@@ -51,10 +70,35 @@ impl<const MEMORY_SIZE: usize> Machine<MEMORY_SIZE> {
 			memory: Memory::new(),
 			registers: Registers::new(),
 			csrs: Csrs::new(),
+			instructions_retired: 0,
+			#[cfg(debug_assertions)]
 			log: RingBuffer::new(),
 		}
 	}
 
+	/// Creates a new machine like [Machine::new], but directly on the heap.
+	///
+	/// The memory is held inline, so a machine made with [Machine::new] is built on the stack
+	/// first, which large memories overflow.
+	pub fn new_boxed() -> Box<Self> {
+		let layout = Layout::new::<Self>();
+		// SAFETY: the layout is that of a machine, which is never zero-sized since it holds its
+		// registers. Zeroing the allocation initializes the memory, and every other field is
+		// written before the allocation is handed to the box, which frees it with the same layout.
+		unsafe {
+			let machine = alloc_zeroed(layout).cast::<Self>();
+			if machine.is_null() {
+				handle_alloc_error(layout);
+			}
+			addr_of_mut!((*machine).registers).write(Registers::new());
+			addr_of_mut!((*machine).csrs).write(Csrs::new());
+			addr_of_mut!((*machine).instructions_retired).write(0);
+			#[cfg(debug_assertions)]
+			addr_of_mut!((*machine).log).write(RingBuffer::new());
+			Box::from_raw(machine)
+		}
+	}
+
 	/// Borrows the memory of the machine.
 	#[inline(always)]
 	pub fn memory(&self) -> &Memory<MEMORY_SIZE> {
@@ -97,6 +141,18 @@ impl<const MEMORY_SIZE: usize> Machine<MEMORY_SIZE> {
 		&mut self.csrs
 	}
 
+	/// The number of instructions the machine has executed.
+	#[inline(always)]
+	pub fn instructions_retired(&self) -> u64 {
+		self.instructions_retired
+	}
+
+	/// Counts an executed instruction.
+	#[inline(always)]
+	pub fn retire_instruction(&mut self) {
+		self.instructions_retired += 1;
+	}
+
 	/// Traps the registers of the machine in the CSRs.
 	#[inline(always)]
 	pub fn trap_registers(&mut self) {
@@ -157,3 +213,22 @@ impl Display for MachineError {
 }
 
 impl Error for MachineError {}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_new_boxed() -> Result<(), memory::MemoryError> {
+		// Larger than the stack of a test thread, so building it with `new` would overflow.
+		let machine = Machine::<{ 16 * 1024 * 1024 }>::new_boxed();
+
+		assert!(machine.memory().memory.iter().all(|byte| *byte == 0));
+		assert_eq!(machine.memory().read_word(16 * 1024 * 1024 - 4)?, 0);
+		assert_eq!(machine.registers().program_counter(), 0);
+		assert!((0..32).all(|index| machine.registers().get(index) == 0));
+		assert_eq!(machine.instructions_retired(), 0);
+
+		Ok(())
+	}
+}
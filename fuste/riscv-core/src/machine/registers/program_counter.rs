@@ -24,11 +24,11 @@ impl ProgramCounter {
 	#[inline(always)]
 	pub fn increment(&mut self) {
 		// increment by 4 for RV32I alignment.
-		self.program_counter += 4;
+		self.program_counter = self.program_counter.wrapping_add(4);
 	}
 
 	#[inline(always)]
 	pub fn increment_by(&mut self, value: u32) {
-		self.program_counter += value;
+		self.program_counter = self.program_counter.wrapping_add(value);
 	}
 }
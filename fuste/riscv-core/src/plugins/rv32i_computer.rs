@@ -2,6 +2,7 @@ use crate::instructions::Rv32iInstruction;
 use crate::machine::Machine;
 use crate::machine::MachineError;
 use crate::machine::MachineSystem;
+#[cfg(debug_assertions)]
 use core::fmt::Write;
 use core::ops::ControlFlow;
 
@@ -35,11 +36,15 @@ impl<const MEMORY_SIZE: usize> MachineSystem<MEMORY_SIZE> for Rv32iComputer {
 			machine.memory().read_word(program_counter).map_err(MachineError::MemoryError)?;
 
 		// write the instruction to the machine log
-		let log = machine.log_mut();
-		writeln!(log, "0x{:X}: 0b{:b}", program_counter, instruction).unwrap();
+		#[cfg(debug_assertions)]
+		{
+			let log = machine.log_mut();
+			writeln!(log, "0x{:X}: 0b{:b}", program_counter, instruction).unwrap();
+		}
 
 		Rv32iInstruction::load_and_execute(program_counter, instruction, machine)
 			.map_err(MachineError::InstructionError)?;
+		machine.retire_instruction();
 
 		Ok(ControlFlow::Continue(()))
 	}
@@ -127,6 +132,10 @@ mod tests {
 		assert_eq!(machine.registers().get(1), 33); // counter should be 33
 		assert_eq!(machine.registers().get(4), 15); // loop counter should be 15 (0 to 14 iterations)
 
+		// 3 to initialize, 4 for each iteration but the last, which breaks after 3, and the ebreak
+		// traps rather than retiring
+		assert_eq!(machine.instructions_retired(), 62);
+
 		Ok(())
 	}
 }
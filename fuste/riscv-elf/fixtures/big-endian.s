@@ -0,0 +1,8 @@
+# A big-endian object the loader must reject, rebuild big-endian.elf with
+#
+#   llvm-mc -triple=mips -filetype=obj big-endian.s -o big-endian.elf
+
+	.section .text
+	.globl _start
+_start:
+	nop
@@ -0,0 +1,37 @@
+# An executable linked at 0x1000 for the loader tests, rebuild exec.elf with
+#
+#   llvm-mc -triple=riscv32 -mattr=-c,-relax -g -filetype=obj exec.s -o exec.o
+#   rust-lld -flavor gnu -T link.ld exec.o -o exec.elf
+
+	.section .text
+	.globl _start
+	.type _start, @function
+_start:
+	call main
+	li a7, 93
+	ecall
+	.size _start, .-_start
+
+	.globl main
+	.type main, @function
+main:
+	la t0, value
+	lw a0, 0(t0)
+	la t1, zeroed
+	sw a0, 0(t1)
+	ret
+	.size main, .-main
+
+	.section .data
+	.globl value
+	.type value, @object
+value:
+	.word 0x12345678
+	.size value, 4
+
+	.section .bss
+	.globl zeroed
+	.type zeroed, @object
+zeroed:
+	.zero 16
+	.size zeroed, 16
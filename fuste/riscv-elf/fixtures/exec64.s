@@ -0,0 +1,8 @@
+# A 64-bit RISC-V object the loader must reject, rebuild exec64.elf with
+#
+#   llvm-mc -triple=riscv64 -filetype=obj exec64.s -o exec64.elf
+
+	.section .text
+	.globl _start
+_start:
+	ret
@@ -0,0 +1,27 @@
+# A position-independent executable with R_RISCV_RELATIVE relocations, rebuild pie.elf with
+#
+#   llvm-mc -triple=riscv32 -mattr=-c,-relax -filetype=obj pie.s -o pie.o
+#   rust-lld -flavor gnu -pie --no-dynamic-linker pie.o -o pie.elf
+
+	.section .text
+	.globl _start
+	.type _start, @function
+_start:
+	lla a0, table
+	lw a0, 0(a0)
+	ret
+	.size _start, .-_start
+
+	.globl func
+	.type func, @function
+func:
+	ret
+	.size func, .-func
+
+	.section .data
+	.globl table
+	.type table, @object
+table:
+	.word _start
+	.word func + 4
+	.size table, 8
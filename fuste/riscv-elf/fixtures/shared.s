@@ -0,0 +1,32 @@
+# A shared object with R_RISCV_32 and R_RISCV_JUMP_SLOT relocations against its own symbols,
+# rebuild shared.elf with
+#
+#   llvm-mc -triple=riscv32 -mattr=-c,-relax -filetype=obj shared.s -o shared.o
+#   rust-lld -flavor gnu -shared shared.o -o shared.elf
+
+	.section .text
+	.globl _start
+	.type _start, @function
+_start:
+	call func@plt
+	ret
+	.size _start, .-_start
+
+	.globl func
+	.type func, @function
+func:
+	ret
+	.size func, .-func
+
+	.section .data
+	.globl pointer
+	.type pointer, @object
+pointer:
+	.word value + 8
+	.size pointer, 4
+
+	.globl value
+	.type value, @object
+value:
+	.word 7
+	.size value, 4
@@ -0,0 +1,8 @@
+# A 32-bit little-endian x86 object the loader must reject, rebuild x86.elf with
+#
+#   llvm-mc -triple=i386 -filetype=obj x86.s -o x86.elf
+
+	.section .text
+	.globl _start
+_start:
+	ret
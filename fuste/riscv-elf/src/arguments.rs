@@ -0,0 +1,170 @@
+use crate::ElfLoaderError;
+use fuste_riscv_core::machine::Machine;
+
+/// Ends the auxiliary vector.
+pub const AT_NULL: u32 = 0;
+/// The page size of the machine.
+pub const AT_PAGESZ: u32 = 6;
+/// The address of 16 random bytes.
+pub const AT_RANDOM: u32 = 25;
+
+/// The auxiliary vector entries passed after the environment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuxiliaryVector {
+	pub page_size: u32,
+	pub random_bytes: [u8; 16],
+}
+
+/// The arguments and environment laid out at the top of the stack before the program starts.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProgramArguments {
+	/// The arguments, starting with the program name.
+	pub args: Vec<String>,
+	pub env: Vec<(String, String)>,
+	/// The auxiliary vector, which is left out when `None`.
+	pub auxv: Option<AuxiliaryVector>,
+}
+
+impl ProgramArguments {
+	pub fn new(args: Vec<String>, env: Vec<(String, String)>) -> Self {
+		Self { args, env, auxv: None }
+	}
+
+	pub fn with_auxv(mut self, auxv: AuxiliaryVector) -> Self {
+		self.auxv = Some(auxv);
+		self
+	}
+
+	/// Writes the initial stack below `stack_end` as described by the RISC-V psABI and returns
+	/// the stack pointer, which points at `argc` and is 16-byte aligned.
+	///
+	/// The strings and random bytes go at the top, followed downwards by the auxiliary vector,
+	/// the `envp` and `argv` arrays and `argc`.
+	pub fn write<const MEMORY_SIZE: usize>(
+		&self,
+		machine: &mut Machine<MEMORY_SIZE>,
+		stack_end: u32,
+	) -> Result<u32, ElfLoaderError> {
+		let too_large = || ElfLoaderError::ArgumentsTooLarge { stack_end };
+		let mut cursor = stack_end;
+		let mut push = |machine: &mut Machine<MEMORY_SIZE>, bytes: &[u8]| {
+			cursor = cursor.checked_sub(bytes.len() as u32).ok_or_else(too_large)?;
+			machine.memory_mut().write_bytes(cursor, bytes)?;
+			Ok::<u32, ElfLoaderError>(cursor)
+		};
+
+		let mut argv = Vec::with_capacity(self.args.len());
+		for arg in &self.args {
+			argv.push(push(machine, format!("{arg}\0").as_bytes())?);
+		}
+
+		let mut envp = Vec::with_capacity(self.env.len());
+		for (name, value) in &self.env {
+			envp.push(push(machine, format!("{name}={value}\0").as_bytes())?);
+		}
+
+		let mut words = vec![argv.len() as u32];
+		words.extend(&argv);
+		words.push(0);
+		words.extend(&envp);
+		words.push(0);
+		if let Some(auxv) = &self.auxv {
+			let random_bytes = push(machine, &auxv.random_bytes)?;
+			words.extend([AT_PAGESZ, auxv.page_size, AT_RANDOM, random_bytes]);
+		}
+		words.extend([AT_NULL, 0]);
+
+		let size = words.len() as u32 * 4;
+		let stack_pointer = cursor.checked_sub(size).ok_or_else(too_large)? & !0b1111;
+		for (index, word) in words.iter().enumerate() {
+			machine.memory_mut().write_word(stack_pointer + index as u32 * 4, *word)?;
+		}
+
+		Ok(stack_pointer)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use fuste_args::{auxv, InitialStack};
+
+	const MEMORY_SIZE: usize = 0x2000;
+
+	const RANDOM_BYTES: [u8; 16] = *b"0123456789abcdef";
+
+	fn arguments() -> ProgramArguments {
+		ProgramArguments::new(
+			vec!["prog".to_string(), "--odd".to_string()],
+			vec![("HOME".to_string(), "/root".to_string()), ("EMPTY".to_string(), String::new())],
+		)
+		.with_auxv(AuxiliaryVector { page_size: 4096, random_bytes: RANDOM_BYTES })
+	}
+
+	/// Widens the guest's words from the stack pointer up to the end of the stack for the host,
+	/// pointing words which address the stack at the same bytes of the machine's memory.
+	fn host_words(memory: &[u8], stack_pointer: u32, stack_end: u32) -> Vec<usize> {
+		memory[stack_pointer as usize..stack_end as usize]
+			.chunks_exact(4)
+			.map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+			.map(|word| {
+				if (stack_pointer..stack_end).contains(&word) {
+					memory[word as usize..].as_ptr() as usize
+				} else {
+					word as usize
+				}
+			})
+			.collect()
+	}
+
+	#[test]
+	fn test_write_round_trip() -> Result<(), ElfLoaderError> {
+		let mut machine = Machine::<MEMORY_SIZE>::new();
+		let stack_end = 0x1ffc;
+		let stack_pointer = arguments().write(&mut machine, stack_end)?;
+
+		assert_eq!(stack_pointer % 16, 0);
+		assert_eq!(machine.memory().read_word(stack_pointer)?, 2);
+
+		let memory = machine.memory().read_bytes(0, MEMORY_SIZE as u32)?;
+		let words = host_words(memory, stack_pointer, stack_end);
+		let stack = unsafe { InitialStack::new(words.as_ptr()) };
+
+		assert_eq!(stack.args().collect::<Vec<_>>(), ["prog", "--odd"]);
+		assert_eq!(stack.vars().collect::<Vec<_>>(), [("HOME", "/root"), ("EMPTY", "")]);
+		assert_eq!(stack.auxv(auxv::AT_PAGESZ), Some(4096));
+
+		// AT_RANDOM points at the random bytes, which sit on the stack above the words.
+		let random_bytes =
+			stack.auxv(auxv::AT_RANDOM).map(|pointer| pointer - memory.as_ptr() as usize);
+		let random_bytes = random_bytes.and_then(|address| memory.get(address..address + 16));
+		assert_eq!(random_bytes, Some(&RANDOM_BYTES[..]));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_write_without_auxv() -> Result<(), ElfLoaderError> {
+		let mut machine = Machine::<MEMORY_SIZE>::new();
+		let arguments = ProgramArguments::new(vec!["prog".to_string()], Vec::new());
+		let stack_pointer = arguments.write(&mut machine, 0x2000)?;
+
+		let memory = machine.memory().read_bytes(0, MEMORY_SIZE as u32)?;
+		let words = host_words(memory, stack_pointer, 0x2000);
+		let stack = unsafe { InitialStack::new(words.as_ptr()) };
+
+		assert_eq!(stack.args().collect::<Vec<_>>(), ["prog"]);
+		assert_eq!(stack.vars().next(), None);
+		assert_eq!(stack.auxv(auxv::AT_RANDOM), None);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_write_too_large() {
+		let mut machine = Machine::<MEMORY_SIZE>::new();
+		let result = arguments().write(&mut machine, 0x20);
+
+		assert!(matches!(result, Err(ElfLoaderError::ArgumentsTooLarge { stack_end: 0x20 })));
+	}
+}
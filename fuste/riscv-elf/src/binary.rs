@@ -0,0 +1,111 @@
+use crate::arguments::ProgramArguments;
+use crate::program::LoadedProgram;
+use crate::{write_arguments, write_segment, ElfLoaderError};
+use fuste_riscv_core::machine::Machine;
+use std::fs;
+use std::path::Path;
+
+/// Loads raw binary images, such as those produced by `objcopy -O binary`, into a [Machine].
+///
+/// The image is copied to memory as is, so the load address and entrypoint have to be given.
+pub struct BinaryLoader {
+	load_address: u32,
+	entrypoint: u32,
+	arguments: Option<ProgramArguments>,
+}
+
+impl BinaryLoader {
+	/// Creates a loader which starts executing at the load address.
+	pub fn new(load_address: u32) -> Self {
+		Self { load_address, entrypoint: load_address, arguments: None }
+	}
+
+	pub fn with_entrypoint(mut self, entrypoint: u32) -> Self {
+		self.entrypoint = entrypoint;
+		self
+	}
+
+	/// Lays out the arguments at the end of memory and points the stack pointer at them.
+	pub fn with_arguments(mut self, arguments: ProgramArguments) -> Self {
+		self.arguments = Some(arguments);
+		self
+	}
+
+	pub fn load_address(&self) -> u32 {
+		self.load_address
+	}
+
+	pub fn entrypoint(&self) -> u32 {
+		self.entrypoint
+	}
+
+	/// Loads the binary at the given path and points the program counter at the entrypoint.
+	pub fn load_bin<const MEMORY_SIZE: usize>(
+		&self,
+		machine: &mut Machine<MEMORY_SIZE>,
+		path: impl AsRef<Path>,
+	) -> Result<LoadedProgram, ElfLoaderError> {
+		let buffer = fs::read(path.as_ref())?;
+		self.load_bin_bytes(machine, &buffer)
+	}
+
+	/// Loads a binary already in memory and points the program counter at the entrypoint.
+	///
+	/// Raw binaries carry no symbols, sections or debug information.
+	pub fn load_bin_bytes<const MEMORY_SIZE: usize>(
+		&self,
+		machine: &mut Machine<MEMORY_SIZE>,
+		bytes: &[u8],
+	) -> Result<LoadedProgram, ElfLoaderError> {
+		write_segment(machine, u64::from(self.load_address), bytes, bytes.len() as u64)?;
+		machine.registers_mut().program_counter_set(self.entrypoint);
+
+		if let Some(arguments) = &self.arguments {
+			write_arguments(machine, arguments, None)?;
+		}
+
+		Ok(LoadedProgram { entrypoint: self.entrypoint, ..Default::default() })
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const IMAGE: &[u8] = &[0x13, 0, 0, 0, 0x97, 0, 0, 0];
+
+	#[test]
+	fn test_load_bin_bytes() -> Result<(), ElfLoaderError> {
+		let mut machine = Machine::<0x2000>::new();
+
+		let program = BinaryLoader::new(0x1000).load_bin_bytes(&mut machine, IMAGE)?;
+
+		assert_eq!(program.entrypoint, 0x1000);
+		assert_eq!(machine.registers().program_counter(), 0x1000);
+		assert_eq!(machine.memory().read_bytes(0x1000, 8)?, IMAGE);
+		Ok(())
+	}
+
+	#[test]
+	fn test_load_bin_bytes_entrypoint() -> Result<(), ElfLoaderError> {
+		let mut machine = Machine::<0x2000>::new();
+
+		let program = BinaryLoader::new(0x1000)
+			.with_entrypoint(0x1004)
+			.load_bin_bytes(&mut machine, IMAGE)?;
+
+		assert_eq!(program.entrypoint, 0x1004);
+		assert_eq!(machine.registers().program_counter(), 0x1004);
+		assert_eq!(machine.memory().read_word(0x1004)?, 0x97);
+		Ok(())
+	}
+
+	#[test]
+	fn test_load_bin_bytes_out_of_bounds() {
+		let mut machine = Machine::<0x2000>::new();
+
+		let result = BinaryLoader::new(0x1ffc).load_bin_bytes(&mut machine, IMAGE);
+
+		assert!(result.is_err());
+	}
+}
@@ -0,0 +1,318 @@
+use crate::arguments::ProgramArguments;
+use crate::program::LoadedProgram;
+use crate::{write_arguments, write_segment, ElfLoaderError};
+use fuste_riscv_core::machine::Machine;
+use ihex::Record;
+use std::fs;
+use std::path::Path;
+
+/// The contents of an Intel HEX or Motorola S-record file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HexImage {
+	/// The data records as address and bytes, in file order.
+	pub chunks: Vec<(u32, Vec<u8>)>,
+	/// The address given by the start address record, if any.
+	pub start_address: Option<u32>,
+}
+
+impl HexImage {
+	/// Parses an Intel HEX or Motorola S-record file, telling them apart by their start code.
+	pub fn parse(text: &str) -> Result<Self, ElfLoaderError> {
+		match text.trim_start().chars().next() {
+			Some(':') => Self::parse_intel_hex(text),
+			Some('S') => Self::parse_srec(text),
+			_ => Err(ElfLoaderError::UnknownHexFormat),
+		}
+	}
+
+	/// Parses an Intel HEX file, including its extended segment and linear address records.
+	pub fn parse_intel_hex(text: &str) -> Result<Self, ElfLoaderError> {
+		let mut image = Self::default();
+		let mut upper_address = 0u32;
+
+		for record in ihex::Reader::new(text) {
+			match record? {
+				Record::Data { offset, value } => {
+					image.chunks.push((upper_address.wrapping_add(u32::from(offset)), value));
+				}
+				Record::ExtendedSegmentAddress(segment) => upper_address = u32::from(segment) << 4,
+				Record::ExtendedLinearAddress(upper) => upper_address = u32::from(upper) << 16,
+				Record::StartSegmentAddress { cs, ip } => {
+					image.start_address = Some((u32::from(cs) << 4) + u32::from(ip));
+				}
+				Record::StartLinearAddress(address) => image.start_address = Some(address),
+				Record::EndOfFile => break,
+			}
+		}
+
+		Ok(image)
+	}
+
+	/// Parses a Motorola S-record file.
+	///
+	/// Header and count records are skipped. Every record's byte count and checksum are checked.
+	pub fn parse_srec(text: &str) -> Result<Self, ElfLoaderError> {
+		let mut image = Self::default();
+
+		for (index, line) in text.lines().enumerate() {
+			let line = line.trim();
+			if line.is_empty() {
+				continue;
+			}
+
+			let error = |message: &str| ElfLoaderError::SRecord {
+				line: index + 1,
+				message: message.to_string(),
+			};
+
+			let record = line.strip_prefix('S').ok_or_else(|| error("missing start code"))?;
+			if !record.is_ascii() || record.len() < 3 || record.len() % 2 == 0 {
+				return Err(error("malformed record"));
+			}
+
+			let kind = record.as_bytes()[0];
+			let bytes = (1..record.len())
+				.step_by(2)
+				.map(|start| u8::from_str_radix(&record[start..start + 2], 16))
+				.collect::<Result<Vec<u8>, _>>()
+				.map_err(|_| error("invalid hex digit"))?;
+
+			// The byte count covers the address, data and checksum that follow it.
+			let (checksum, contents) =
+				bytes.split_last().ok_or_else(|| error("malformed record"))?;
+			let (count, payload) =
+				contents.split_first().ok_or_else(|| error("malformed record"))?;
+			if usize::from(*count) != payload.len() + 1 {
+				return Err(error("byte count doesn't match the record length"));
+			}
+
+			let sum = contents.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+			if !sum != *checksum {
+				return Err(error("checksum mismatch"));
+			}
+
+			let address_width = match kind {
+				b'0' | b'1' | b'5' | b'9' => 2,
+				b'2' | b'6' | b'8' => 3,
+				b'3' | b'7' => 4,
+				_ => return Err(error("unsupported record type")),
+			};
+			if payload.len() < address_width {
+				return Err(error("record too short for its address"));
+			}
+
+			let (address, data) = payload.split_at(address_width);
+			let address =
+				address.iter().fold(0u32, |address, byte| address << 8 | u32::from(*byte));
+
+			match kind {
+				b'1' | b'2' | b'3' => image.chunks.push((address, data.to_vec())),
+				b'7' | b'8' | b'9' => image.start_address = Some(address),
+				_ => (),
+			}
+		}
+
+		Ok(image)
+	}
+}
+
+/// Loads Intel HEX and Motorola S-record images into a [Machine].
+#[derive(Default)]
+pub struct HexLoader {
+	entrypoint: Option<u32>,
+	arguments: Option<ProgramArguments>,
+}
+
+impl HexLoader {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Starts executing at the given address instead of the file's start address.
+	pub fn with_entrypoint(mut self, entrypoint: u32) -> Self {
+		self.entrypoint = Some(entrypoint);
+		self
+	}
+
+	/// Lays out the arguments at the end of memory and points the stack pointer at them.
+	pub fn with_arguments(mut self, arguments: ProgramArguments) -> Self {
+		self.arguments = Some(arguments);
+		self
+	}
+
+	pub fn entrypoint(&self) -> Option<u32> {
+		self.entrypoint
+	}
+
+	/// Loads the hex file at the given path and points the program counter at its entrypoint.
+	pub fn load_hex<const MEMORY_SIZE: usize>(
+		&self,
+		machine: &mut Machine<MEMORY_SIZE>,
+		path: impl AsRef<Path>,
+	) -> Result<LoadedProgram, ElfLoaderError> {
+		let text = fs::read_to_string(path.as_ref())?;
+		self.load_hex_str(machine, &text)
+	}
+
+	/// Loads a hex file already in memory and points the program counter at its entrypoint.
+	///
+	/// Hex files carry no symbols, sections or debug information.
+	pub fn load_hex_str<const MEMORY_SIZE: usize>(
+		&self,
+		machine: &mut Machine<MEMORY_SIZE>,
+		text: &str,
+	) -> Result<LoadedProgram, ElfLoaderError> {
+		let image = HexImage::parse(text)?;
+		let entrypoint = self
+			.entrypoint
+			.or(image.start_address)
+			.ok_or(ElfLoaderError::MissingStartAddress)?;
+
+		for (address, data) in &image.chunks {
+			write_segment(machine, u64::from(*address), data, data.len() as u64)?;
+		}
+		machine.registers_mut().program_counter_set(entrypoint);
+
+		if let Some(arguments) = &self.arguments {
+			write_arguments(machine, arguments, None)?;
+		}
+
+		Ok(LoadedProgram { entrypoint, ..Default::default() })
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// A header, S1, S2 and S3 data records, a count record and an S9 start address record.
+	const SREC: &str = "S00600004844521B
+S107100013000000D5
+S206012000AABB73
+S3090001300078563412B1
+S5030003F9
+S9031000EC
+";
+
+	/// Data records under no, an extended linear and an extended segment address, and a start
+	/// linear address record.
+	const INTEL_HEX: &str = ":0410000013000000D9
+:020000040001F9
+:02200000AABB79
+:020000021000EC
+:01000400CC2F
+:0400000500012000D6
+:00000001FF
+";
+
+	fn srec_error(text: &str) -> Option<(usize, String)> {
+		match HexImage::parse_srec(text) {
+			Err(ElfLoaderError::SRecord { line, message }) => Some((line, message)),
+			_ => None,
+		}
+	}
+
+	#[test]
+	fn test_parse_srec() -> Result<(), ElfLoaderError> {
+		let image = HexImage::parse(SREC)?;
+
+		assert_eq!(
+			image.chunks,
+			vec![
+				(0x1000, vec![0x13, 0, 0, 0]),
+				(0x0001_2000, vec![0xaa, 0xbb]),
+				(0x0001_3000, vec![0x78, 0x56, 0x34, 0x12]),
+			]
+		);
+		assert_eq!(image.start_address, Some(0x1000));
+		Ok(())
+	}
+
+	#[test]
+	fn test_parse_srec_start_address_widths() -> Result<(), ElfLoaderError> {
+		assert_eq!(HexImage::parse_srec("S804012000DA")?.start_address, Some(0x0001_2000));
+		assert_eq!(HexImage::parse_srec("S70500013000C9")?.start_address, Some(0x0001_3000));
+		assert_eq!(HexImage::parse_srec("S107100013000000D5")?.start_address, None);
+		Ok(())
+	}
+
+	#[test]
+	fn test_parse_srec_rejects_bad_records() {
+		assert_eq!(
+			srec_error("S107100013000000D5\nS107100013000000D6"),
+			Some((2, "checksum mismatch".to_string()))
+		);
+		assert_eq!(
+			srec_error("S108100013000000D5"),
+			Some((1, "byte count doesn't match the record length".to_string()))
+		);
+		assert_eq!(srec_error("S4031000EC"), Some((1, "unsupported record type".to_string())));
+		assert_eq!(
+			srec_error("S3031000EC"),
+			Some((1, "record too short for its address".to_string()))
+		);
+		assert_eq!(srec_error("S1071000130000ZZD5"), Some((1, "invalid hex digit".to_string())));
+		assert_eq!(srec_error("S10710001300000"), Some((1, "malformed record".to_string())));
+		assert_eq!(srec_error(":0410000013000000D9"), Some((1, "missing start code".to_string())));
+	}
+
+	#[test]
+	fn test_parse_intel_hex() -> Result<(), ElfLoaderError> {
+		let image = HexImage::parse(INTEL_HEX)?;
+
+		assert_eq!(
+			image.chunks,
+			vec![
+				(0x1000, vec![0x13, 0, 0, 0]),
+				(0x0001_2000, vec![0xaa, 0xbb]),
+				(0x0001_0004, vec![0xcc]),
+			]
+		);
+		assert_eq!(image.start_address, Some(0x0001_2000));
+		Ok(())
+	}
+
+	#[test]
+	fn test_parse_intel_hex_rejects_bad_checksum() {
+		let result = HexImage::parse(":0410000013000000D8\n:00000001FF\n");
+
+		assert!(matches!(
+			result,
+			Err(ElfLoaderError::IntelHex(ihex::ReaderError::ChecksumMismatch(..)))
+		));
+	}
+
+	#[test]
+	fn test_parse_rejects_unknown_format() {
+		assert!(matches!(HexImage::parse("\x7fELF"), Err(ElfLoaderError::UnknownHexFormat)));
+		assert!(matches!(HexImage::parse(""), Err(ElfLoaderError::UnknownHexFormat)));
+	}
+
+	#[test]
+	fn test_load_hex_str() -> Result<(), ElfLoaderError> {
+		let mut machine = Machine::<0x2000>::new();
+
+		let program =
+			HexLoader::new().load_hex_str(&mut machine, "S107100013000000D5\nS9031000EC")?;
+
+		assert_eq!(program.entrypoint, 0x1000);
+		assert_eq!(machine.registers().program_counter(), 0x1000);
+		assert_eq!(machine.memory().read_word(0x1000)?, 0x13);
+		Ok(())
+	}
+
+	#[test]
+	fn test_load_hex_str_entrypoint() -> Result<(), ElfLoaderError> {
+		let mut machine = Machine::<0x2000>::new();
+
+		let result = HexLoader::new().load_hex_str(&mut machine, "S107100013000000D5");
+		assert!(matches!(result, Err(ElfLoaderError::MissingStartAddress)));
+
+		let program = HexLoader::new()
+			.with_entrypoint(0x1004)
+			.load_hex_str(&mut machine, "S107100013000000D5\nS9031000EC")?;
+		assert_eq!(program.entrypoint, 0x1004);
+		assert_eq!(machine.registers().program_counter(), 0x1004);
+		Ok(())
+	}
+}
@@ -1,6 +1,17 @@
+pub mod arguments;
+pub mod binary;
+pub mod hex;
+pub mod lines;
+pub mod program;
+pub mod relocations;
+pub mod sections;
+pub mod symbols;
+
+use arguments::ProgramArguments;
 use fuste_riscv_core::machine::memory::MemoryError;
 use fuste_riscv_core::machine::Machine;
-use goblin::elf::Elf;
+use goblin::elf::{header, program_header, Elf};
+use program::LoadedProgram;
 use std::fs;
 use std::path::Path;
 
@@ -16,12 +27,90 @@ pub enum ElfLoaderError {
 	MemoryError(#[from] MemoryError),
 	#[error("Invalid symbol name: {0}")]
 	InvalidSymbolName(String),
+	#[error("Failed to read DWARF debug information: {0}")]
+	Dwarf(#[from] gimli::Error),
 	#[error("Symbol name matching entrypoint \"{0}\" not found in ELF file")]
 	EntrypointNotFound(String),
+	#[error("Unsupported ELF class {0}, only 32-bit ELF files can be loaded")]
+	UnsupportedClass(u8),
+	#[error("Unsupported ELF data encoding {0}, only little-endian ELF files can be loaded")]
+	UnsupportedEndianness(u8),
+	#[error("Unsupported ELF machine {0}, only RISC-V ELF files can be loaded")]
+	UnsupportedMachine(u16),
+	#[error("Base address 0x{base_address:x} is not aligned to the {alignment} byte alignment of the image's segments")]
+	MisalignedBaseAddress { base_address: u32, alignment: u64 },
+	#[error("Unsupported relocation {kind} at offset 0x{offset:x}")]
+	UnsupportedRelocation { kind: String, offset: u64 },
+	#[error("Symbol \"{0}\" is imported, but guests can't be dynamically linked")]
+	UnresolvedSymbol(String),
+	#[error(
+		"Segment of {size} bytes at 0x{address:x} does not fit in {memory_size} bytes of memory"
+	)]
+	SegmentOutOfBounds { address: u64, size: u64, memory_size: usize },
+	#[error("Program arguments don't fit below the end of the stack at 0x{stack_end:x}")]
+	ArgumentsTooLarge { stack_end: u32 },
+	#[error("Invalid program manifest: {0}")]
+	Manifest(#[from] fuste_manifest::ManifestError),
+	#[error("Failed to parse Intel HEX file: {0}")]
+	IntelHex(#[from] ihex::ReaderError),
+	#[error("Failed to parse S-record on line {line}: {message}")]
+	SRecord { line: usize, message: String },
+	#[error("Unrecognized hex file format, expected Intel HEX or Motorola S-records")]
+	UnknownHexFormat,
+	#[error("Hex file has no start address and no entrypoint was given")]
+	MissingStartAddress,
+}
+
+fn malformed(message: String) -> ElfLoaderError {
+	ElfLoaderError::Parse(goblin::error::Error::Malformed(message))
 }
 
+/// Writes a segment of an image into memory, zero-filling it up to `size` bytes.
+fn write_segment<const MEMORY_SIZE: usize>(
+	machine: &mut Machine<MEMORY_SIZE>,
+	address: u64,
+	data: &[u8],
+	size: u64,
+) -> Result<(), ElfLoaderError> {
+	if address + size > MEMORY_SIZE as u64 {
+		return Err(ElfLoaderError::SegmentOutOfBounds { address, size, memory_size: MEMORY_SIZE });
+	}
+
+	// Write the file portion
+	machine.memory_mut().write_bytes(address as u32, data)?;
+
+	// Zero-fill remaining memory if necessary
+	if size > data.len() as u64 {
+		let zero_padding = vec![0u8; (size - data.len() as u64) as usize];
+		machine
+			.memory_mut()
+			.write_bytes((address + data.len() as u64) as u32, &zero_padding)?;
+	}
+
+	Ok(())
+}
+
+/// Lays out the arguments below the end of the stack, which defaults to the end of memory, and
+/// points the stack pointer at them.
+fn write_arguments<const MEMORY_SIZE: usize>(
+	machine: &mut Machine<MEMORY_SIZE>,
+	arguments: &ProgramArguments,
+	stack_end: Option<u32>,
+) -> Result<(), ElfLoaderError> {
+	let memory_end = u32::try_from(MEMORY_SIZE).unwrap_or(u32::MAX) & !0b1111;
+	let stack_pointer = arguments.write(machine, stack_end.unwrap_or(memory_end))?;
+	machine.registers_mut().set(2, stack_pointer);
+	Ok(())
+}
+
+/// Loads 32-bit little-endian RISC-V ELF files into a [Machine].
+///
+/// Position-independent (`ET_DYN`) images can be placed anywhere with
+/// [Elf32Loader::with_base_address], their dynamic relocations are applied after loading.
 pub struct Elf32Loader {
 	entrypoint_symbol_name: String,
+	base_address: u32,
+	arguments: Option<ProgramArguments>,
 }
 
 impl Default for Elf32Loader {
@@ -32,60 +121,245 @@ impl Default for Elf32Loader {
 
 impl Elf32Loader {
 	pub fn new(entrypoint_symbol_name: String) -> Self {
-		Self { entrypoint_symbol_name }
+		Self { entrypoint_symbol_name, base_address: 0, arguments: None }
+	}
+
+	/// Offsets every segment and the entrypoint by the given address.
+	pub fn with_base_address(mut self, base_address: u32) -> Self {
+		self.base_address = base_address;
+		self
+	}
+
+	/// Lays out the arguments at the top of the stack and points the stack pointer at them.
+	///
+	/// The stack ends at the `_stack_end` symbol, or at the end of memory if there is none.
+	/// Without arguments the stack pointer is left at zero for the program to set up.
+	pub fn with_arguments(mut self, arguments: ProgramArguments) -> Self {
+		self.arguments = Some(arguments);
+		self
 	}
 
+	pub fn entrypoint_symbol_name(&self) -> &str {
+		&self.entrypoint_symbol_name
+	}
+
+	pub fn base_address(&self) -> u32 {
+		self.base_address
+	}
+
+	pub fn arguments(&self) -> Option<&ProgramArguments> {
+		self.arguments.as_ref()
+	}
+
+	/// Checks that the ELF file targets the machine: 32-bit, little-endian RISC-V.
+	pub fn validate(elf: &Elf) -> Result<(), ElfLoaderError> {
+		let class = elf.header.e_ident[header::EI_CLASS];
+		if class != header::ELFCLASS32 {
+			return Err(ElfLoaderError::UnsupportedClass(class));
+		}
+
+		let data = elf.header.e_ident[header::EI_DATA];
+		if data != header::ELFDATA2LSB {
+			return Err(ElfLoaderError::UnsupportedEndianness(data));
+		}
+
+		if elf.header.e_machine != header::EM_RISCV {
+			return Err(ElfLoaderError::UnsupportedMachine(elf.header.e_machine));
+		}
+
+		Ok(())
+	}
+
+	/// Finds the address of the entrypoint symbol, before the base address is applied.
+	pub fn entrypoint(&self, elf: &Elf) -> Result<u32, ElfLoaderError> {
+		// Stripped position-independent images may only have their dynamic symbols left.
+		let tables = [(&elf.syms, &elf.strtab), (&elf.dynsyms, &elf.dynstrtab)];
+		for (syms, strtab) in tables {
+			for sym in syms.iter() {
+				let name = strtab
+					.get_at(sym.st_name)
+					.ok_or(ElfLoaderError::InvalidSymbolName(sym.st_name.to_string()))?;
+
+				if name == self.entrypoint_symbol_name {
+					return Ok(sym.st_value as u32);
+				}
+			}
+		}
+
+		Err(ElfLoaderError::EntrypointNotFound(self.entrypoint_symbol_name.clone()))
+	}
+
+	/// Loads the ELF file at the given path and points the program counter at its entrypoint.
+	///
+	/// Returns the symbols, sections and line table of the program as loaded.
 	pub fn load_elf<const MEMORY_SIZE: usize>(
 		&self,
 		machine: &mut Machine<MEMORY_SIZE>,
 		path: impl AsRef<Path>,
-	) -> Result<(), ElfLoaderError> {
-		// Read the ELF file into memory
+	) -> Result<LoadedProgram, ElfLoaderError> {
 		let buffer = fs::read(path.as_ref())?;
-		let elf = Elf::parse(buffer.as_slice())?;
-
-		// Normal symbol table
-		let mut flag_entrypoint_found = false;
-		for sym in elf.syms.iter() {
-			let name = elf
-				.strtab
-				.get_at(sym.st_name)
-				.ok_or(ElfLoaderError::InvalidSymbolName(sym.st_name.to_string()))?;
-
-			if name == "_start" {
-				flag_entrypoint_found = true;
-				break;
-			}
-		}
+		self.load_elf_bytes(machine, &buffer)
+	}
 
-		if !flag_entrypoint_found {
-			return Err(ElfLoaderError::EntrypointNotFound(self.entrypoint_symbol_name.clone()));
+	/// Loads an ELF file already in memory and points the program counter at its entrypoint.
+	pub fn load_elf_bytes<const MEMORY_SIZE: usize>(
+		&self,
+		machine: &mut Machine<MEMORY_SIZE>,
+		bytes: &[u8],
+	) -> Result<LoadedProgram, ElfLoaderError> {
+		let elf = Elf::parse(bytes)?;
+		Self::validate(&elf)?;
+
+		let entrypoint = self.entrypoint(&elf)?;
+
+		// Position-independent images still expect their segments to keep their alignment.
+		if elf.header.e_type == header::ET_DYN {
+			let alignment = elf
+				.program_headers
+				.iter()
+				.filter(|ph| ph.p_type == program_header::PT_LOAD)
+				.map(|ph| ph.p_align)
+				.max()
+				.unwrap_or(1)
+				.max(1);
+			if u64::from(self.base_address) % alignment != 0 {
+				return Err(ElfLoaderError::MisalignedBaseAddress {
+					base_address: self.base_address,
+					alignment,
+				});
+			}
 		}
 
 		for ph in &elf.program_headers {
-			if ph.p_type != goblin::elf::program_header::PT_LOAD {
+			if ph.p_type != program_header::PT_LOAD {
 				continue;
 			}
 
-			let start = ph.p_paddr as usize;
-			let file_size = ph.p_filesz as usize;
-			let mem_size = ph.p_memsz as usize;
-
-			// Write the file portion
-			let data = &buffer[ph.file_range()];
-			machine.memory_mut().write_bytes(start as u32, data)?;
-
-			// Zero-fill remaining memory if necessary
-			if mem_size > file_size {
-				let padding_size = mem_size - file_size;
-				let zero_padding = vec![0u8; padding_size];
-				machine.memory_mut().write_bytes((start + file_size) as u32, &zero_padding)?;
+			if ph.p_filesz > ph.p_memsz {
+				return Err(malformed(format!(
+					"segment at offset {} has more bytes in the file than in memory",
+					ph.p_offset
+				)));
 			}
+
+			let data = bytes.get(ph.file_range()).ok_or_else(|| {
+				malformed(format!(
+					"segment at offset {} runs past the end of the file",
+					ph.p_offset
+				))
+			})?;
+			let address = u64::from(self.base_address) + ph.p_vaddr;
+			write_segment(machine, address, data, ph.p_memsz)?;
 		}
 
+		// Position-independent images point at themselves through their dynamic relocations
+		relocations::apply(&elf, self.base_address, machine)?;
+
 		// set the program counter
-		let entry = elf.entry as u32;
-		machine.registers_mut().program_counter_set(entry);
+		let entrypoint = self.base_address.wrapping_add(entrypoint);
+		machine.registers_mut().program_counter_set(entrypoint);
+
+		let program = LoadedProgram::from_elf(&elf, bytes, entrypoint, self.base_address)?;
+
+		if let Some(arguments) = &self.arguments {
+			write_arguments(machine, arguments, program.stack_end())?;
+		}
+
+		Ok(program)
+	}
+}
+
+#[cfg(test)]
+pub mod tests {
+	use super::*;
+
+	pub const EXEC_ELF: &[u8] = include_bytes!("../fixtures/exec.elf");
+
+	/// The memory of the machine the fixtures are loaded into, see `fixtures/link.ld`.
+	pub const MEMORY_SIZE: usize = 0x8000;
+
+	/// The first instruction of `_start` in `fixtures/exec.elf`, `auipc ra, 0`.
+	pub const START_WORD: u32 = 0x0000_0097;
+
+	#[test]
+	fn test_validate_rejects_foreign_elf_files() {
+		let loader = Elf32Loader::default();
+		let mut machine = Machine::<MEMORY_SIZE>::new();
+
+		let result = loader.load_elf_bytes(&mut machine, include_bytes!("../fixtures/exec64.elf"));
+		assert!(matches!(result, Err(ElfLoaderError::UnsupportedClass(header::ELFCLASS64))));
+
+		let result =
+			loader.load_elf_bytes(&mut machine, include_bytes!("../fixtures/big-endian.elf"));
+		assert!(matches!(result, Err(ElfLoaderError::UnsupportedEndianness(header::ELFDATA2MSB))));
+
+		let result = loader.load_elf_bytes(&mut machine, include_bytes!("../fixtures/x86.elf"));
+		assert!(matches!(result, Err(ElfLoaderError::UnsupportedMachine(header::EM_386))));
+	}
+
+	#[test]
+	fn test_load_elf_bytes() -> Result<(), ElfLoaderError> {
+		let mut machine = Machine::<MEMORY_SIZE>::new();
+		// Dirty the bss so the zero-fill is observable.
+		machine.memory_mut().write_bytes(0x2004, &[0xff; 16])?;
+
+		let program = Elf32Loader::default().load_elf_bytes(&mut machine, EXEC_ELF)?;
+
+		assert_eq!(program.entrypoint, 0x1000);
+		assert_eq!(machine.registers().program_counter(), 0x1000);
+		assert_eq!(machine.memory().read_word(0x1000)?, START_WORD);
+		assert_eq!(machine.memory().read_word(0x2000)?, 0x1234_5678);
+		assert_eq!(machine.memory().read_bytes(0x2004, 16)?, &[0; 16]);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_segment_out_of_bounds() {
+		// The data segment of 0x14 bytes at 0x2000 starts right at the end of memory.
+		let mut machine = Machine::<0x2000>::new();
+		let result = Elf32Loader::default().load_elf_bytes(&mut machine, EXEC_ELF);
+
+		assert!(matches!(
+			result,
+			Err(ElfLoaderError::SegmentOutOfBounds {
+				address: 0x2000,
+				size: 0x14,
+				memory_size: 0x2000
+			})
+		));
+	}
+
+	#[test]
+	fn test_base_address() -> Result<(), ElfLoaderError> {
+		let mut machine = Machine::<MEMORY_SIZE>::new();
+		let program = Elf32Loader::default()
+			.with_base_address(0x3000)
+			.load_elf_bytes(&mut machine, EXEC_ELF)?;
+
+		assert_eq!(program.base_address, 0x3000);
+		assert_eq!(program.entrypoint, 0x4000);
+		assert_eq!(machine.registers().program_counter(), 0x4000);
+		assert_eq!(machine.memory().read_word(0x1000)?, 0);
+		assert_eq!(machine.memory().read_word(0x4000)?, START_WORD);
+		assert_eq!(machine.memory().read_word(0x5000)?, 0x1234_5678);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_entrypoint_symbol() -> Result<(), ElfLoaderError> {
+		let mut machine = Machine::<MEMORY_SIZE>::new();
+		let program =
+			Elf32Loader::new("main".to_string()).load_elf_bytes(&mut machine, EXEC_ELF)?;
+
+		assert_eq!(program.entrypoint, 0x1010);
+		assert_eq!(machine.registers().program_counter(), 0x1010);
+
+		let result = Elf32Loader::new("missing".to_string()).load_elf_bytes(&mut machine, EXEC_ELF);
+		assert!(
+			matches!(result, Err(ElfLoaderError::EntrypointNotFound(name)) if name == "missing")
+		);
 
 		Ok(())
 	}
@@ -0,0 +1,150 @@
+use crate::ElfLoaderError;
+use gimli::{Dwarf, EndianSlice, LittleEndian, SectionId};
+use goblin::elf::{section_header, Elf};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A row of the DWARF line table.
+///
+/// A row covers the addresses from its own up to the next row's.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineRow {
+	pub address: u32,
+	/// The index of the source file in [LineTable::files].
+	pub file: usize,
+	/// The source line, or zero if the instructions can't be attributed to a line.
+	pub line: u32,
+	/// Whether the row marks the first address after a sequence of instructions.
+	pub end_sequence: bool,
+}
+
+/// The address-ordered source line mapping recovered from `.debug_line`.
+#[derive(Debug, Clone, Default)]
+pub struct LineTable {
+	files: Vec<PathBuf>,
+	rows: Vec<LineRow>,
+}
+
+impl LineTable {
+	/// Creates a line table from the given files and rows.
+	pub fn new(files: Vec<PathBuf>, mut rows: Vec<LineRow>) -> Self {
+		// The end of one sequence may coincide with the start of the next, which should win.
+		rows.sort_by_key(|row| (row.address, !row.end_sequence));
+		Self { files, rows }
+	}
+
+	/// Reads the line programs of every compilation unit in a parsed ELF file.
+	///
+	/// Files without debug information produce an empty table.
+	pub fn from_elf(elf: &Elf, bytes: &[u8]) -> Result<Self, ElfLoaderError> {
+		let section = |id: SectionId| -> Result<EndianSlice<LittleEndian>, ElfLoaderError> {
+			let data = elf
+				.section_headers
+				.iter()
+				.find(|header| elf.shdr_strtab.get_at(header.sh_name) == Some(id.name()))
+				.filter(|header| header.sh_type != section_header::SHT_NOBITS)
+				.and_then(|header| header.file_range())
+				.and_then(|range| bytes.get(range))
+				.unwrap_or_default();
+			Ok(EndianSlice::new(data, LittleEndian))
+		};
+		let dwarf = Dwarf::load(section)?;
+
+		let mut files = Vec::new();
+		let mut file_indices = HashMap::<PathBuf, usize>::new();
+		let mut rows = Vec::new();
+
+		let mut units = dwarf.units();
+		while let Some(header) = units.next()? {
+			let unit = dwarf.unit(header)?;
+			let Some(program) = unit.line_program.clone() else {
+				continue;
+			};
+
+			let mut program_rows = program.rows();
+			while let Some((header, row)) = program_rows.next_row()? {
+				let path = match row.file(header) {
+					Some(file) => {
+						let mut path = PathBuf::new();
+						if let Some(comp_dir) = &unit.comp_dir {
+							path.push(comp_dir.to_string_lossy().as_ref());
+						}
+						if let Some(directory) = file.directory(header) {
+							path.push(
+								dwarf.attr_string(&unit, directory)?.to_string_lossy().as_ref(),
+							);
+						}
+						path.push(
+							dwarf.attr_string(&unit, file.path_name())?.to_string_lossy().as_ref(),
+						);
+						path
+					}
+					None => PathBuf::new(),
+				};
+
+				let file = *file_indices.entry(path.clone()).or_insert_with(|| {
+					files.push(path);
+					files.len() - 1
+				});
+
+				rows.push(LineRow {
+					address: row.address() as u32,
+					file,
+					line: row.line().map(|line| line.get() as u32).unwrap_or(0),
+					end_sequence: row.end_sequence(),
+				});
+			}
+		}
+
+		Ok(Self::new(files, rows))
+	}
+
+	/// Parses the ELF bytes and reads their line table.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, ElfLoaderError> {
+		let elf = Elf::parse(bytes)?;
+		Self::from_elf(&elf, bytes)
+	}
+
+	/// Reads the ELF file at the given path and reads its line table.
+	pub fn from_path(path: impl AsRef<Path>) -> Result<Self, ElfLoaderError> {
+		let buffer = fs::read(path.as_ref())?;
+		Self::from_bytes(&buffer)
+	}
+
+	/// Moves every row by the base address an image was loaded at.
+	pub fn rebase(&mut self, base_address: u32) {
+		for row in &mut self.rows {
+			row.address = row.address.wrapping_add(base_address);
+		}
+	}
+
+	/// The source files referenced by the rows.
+	pub fn files(&self) -> &[PathBuf] {
+		&self.files
+	}
+
+	/// Gets the path of the source file with the given index.
+	pub fn file(&self, index: usize) -> Option<&Path> {
+		self.files.get(index).map(PathBuf::as_path)
+	}
+
+	/// Iterates over the rows which start a range of instructions, in address order.
+	pub fn rows(&self) -> impl Iterator<Item = &LineRow> {
+		self.rows.iter().filter(|row| !row.end_sequence)
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.rows.is_empty()
+	}
+
+	/// Finds the row covering the address.
+	pub fn lookup(&self, address: u32) -> Option<&LineRow> {
+		let end = self.rows.partition_point(|row| row.address <= address);
+		let row = self.rows[..end].last()?;
+		if row.end_sequence {
+			return None;
+		}
+		Some(row)
+	}
+}
@@ -0,0 +1,164 @@
+use crate::lines::LineTable;
+use crate::sections::{Section, SectionTable};
+use crate::symbols::{Symbol, SymbolTable};
+use crate::ElfLoaderError;
+use fuste_manifest::Manifest;
+use goblin::elf::{section_header, Elf};
+use std::fmt::{self, Display};
+
+/// What the host knows about a program after loading it.
+///
+/// Every address is where the program ended up in memory, so the base address has already been
+/// applied to the symbols, sections and line rows.
+#[derive(Debug, Clone, Default)]
+pub struct LoadedProgram {
+	pub entrypoint: u32,
+	pub base_address: u32,
+	pub symbols: SymbolTable,
+	pub sections: SectionTable,
+	/// The DWARF line table, if the program was built with debug information.
+	pub lines: Option<LineTable>,
+	/// The requirements declared in the `.fuste.manifest` section, if the program has one.
+	pub manifest: Option<Manifest>,
+}
+
+/// Reads the manifest section of a parsed ELF file.
+pub fn read_manifest(elf: &Elf, bytes: &[u8]) -> Result<Option<Manifest>, ElfLoaderError> {
+	let Some(header) = elf.section_headers.iter().find(|header| {
+		elf.shdr_strtab.get_at(header.sh_name) == Some(fuste_manifest::SECTION_NAME)
+	}) else {
+		return Ok(None);
+	};
+
+	let data = header
+		.file_range()
+		.filter(|_| header.sh_type != section_header::SHT_NOBITS)
+		.and_then(|range| bytes.get(range))
+		.unwrap_or_default();
+	Ok(Some(Manifest::from_bytes(data)?))
+}
+
+impl LoadedProgram {
+	/// Collects the symbols, sections, line table and manifest of a parsed ELF file loaded at the
+	/// base address.
+	///
+	/// Unreadable debug information is treated the same as none at all, since it is only a nicety.
+	/// A malformed manifest is an error, since the host can't tell what the program needs.
+	pub fn from_elf(
+		elf: &Elf,
+		bytes: &[u8],
+		entrypoint: u32,
+		base_address: u32,
+	) -> Result<Self, ElfLoaderError> {
+		let mut symbols = SymbolTable::from_elf(elf);
+		symbols.rebase(base_address);
+
+		let mut sections = SectionTable::from_elf(elf);
+		sections.rebase(base_address);
+
+		let lines = LineTable::from_elf(elf, bytes).ok().filter(|lines| !lines.is_empty()).map(
+			|mut lines| {
+				lines.rebase(base_address);
+				lines
+			},
+		);
+
+		let manifest = read_manifest(elf, bytes)?;
+
+		Ok(Self { entrypoint, base_address, symbols, sections, lines, manifest })
+	}
+
+	/// Gets the allocated section with the given name, e.g. `.text` or `.bss`.
+	pub fn section(&self, name: &str) -> Option<&Section> {
+		self.sections.get(name)
+	}
+
+	/// Gets the symbol with the given name.
+	pub fn symbol(&self, name: &str) -> Option<&Symbol> {
+		self.symbols.get(name)
+	}
+
+	/// The top of the stack, as placed by the linker script.
+	pub fn stack_end(&self) -> Option<u32> {
+		self.symbol("_stack_end").map(|symbol| symbol.address)
+	}
+
+	/// Formats the address with the symbol containing it, e.g. `0x00001040 <main+0x20>`.
+	pub fn symbolize(&self, address: u32) -> SymbolizedAddress<'_> {
+		SymbolizedAddress { symbols: &self.symbols, address }
+	}
+}
+
+/// An address displayed along with the symbol it falls in, see [LoadedProgram::symbolize].
+pub struct SymbolizedAddress<'a> {
+	symbols: &'a SymbolTable,
+	address: u32,
+}
+
+impl<'a> SymbolizedAddress<'a> {
+	pub fn new(symbols: &'a SymbolTable, address: u32) -> Self {
+		Self { symbols, address }
+	}
+}
+
+impl Display for SymbolizedAddress<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "0x{:08x}", self.address)?;
+		match self.symbols.symbolize(self.address) {
+			Some((symbol, 0)) => write!(f, " <{}>", symbol.demangled_name()),
+			Some((symbol, offset)) => write!(f, " <{}+0x{offset:x}>", symbol.demangled_name()),
+			None => Ok(()),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::EXEC_ELF;
+
+	fn symbols() -> SymbolTable {
+		SymbolTable::new(vec![
+			Symbol { name: "_start".to_string(), address: 0x1000, size: 0x20 },
+			Symbol { name: "main".to_string(), address: 0x1020, size: 0x40 },
+		])
+	}
+
+	#[test]
+	fn test_symbolize_exact_symbol() {
+		let symbols = symbols();
+		assert_eq!(SymbolizedAddress::new(&symbols, 0x1020).to_string(), "0x00001020 <main>");
+	}
+
+	#[test]
+	fn test_symbolize_offset_in_symbol() {
+		let symbols = symbols();
+		assert_eq!(SymbolizedAddress::new(&symbols, 0x1040).to_string(), "0x00001040 <main+0x20>");
+	}
+
+	#[test]
+	fn test_symbolize_outside_symbols() {
+		let symbols = symbols();
+		assert_eq!(SymbolizedAddress::new(&symbols, 0x0800).to_string(), "0x00000800");
+		assert_eq!(SymbolizedAddress::new(&symbols, 0x1060).to_string(), "0x00001060");
+	}
+
+	#[test]
+	fn test_rebased_tables() -> Result<(), ElfLoaderError> {
+		let elf = Elf::parse(EXEC_ELF)?;
+		let program = LoadedProgram::from_elf(&elf, EXEC_ELF, 0x4000, 0x3000)?;
+
+		assert_eq!(program.symbol("main").map(|symbol| symbol.address), Some(0x4010));
+		assert_eq!(program.symbolize(0x4018).to_string(), "0x00004018 <main+0x8>");
+
+		assert_eq!(program.section(".text").map(|section| section.address), Some(0x4000));
+		assert_eq!(program.section(".data").map(|section| section.address), Some(0x5000));
+
+		// `main` starts on line 18 of `fixtures/exec.s`.
+		let lines = program.lines.as_ref();
+		assert_eq!(lines.and_then(|lines| lines.lookup(0x4010)).map(|row| row.line), Some(18));
+		assert_eq!(lines.and_then(|lines| lines.lookup(0x1010)), None);
+
+		Ok(())
+	}
+}
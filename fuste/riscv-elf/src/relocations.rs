@@ -0,0 +1,138 @@
+use crate::ElfLoaderError;
+use fuste_riscv_core::machine::Machine;
+use goblin::elf::header::EM_RISCV;
+use goblin::elf::reloc::{self, Reloc};
+use goblin::elf::section_header::SHN_ABS;
+use goblin::elf::Elf;
+
+/// Resolves the address of a dynamic symbol in an image loaded at the base address.
+fn symbol_address(elf: &Elf, base_address: u32, relocation: &Reloc) -> Result<u32, ElfLoaderError> {
+	let symbol = elf
+		.dynsyms
+		.get(relocation.r_sym)
+		.ok_or(ElfLoaderError::InvalidSymbolName(relocation.r_sym.to_string()))?;
+
+	// Guests are self-contained, so there is nothing to import a symbol from.
+	if symbol.is_import() {
+		let name = elf.dynstrtab.get_at(symbol.st_name).unwrap_or_default();
+		return Err(ElfLoaderError::UnresolvedSymbol(name.to_string()));
+	}
+
+	if symbol.st_shndx == SHN_ABS as usize {
+		Ok(symbol.st_value as u32)
+	} else {
+		Ok(base_address.wrapping_add(symbol.st_value as u32))
+	}
+}
+
+/// Applies the dynamic relocations of an image loaded at the base address.
+///
+/// Addends come from the relocation entries for `.rela` sections and from the relocated words
+/// for `.rel` sections. Returns the number of relocations applied.
+pub fn apply<const MEMORY_SIZE: usize>(
+	elf: &Elf,
+	base_address: u32,
+	machine: &mut Machine<MEMORY_SIZE>,
+) -> Result<usize, ElfLoaderError> {
+	let mut applied = 0;
+
+	for relocation in elf.dynrelas.iter().chain(elf.dynrels.iter()).chain(elf.pltrelocs.iter()) {
+		let address = base_address.wrapping_add(relocation.r_offset as u32);
+		let addend = match relocation.r_addend {
+			Some(addend) => addend as u32,
+			None => machine.memory().read_word(address)?,
+		};
+
+		let value = match relocation.r_type {
+			reloc::R_RISCV_NONE => continue,
+			reloc::R_RISCV_RELATIVE => base_address.wrapping_add(addend),
+			reloc::R_RISCV_32 => {
+				symbol_address(elf, base_address, &relocation)?.wrapping_add(addend)
+			}
+			reloc::R_RISCV_JUMP_SLOT => symbol_address(elf, base_address, &relocation)?,
+			r_type => {
+				return Err(ElfLoaderError::UnsupportedRelocation {
+					kind: reloc::r_to_str(r_type, EM_RISCV).to_string(),
+					offset: relocation.r_offset,
+				});
+			}
+		};
+
+		machine.memory_mut().write_word(address, value)?;
+		applied += 1;
+	}
+
+	Ok(applied)
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::program::LoadedProgram;
+	use crate::tests::MEMORY_SIZE;
+	use crate::{Elf32Loader, ElfLoaderError};
+	use fuste_riscv_core::machine::{Machine, MachineSystem};
+	use fuste_riscv_core::plugins::rv32i_computer::Rv32iComputer;
+
+	const PIE_ELF: &[u8] = include_bytes!("../fixtures/pie.elf");
+	const SHARED_ELF: &[u8] = include_bytes!("../fixtures/shared.elf");
+
+	fn address(program: &LoadedProgram, name: &str) -> Result<u32, String> {
+		program
+			.symbol(name)
+			.map(|symbol| symbol.address)
+			.ok_or(format!("no symbol {name}"))
+	}
+
+	#[test]
+	fn test_relative_relocations() -> Result<(), Box<dyn std::error::Error>> {
+		let mut machine = Machine::<MEMORY_SIZE>::new();
+		let program = Elf32Loader::default()
+			.with_base_address(0x4000)
+			.load_elf_bytes(&mut machine, PIE_ELF)?;
+
+		let start = address(&program, "_start")?;
+		let table = address(&program, "table")?;
+		assert_eq!(start, 0x4000 + 0x1188);
+		assert_eq!(machine.memory().read_word(table)?, start);
+		assert_eq!(machine.memory().read_word(table + 4)?, address(&program, "func")? + 4);
+
+		// `_start` loads the first word of the table through a PC-relative address.
+		for _ in 0..3 {
+			let _control_flow = Rv32iComputer.tick(&mut machine)?;
+		}
+		assert_eq!(machine.registers().get(10), start);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_symbol_relocations() -> Result<(), Box<dyn std::error::Error>> {
+		let mut machine = Machine::<MEMORY_SIZE>::new();
+		let program = Elf32Loader::default()
+			.with_base_address(0x4000)
+			.load_elf_bytes(&mut machine, SHARED_ELF)?;
+
+		// R_RISCV_32 against `value` with an addend of 8.
+		let pointer = address(&program, "pointer")?;
+		assert_eq!(machine.memory().read_word(pointer)?, address(&program, "value")? + 8);
+
+		// R_RISCV_JUMP_SLOT binds the PLT entry of `func` straight to it.
+		let plt_slot = 0x4000 + 0x32e0;
+		assert_eq!(machine.memory().read_word(plt_slot)?, address(&program, "func")?);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_misaligned_base_address() {
+		let mut machine = Machine::<MEMORY_SIZE>::new();
+		let result = Elf32Loader::default()
+			.with_base_address(0x800)
+			.load_elf_bytes(&mut machine, PIE_ELF);
+
+		assert!(matches!(
+			result,
+			Err(ElfLoaderError::MisalignedBaseAddress { base_address: 0x800, alignment: 0x1000 })
+		));
+	}
+}
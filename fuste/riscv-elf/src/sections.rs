@@ -0,0 +1,95 @@
+use crate::ElfLoaderError;
+use goblin::elf::{section_header, Elf};
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+/// A section which occupies memory when the program is loaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+	pub name: String,
+	pub address: u32,
+	pub size: u32,
+	/// Whether the section contains instructions.
+	pub executable: bool,
+}
+
+impl Section {
+	/// The address range occupied by the section.
+	pub fn range(&self) -> Range<u32> {
+		self.address..self.address.saturating_add(self.size)
+	}
+}
+
+/// The address-ordered allocated sections of an ELF file.
+#[derive(Debug, Clone, Default)]
+pub struct SectionTable {
+	sections: Vec<Section>,
+}
+
+impl SectionTable {
+	/// Creates a section table from the given sections.
+	pub fn new(mut sections: Vec<Section>) -> Self {
+		sections.sort_by(|a, b| a.address.cmp(&b.address).then_with(|| a.name.cmp(&b.name)));
+		Self { sections }
+	}
+
+	/// Collects the sections of a parsed ELF file which are allocated at runtime.
+	pub fn from_elf(elf: &Elf) -> Self {
+		let sections = elf
+			.section_headers
+			.iter()
+			.filter(|header| header.sh_flags & section_header::SHF_ALLOC as u64 != 0)
+			.map(|header| Section {
+				name: elf.shdr_strtab.get_at(header.sh_name).unwrap_or_default().to_string(),
+				address: header.sh_addr as u32,
+				size: header.sh_size as u32,
+				executable: header.sh_flags & section_header::SHF_EXECINSTR as u64 != 0,
+			})
+			.collect();
+
+		Self::new(sections)
+	}
+
+	/// Parses the ELF bytes and collects their allocated sections.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, ElfLoaderError> {
+		let elf = Elf::parse(bytes)?;
+		Ok(Self::from_elf(&elf))
+	}
+
+	/// Reads the ELF file at the given path and collects its allocated sections.
+	pub fn from_path(path: impl AsRef<Path>) -> Result<Self, ElfLoaderError> {
+		let buffer = fs::read(path.as_ref())?;
+		Self::from_bytes(&buffer)
+	}
+
+	/// Moves every section by the base address an image was loaded at.
+	pub fn rebase(&mut self, base_address: u32) {
+		for section in &mut self.sections {
+			section.address = section.address.wrapping_add(base_address);
+		}
+	}
+
+	/// Iterates over the sections in address order.
+	pub fn iter(&self) -> impl Iterator<Item = &Section> {
+		self.sections.iter()
+	}
+
+	/// Iterates over the sections which contain instructions.
+	pub fn executable(&self) -> impl Iterator<Item = &Section> {
+		self.sections.iter().filter(|section| section.executable)
+	}
+
+	pub fn len(&self) -> usize {
+		self.sections.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.sections.is_empty()
+	}
+
+	/// Gets the section with the given name.
+	pub fn get(&self, name: &str) -> Option<&Section> {
+		self.sections.iter().find(|section| section.name == name)
+	}
+}
@@ -0,0 +1,148 @@
+use crate::ElfLoaderError;
+use goblin::elf::{sym, Elf};
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+/// A named symbol recovered from the ELF symbol table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+	pub name: String,
+	pub address: u32,
+	/// The size recorded in the symbol table.
+	///
+	/// Symbols defined in assembly or by the linker script often have a size of zero.
+	pub size: u32,
+}
+
+impl Symbol {
+	/// The demangled name of the symbol, without the Rust hash suffix.
+	///
+	/// Names which are not mangled are returned as is.
+	pub fn demangled_name(&self) -> String {
+		format!("{:#}", rustc_demangle::demangle(&self.name))
+	}
+
+	/// Whether the address falls within the recorded size of the symbol.
+	pub fn contains(&self, address: u32) -> bool {
+		address >= self.address && address - self.address < self.size
+	}
+}
+
+/// The address-ordered symbol table of an ELF file.
+#[derive(Debug, Clone, Default)]
+pub struct SymbolTable {
+	symbols: Vec<Symbol>,
+}
+
+impl SymbolTable {
+	/// Creates a symbol table from the given symbols.
+	pub fn new(mut symbols: Vec<Symbol>) -> Self {
+		symbols.sort_by(|a, b| a.address.cmp(&b.address).then_with(|| a.name.cmp(&b.name)));
+		Self { symbols }
+	}
+
+	/// Collects the named function, object and untyped symbols of a parsed ELF file.
+	///
+	/// Section and file symbols are skipped, as are mapping symbols and assembler-local labels.
+	pub fn from_elf(elf: &Elf) -> Self {
+		let symbols = elf
+			.syms
+			.iter()
+			.filter(|sym| {
+				matches!(sym.st_type(), sym::STT_FUNC | sym::STT_OBJECT | sym::STT_NOTYPE)
+			})
+			.filter_map(|sym| {
+				let name = elf.strtab.get_at(sym.st_name)?;
+				if name.is_empty() || name.starts_with('$') || name.starts_with(".L") {
+					return None;
+				}
+				Some(Symbol {
+					name: name.to_string(),
+					address: sym.st_value as u32,
+					size: sym.st_size as u32,
+				})
+			})
+			.collect();
+
+		Self::new(symbols)
+	}
+
+	/// Parses the ELF bytes and collects their symbol table.
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self, ElfLoaderError> {
+		let elf = Elf::parse(bytes)?;
+		Ok(Self::from_elf(&elf))
+	}
+
+	/// Reads the ELF file at the given path and collects its symbol table.
+	pub fn from_path(path: impl AsRef<Path>) -> Result<Self, ElfLoaderError> {
+		let buffer = fs::read(path.as_ref())?;
+		Self::from_bytes(&buffer)
+	}
+
+	/// Moves every symbol by the base address an image was loaded at.
+	pub fn rebase(&mut self, base_address: u32) {
+		for symbol in &mut self.symbols {
+			symbol.address = symbol.address.wrapping_add(base_address);
+		}
+	}
+
+	/// Iterates over the symbols in address order.
+	pub fn iter(&self) -> impl Iterator<Item = &Symbol> {
+		self.symbols.iter()
+	}
+
+	pub fn len(&self) -> usize {
+		self.symbols.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.symbols.is_empty()
+	}
+
+	/// Gets the symbol with the given name.
+	pub fn get(&self, name: &str) -> Option<&Symbol> {
+		self.symbols.iter().find(|symbol| symbol.name == name)
+	}
+
+	/// Gets the address range covered by the symbol with the given name.
+	///
+	/// Symbols without a recorded size extend to the next symbol at a higher address.
+	pub fn range(&self, name: &str) -> Option<Range<u32>> {
+		let symbol = self.get(name)?;
+		if symbol.size > 0 {
+			return Some(symbol.address..symbol.address.saturating_add(symbol.size));
+		}
+
+		let end = self
+			.symbols
+			.iter()
+			.map(|other| other.address)
+			.find(|address| *address > symbol.address)
+			.unwrap_or(u32::MAX);
+		Some(symbol.address..end)
+	}
+
+	/// Finds the symbol containing the address and the offset of the address within it.
+	///
+	/// Sized symbols which contain the address are preferred. Otherwise the nearest symbol
+	/// without a recorded size is taken to extend to the address, as in [SymbolTable::range].
+	pub fn symbolize(&self, address: u32) -> Option<(&Symbol, u32)> {
+		let end = self.symbols.partition_point(|symbol| symbol.address <= address);
+		let candidates = &self.symbols[..end];
+		let nearest = candidates.last()?.address;
+
+		candidates
+			.iter()
+			.rev()
+			.find(|symbol| symbol.contains(address))
+			.or_else(|| {
+				candidates
+					.iter()
+					.rev()
+					.take_while(|symbol| symbol.address == nearest)
+					.find(|symbol| symbol.size == 0)
+			})
+			.map(|symbol| (symbol, address - symbol.address))
+	}
+}
@@ -0,0 +1,239 @@
+//! Differential checks of `fuste-riscv-core` against the reference [State].
+use crate::{State, Trap};
+use core::fmt::{self, Display};
+use fuste_riscv_core::instructions::rv32i::Rv32iInstruction;
+use fuste_riscv_core::instructions::ExecutableInstructionError;
+use fuste_riscv_core::machine::Machine;
+
+/// A disagreement between the machine and the reference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mismatch {
+	/// Exactly one of the two accepts the word.
+	Decode {
+		word: u32,
+		reference: bool,
+		machine: bool,
+	},
+	/// The decoded instruction encodes back to a different word.
+	RoundTrip {
+		word: u32,
+		encoded: u32,
+	},
+	/// The two disagree on whether, or how, the instruction traps.
+	Outcome {
+		word: u32,
+		reference: Result<(), Trap>,
+		machine: Result<(), Trap>,
+	},
+	Register {
+		word: u32,
+		index: usize,
+		reference: u32,
+		machine: u32,
+	},
+	ProgramCounter {
+		word: u32,
+		reference: u32,
+		machine: u32,
+	},
+	Memory {
+		word: u32,
+		address: u32,
+		reference: u8,
+		machine: u8,
+	},
+}
+
+impl Display for Mismatch {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Mismatch::Decode { word, reference, machine } => write!(
+				f,
+				"0x{word:08x}: reference decodes {reference}, machine decodes {machine}"
+			),
+			Mismatch::RoundTrip { word, encoded } => {
+				write!(f, "0x{word:08x}: encodes back to 0x{encoded:08x}")
+			}
+			Mismatch::Outcome { word, reference, machine } => {
+				write!(f, "0x{word:08x}: reference {reference:?}, machine {machine:?}")
+			}
+			Mismatch::Register { word, index, reference, machine } => write!(
+				f,
+				"0x{word:08x}: x{index} is 0x{reference:08x} in the reference, 0x{machine:08x} in the machine"
+			),
+			Mismatch::ProgramCounter { word, reference, machine } => write!(
+				f,
+				"0x{word:08x}: pc is 0x{reference:08x} in the reference, 0x{machine:08x} in the machine"
+			),
+			Mismatch::Memory { word, address, reference, machine } => write!(
+				f,
+				"0x{word:08x}: byte 0x{address:08x} is 0x{reference:02x} in the reference, 0x{machine:02x} in the machine"
+			),
+		}
+	}
+}
+
+impl From<ExecutableInstructionError> for Trap {
+	fn from(error: ExecutableInstructionError) -> Self {
+		match error {
+			ExecutableInstructionError::EbreakInterrupt(_) => Trap::Ebreak,
+			ExecutableInstructionError::EcallInterrupt(_) => Trap::Ecall,
+			ExecutableInstructionError::InvalidInstruction(_) => Trap::IllegalInstruction,
+			ExecutableInstructionError::MemoryError(_) => Trap::AccessFault,
+		}
+	}
+}
+
+/// Checks that every word the machine decodes encodes back to itself.
+pub fn check_round_trip<const MEMORY_SIZE: usize>(word: u32) -> Result<(), Mismatch> {
+	if let Ok(instruction) = Rv32iInstruction::<MEMORY_SIZE>::from_word(word) {
+		let encoded = instruction.to_word();
+		if encoded != word {
+			return Err(Mismatch::RoundTrip { word, encoded });
+		}
+	}
+	Ok(())
+}
+
+/// Executes the word on the machine and the reference from the same state and compares them.
+///
+/// The memory is truncated or zero-padded to `MEMORY_SIZE` bytes.
+/// The state after a trap is not compared, since the machine leaves it to the systems
+/// handling the trap.
+pub fn check<const MEMORY_SIZE: usize>(
+	word: u32,
+	registers: [u32; 32],
+	pc: u32,
+	memory: &[u8],
+) -> Result<(), Mismatch> {
+	let mut reference = State::new(MEMORY_SIZE);
+	reference.registers = registers;
+	reference.registers[0] = 0;
+	reference.pc = pc;
+	let length = memory.len().min(MEMORY_SIZE);
+	reference.memory[..length].copy_from_slice(&memory[..length]);
+
+	let mut machine = Machine::<MEMORY_SIZE>::new();
+	machine.memory_mut().memory.copy_from_slice(&reference.memory);
+	for (index, value) in reference.registers.iter().enumerate() {
+		machine.registers_mut().set(index as u8, *value);
+	}
+	machine.registers_mut().program_counter_set(pc);
+
+	let instruction = Rv32iInstruction::<MEMORY_SIZE>::from_word(word);
+	let legal = crate::is_legal(word);
+	if instruction.is_ok() != legal {
+		return Err(Mismatch::Decode { word, reference: legal, machine: instruction.is_ok() });
+	}
+	let Ok(instruction) = instruction else {
+		return Ok(());
+	};
+
+	let reference_outcome = reference.step(word);
+	let machine_outcome = instruction.execute(&mut machine).map_err(Trap::from);
+	if reference_outcome != machine_outcome {
+		return Err(Mismatch::Outcome {
+			word,
+			reference: reference_outcome,
+			machine: machine_outcome,
+		});
+	}
+	if reference_outcome.is_err() {
+		return Ok(());
+	}
+
+	for index in 0..32 {
+		let (expected, actual) = (reference.registers[index], machine.registers().get(index));
+		if expected != actual {
+			return Err(Mismatch::Register { word, index, reference: expected, machine: actual });
+		}
+	}
+
+	let actual_pc = machine.registers().program_counter();
+	if reference.pc != actual_pc {
+		return Err(Mismatch::ProgramCounter { word, reference: reference.pc, machine: actual_pc });
+	}
+
+	let bytes = reference.memory.iter().zip(machine.memory().memory.iter());
+	for (address, (expected, actual)) in bytes.enumerate() {
+		if expected != actual {
+			return Err(Mismatch::Memory {
+				word,
+				address: address as u32,
+				reference: *expected,
+				machine: *actual,
+			});
+		}
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const MEMORY_SIZE: usize = 64;
+
+	const OPCODES: [u32; 11] = [
+		0b0110111, 0b0010111, 0b1101111, 0b1100111, 0b1100011, 0b0000011, 0b0100011, 0b0010011,
+		0b0110011, 0b0001111, 0b1110011,
+	];
+
+	/// A xorshift generator, so the sweeps are reproducible without extra dependencies.
+	struct Xorshift(u64);
+
+	impl Xorshift {
+		fn next(&mut self) -> u32 {
+			self.0 ^= self.0 << 13;
+			self.0 ^= self.0 >> 7;
+			self.0 ^= self.0 << 17;
+			(self.0 >> 32) as u32
+		}
+
+		/// A word with one of the RV32I opcodes and random remaining bits.
+		fn word(&mut self) -> u32 {
+			let opcode = OPCODES[self.next() as usize % OPCODES.len()];
+			match self.next() % 8 {
+				// Clearing the top bits keeps the shift and R-type funct7 values in range often.
+				0 => (self.next() & 0x41ff_ff80) | opcode,
+				// Small immediates keep loads and stores near the end of memory.
+				1 => (self.next() & 0x01ff_ff80) | opcode,
+				2 => [0x0000_0073, 0x0010_0073][self.next() as usize % 2],
+				_ => (self.next() & !0x7f) | opcode,
+			}
+		}
+
+		/// A register value which is often a valid address, so that loads and stores retire.
+		fn register(&mut self) -> u32 {
+			match self.next() % 4 {
+				0 => self.next(),
+				1 => self.next() % 8,
+				_ => self.next() % (MEMORY_SIZE as u32 + 8),
+			}
+		}
+	}
+
+	#[test]
+	fn test_round_trip_sweep() -> Result<(), Mismatch> {
+		let mut rng = Xorshift(0x5eed_0001);
+		for _ in 0..1_000_000 {
+			check_round_trip::<MEMORY_SIZE>(rng.word())?;
+			check_round_trip::<MEMORY_SIZE>(rng.next())?;
+		}
+		Ok(())
+	}
+
+	#[test]
+	fn test_differential_sweep() -> Result<(), Mismatch> {
+		let mut rng = Xorshift(0x5eed_0002);
+		for _ in 0..200_000 {
+			let word = rng.word();
+			let registers = core::array::from_fn(|_| rng.register());
+			let pc = rng.next() & !0b11;
+			let memory: Vec<u8> = (0..MEMORY_SIZE).map(|_| rng.next() as u8).collect();
+			check::<MEMORY_SIZE>(word, registers, pc, &memory)?;
+		}
+		Ok(())
+	}
+}
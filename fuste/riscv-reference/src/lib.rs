@@ -0,0 +1,303 @@
+//! An independent RV32I interpreter written directly from the encoding and semantics tables of
+//! the unprivileged specification.
+//!
+//! It shares no decoding code with `fuste-riscv-core` on purpose, so that the two can be run
+//! side by side on the same inputs by [diff] and the fuzz targets under `fuste/riscv-core/fuzz`.
+pub mod diff;
+
+use core::fmt::{self, Display};
+
+/// The reasons the reference stops without retiring an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trap {
+	IllegalInstruction,
+	Ecall,
+	Ebreak,
+	/// A load or store touched a byte outside of memory.
+	AccessFault,
+}
+
+impl Display for Trap {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{self:?}")
+	}
+}
+
+/// The architectural state the reference operates on.
+///
+/// Memory starts at address zero. Misaligned accesses are allowed, matching the machine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct State {
+	pub registers: [u32; 32],
+	pub pc: u32,
+	pub memory: Vec<u8>,
+}
+
+const OP_LUI: u32 = 0b0110111;
+const OP_AUIPC: u32 = 0b0010111;
+const OP_JAL: u32 = 0b1101111;
+const OP_JALR: u32 = 0b1100111;
+const OP_BRANCH: u32 = 0b1100011;
+const OP_LOAD: u32 = 0b0000011;
+const OP_STORE: u32 = 0b0100011;
+const OP_IMM: u32 = 0b0010011;
+const OP: u32 = 0b0110011;
+const OP_MISC_MEM: u32 = 0b0001111;
+const OP_SYSTEM: u32 = 0b1110011;
+
+const ECALL: u32 = 0x0000_0073;
+const EBREAK: u32 = 0x0010_0073;
+
+fn rd(word: u32) -> usize {
+	((word >> 7) & 0x1f) as usize
+}
+
+fn rs1(word: u32) -> usize {
+	((word >> 15) & 0x1f) as usize
+}
+
+fn rs2(word: u32) -> usize {
+	((word >> 20) & 0x1f) as usize
+}
+
+fn funct3(word: u32) -> u32 {
+	(word >> 12) & 0x7
+}
+
+fn funct7(word: u32) -> u32 {
+	word >> 25
+}
+
+fn imm_i(word: u32) -> u32 {
+	((word as i32) >> 20) as u32
+}
+
+fn imm_s(word: u32) -> u32 {
+	(((word as i32) >> 25) << 5) as u32 | ((word >> 7) & 0x1f)
+}
+
+fn imm_b(word: u32) -> u32 {
+	(((word as i32) >> 31) << 12) as u32
+		| ((word << 4) & 0x800)
+		| ((word >> 20) & 0x7e0)
+		| ((word >> 7) & 0x1e)
+}
+
+fn imm_u(word: u32) -> u32 {
+	word & 0xffff_f000
+}
+
+fn imm_j(word: u32) -> u32 {
+	(((word as i32) >> 31) << 20) as u32
+		| (word & 0xf_f000)
+		| ((word >> 9) & 0x800)
+		| ((word >> 20) & 0x7fe)
+}
+
+/// Whether the word is a legal RV32I instruction.
+pub fn is_legal(word: u32) -> bool {
+	match word & 0x7f {
+		OP_LUI | OP_AUIPC | OP_JAL => true,
+		// FENCE ignores its other reserved fields, so funct3 is all that selects it.
+		OP_JALR | OP_MISC_MEM => funct3(word) == 0,
+		OP_BRANCH => !matches!(funct3(word), 0b010 | 0b011),
+		OP_LOAD => matches!(funct3(word), 0b000 | 0b001 | 0b010 | 0b100 | 0b101),
+		OP_STORE => funct3(word) <= 0b010,
+		OP_IMM => match funct3(word) {
+			0b001 => funct7(word) == 0,
+			0b101 => matches!(funct7(word), 0b000_0000 | 0b010_0000),
+			_ => true,
+		},
+		OP => match funct7(word) {
+			0b000_0000 => true,
+			0b010_0000 => matches!(funct3(word), 0b000 | 0b101),
+			_ => false,
+		},
+		OP_SYSTEM => word == ECALL || word == EBREAK,
+		_ => false,
+	}
+}
+
+impl State {
+	pub fn new(memory_size: usize) -> Self {
+		Self { registers: [0; 32], pc: 0, memory: vec![0; memory_size] }
+	}
+
+	fn x(&self, index: usize) -> u32 {
+		self.registers[index]
+	}
+
+	fn set_x(&mut self, index: usize, value: u32) {
+		if index != 0 {
+			self.registers[index] = value;
+		}
+	}
+
+	fn range(&self, address: u32, width: u32) -> Result<core::ops::Range<usize>, Trap> {
+		let start = address as usize;
+		let end = start + width as usize;
+		if end > self.memory.len() {
+			return Err(Trap::AccessFault);
+		}
+		Ok(start..end)
+	}
+
+	fn load(&self, address: u32, width: u32) -> Result<u32, Trap> {
+		let range = self.range(address, width)?;
+		let mut bytes = [0u8; 4];
+		bytes[..width as usize].copy_from_slice(&self.memory[range]);
+		Ok(u32::from_le_bytes(bytes))
+	}
+
+	fn store(&mut self, address: u32, width: u32, value: u32) -> Result<(), Trap> {
+		let range = self.range(address, width)?;
+		self.memory[range].copy_from_slice(&value.to_le_bytes()[..width as usize]);
+		Ok(())
+	}
+
+	/// Executes a single instruction word.
+	///
+	/// The state is only updated when the instruction retires.
+	/// Jump and branch targets aren't checked for alignment, fetching from them is what fails.
+	pub fn step(&mut self, word: u32) -> Result<(), Trap> {
+		if !is_legal(word) {
+			return Err(Trap::IllegalInstruction);
+		}
+
+		let pc = self.pc;
+		let mut next_pc = pc.wrapping_add(4);
+		let (a, b) = (self.x(rs1(word)), self.x(rs2(word)));
+
+		match word & 0x7f {
+			OP_LUI => self.set_x(rd(word), imm_u(word)),
+			OP_AUIPC => self.set_x(rd(word), pc.wrapping_add(imm_u(word))),
+			OP_JAL => {
+				self.set_x(rd(word), next_pc);
+				next_pc = pc.wrapping_add(imm_j(word));
+			}
+			OP_JALR => {
+				let target = a.wrapping_add(imm_i(word)) & !1;
+				self.set_x(rd(word), next_pc);
+				next_pc = target;
+			}
+			OP_BRANCH => {
+				let taken = match funct3(word) {
+					0b000 => a == b,
+					0b001 => a != b,
+					0b100 => (a as i32) < (b as i32),
+					0b101 => (a as i32) >= (b as i32),
+					0b110 => a < b,
+					_ => a >= b,
+				};
+				if taken {
+					next_pc = pc.wrapping_add(imm_b(word));
+				}
+			}
+			OP_LOAD => {
+				let address = a.wrapping_add(imm_i(word));
+				let value = match funct3(word) {
+					0b000 => self.load(address, 1)? as i8 as i32 as u32,
+					0b001 => self.load(address, 2)? as i16 as i32 as u32,
+					0b010 => self.load(address, 4)?,
+					0b100 => self.load(address, 1)?,
+					_ => self.load(address, 2)?,
+				};
+				self.set_x(rd(word), value);
+			}
+			OP_STORE => {
+				let address = a.wrapping_add(imm_s(word));
+				self.store(address, 1 << funct3(word), b)?;
+			}
+			OP_IMM => {
+				let imm = imm_i(word);
+				let shamt = imm & 0x1f;
+				let value = match funct3(word) {
+					0b000 => a.wrapping_add(imm),
+					0b010 => ((a as i32) < (imm as i32)) as u32,
+					0b011 => (a < imm) as u32,
+					0b100 => a ^ imm,
+					0b110 => a | imm,
+					0b111 => a & imm,
+					0b001 => a << shamt,
+					_ if funct7(word) == 0 => a >> shamt,
+					_ => ((a as i32) >> shamt) as u32,
+				};
+				self.set_x(rd(word), value);
+			}
+			OP => {
+				let shamt = b & 0x1f;
+				let value = match (funct3(word), funct7(word)) {
+					(0b000, 0) => a.wrapping_add(b),
+					(0b000, _) => a.wrapping_sub(b),
+					(0b001, _) => a << shamt,
+					(0b010, _) => ((a as i32) < (b as i32)) as u32,
+					(0b011, _) => (a < b) as u32,
+					(0b100, _) => a ^ b,
+					(0b101, 0) => a >> shamt,
+					(0b101, _) => ((a as i32) >> shamt) as u32,
+					(0b110, _) => a | b,
+					_ => a & b,
+				};
+				self.set_x(rd(word), value);
+			}
+			OP_MISC_MEM => (),
+			_ if word == ECALL => return Err(Trap::Ecall),
+			_ => return Err(Trap::Ebreak),
+		}
+
+		self.pc = next_pc;
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_immediates() {
+		// addi x1, x0, -1
+		assert_eq!(imm_i(0xfff0_0093), u32::MAX);
+		// sw x2, -4(x1)
+		assert_eq!(imm_s(0xfe20_ae23) as i32, -4);
+		// beq x0, x0, -8
+		assert_eq!(imm_b(0xfe00_0ce3) as i32, -8);
+		// beq x0, x0, 2048
+		assert_eq!(imm_b(0x0000_0063 | (1 << 7)), 2048);
+		// jal x1, -2
+		assert_eq!(imm_j(0xfffff0ef) as i32, -2);
+		// jal x0, 2048
+		assert_eq!(imm_j(0x0010_006f), 2048);
+	}
+
+	#[test]
+	fn test_legality() {
+		assert!(is_legal(ECALL));
+		assert!(is_legal(EBREAK));
+		assert!(!is_legal(ECALL | (1 << 7)));
+		// srai x1, x1, 1 and its srli sibling
+		assert!(is_legal(0x4010_d093));
+		assert!(is_legal(0x0010_d093));
+		assert!(!is_legal(0x2010_d093));
+		// jalr with funct3 = 1
+		assert!(!is_legal(0x0000_10e7));
+	}
+
+	#[test]
+	fn test_step() -> Result<(), Trap> {
+		let mut state = State::new(16);
+		state.registers[1] = 0xffff_fff0;
+
+		// srai x2, x1, 4
+		state.step(0x4040_d113)?;
+		assert_eq!(state.registers[2], 0xffff_ffff);
+		// sw x1, 12(x0), the last word in memory
+		state.step(0x0010_2623)?;
+		assert_eq!(state.memory[12..], [0xf0, 0xff, 0xff, 0xff]);
+		// sh x1, 15(x0) runs over the end of memory
+		assert_eq!(state.step(0x0010_17a3), Err(Trap::AccessFault));
+		assert_eq!(state.pc, 8);
+
+		Ok(())
+	}
+}
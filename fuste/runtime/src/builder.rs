@@ -0,0 +1,516 @@
+use crate::Runtime;
+use fuste_capture_output_system::CaptureOutputSystem;
+use fuste_clock_system::{Clock, ClockSystem};
+use fuste_ecall_dispatcher::{
+	table::{EcallEntry, EcallTable},
+	CheckChannelSystemDispatcher, EcallDispatcher, ExitSystemDispatcher, NoopDispatcher,
+	OpenChannelSystemDispatcher, RandomSystemDispatcher, ReadSystemDispatcher,
+	TimeSystemDispatcher, WriteSystemDispatcher,
+};
+use fuste_exit_system::ExitSystem;
+use fuste_interrupt_handler::{InterruptHandler, NoopEbreakDispatcher};
+use fuste_riscv_core::{machine::MachineSystem, plugins::rv32i_computer::Rv32iComputer};
+use fuste_std_input_system::StdInputSystem;
+use fuste_std_output_system::StdOutputSystem;
+use fuste_std_random_system::StdRandomSystem;
+
+/// The computer a [RuntimeBuilder] assembles: an [Rv32iComputer] whose ECALLs are dispatched to
+/// the chosen systems.
+pub type EcallComputer<
+	const MEMORY_SIZE: usize,
+	Exit,
+	Write,
+	Read,
+	OpenChannel,
+	CheckChannel,
+	Random,
+	Time,
+	Table = (),
+> = InterruptHandler<
+	MEMORY_SIZE,
+	Rv32iComputer,
+	EcallDispatcher<MEMORY_SIZE, Exit, Write, Read, OpenChannel, CheckChannel, Random, Time, Table>,
+	NoopEbreakDispatcher<MEMORY_SIZE>,
+>;
+
+/// The [Runtime] a [RuntimeBuilder] builds, running an [EcallComputer].
+pub type EcallRuntime<
+	const MEMORY_SIZE: usize,
+	Exit,
+	Write,
+	Read,
+	OpenChannel,
+	CheckChannel,
+	Random,
+	Time,
+	Table = (),
+> = Runtime<
+	MEMORY_SIZE,
+	EcallComputer<MEMORY_SIZE, Exit, Write, Read, OpenChannel, CheckChannel, Random, Time, Table>,
+>;
+
+/// The table serving the handler's ECALL ahead of those in the rest of the table.
+pub type WithEcallEntry<Handler, Table> = (EcallEntry<Handler>, Table);
+
+/// Composes the systems serving a [Runtime]'s ECALLs.
+///
+/// Each slot starts out as a [NoopDispatcher] and changes type when a system is chosen, so the
+/// built computer is monomorphized over exactly the systems in use. Handlers for other ECALL
+/// numbers are kept in an [EcallTable].
+pub struct RuntimeBuilder<
+	const MEMORY_SIZE: usize,
+	Exit,
+	Write,
+	Read,
+	OpenChannel,
+	CheckChannel,
+	Random,
+	Time,
+	Table = (),
+> {
+	exit: Exit,
+	write: Write,
+	read: Read,
+	open_channel: OpenChannel,
+	check_channel: CheckChannel,
+	random: Random,
+	time: Time,
+	table: Table,
+	max_ticks: Option<u32>,
+}
+
+impl<const MEMORY_SIZE: usize>
+	RuntimeBuilder<
+		MEMORY_SIZE,
+		NoopDispatcher<MEMORY_SIZE>,
+		NoopDispatcher<MEMORY_SIZE>,
+		NoopDispatcher<MEMORY_SIZE>,
+		NoopDispatcher<MEMORY_SIZE>,
+		NoopDispatcher<MEMORY_SIZE>,
+		NoopDispatcher<MEMORY_SIZE>,
+		NoopDispatcher<MEMORY_SIZE>,
+	>
+{
+	pub fn new() -> Self {
+		Self {
+			exit: NoopDispatcher {},
+			write: NoopDispatcher {},
+			read: NoopDispatcher {},
+			open_channel: NoopDispatcher {},
+			check_channel: NoopDispatcher {},
+			random: NoopDispatcher {},
+			time: NoopDispatcher {},
+			table: (),
+			max_ticks: None,
+		}
+	}
+}
+
+impl<const MEMORY_SIZE: usize> Default
+	for RuntimeBuilder<
+		MEMORY_SIZE,
+		NoopDispatcher<MEMORY_SIZE>,
+		NoopDispatcher<MEMORY_SIZE>,
+		NoopDispatcher<MEMORY_SIZE>,
+		NoopDispatcher<MEMORY_SIZE>,
+		NoopDispatcher<MEMORY_SIZE>,
+		NoopDispatcher<MEMORY_SIZE>,
+		NoopDispatcher<MEMORY_SIZE>,
+	>
+{
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<
+		const MEMORY_SIZE: usize,
+		Exit: ExitSystemDispatcher<MEMORY_SIZE>,
+		Write: WriteSystemDispatcher<MEMORY_SIZE>,
+		Read: ReadSystemDispatcher<MEMORY_SIZE>,
+		OpenChannel: OpenChannelSystemDispatcher<MEMORY_SIZE>,
+		CheckChannel: CheckChannelSystemDispatcher<MEMORY_SIZE>,
+		Random: RandomSystemDispatcher<MEMORY_SIZE>,
+		Time: TimeSystemDispatcher<MEMORY_SIZE>,
+		Table: EcallTable<MEMORY_SIZE>,
+	> RuntimeBuilder<MEMORY_SIZE, Exit, Write, Read, OpenChannel, CheckChannel, Random, Time, Table>
+{
+	/// Serves the exit ECALL with an [ExitSystem].
+	pub fn with_exit(
+		self,
+	) -> RuntimeBuilder<
+		MEMORY_SIZE,
+		ExitSystem<MEMORY_SIZE>,
+		Write,
+		Read,
+		OpenChannel,
+		CheckChannel,
+		Random,
+		Time,
+		Table,
+	> {
+		self.with_exit_system(ExitSystem::new())
+	}
+
+	pub fn with_exit_system<NewExit: ExitSystemDispatcher<MEMORY_SIZE>>(
+		self,
+		exit: NewExit,
+	) -> RuntimeBuilder<
+		MEMORY_SIZE,
+		NewExit,
+		Write,
+		Read,
+		OpenChannel,
+		CheckChannel,
+		Random,
+		Time,
+		Table,
+	> {
+		RuntimeBuilder {
+			exit,
+			write: self.write,
+			read: self.read,
+			open_channel: self.open_channel,
+			check_channel: self.check_channel,
+			random: self.random,
+			time: self.time,
+			table: self.table,
+			max_ticks: self.max_ticks,
+		}
+	}
+
+	/// Serves the write ECALL with a [StdOutputSystem], writing to the host's stdout and stderr.
+	pub fn with_stdout(
+		self,
+	) -> RuntimeBuilder<
+		MEMORY_SIZE,
+		Exit,
+		StdOutputSystem<MEMORY_SIZE>,
+		Read,
+		OpenChannel,
+		CheckChannel,
+		Random,
+		Time,
+		Table,
+	> {
+		self.with_write_system(StdOutputSystem::new())
+	}
+
+	/// Serves the write ECALL with a [CaptureOutputSystem], capturing stdout and stderr.
+	pub fn with_captured_output(
+		self,
+	) -> RuntimeBuilder<
+		MEMORY_SIZE,
+		Exit,
+		CaptureOutputSystem<MEMORY_SIZE>,
+		Read,
+		OpenChannel,
+		CheckChannel,
+		Random,
+		Time,
+		Table,
+	> {
+		self.with_write_system(CaptureOutputSystem::std())
+	}
+
+	pub fn with_write_system<NewWrite: WriteSystemDispatcher<MEMORY_SIZE>>(
+		self,
+		write: NewWrite,
+	) -> RuntimeBuilder<
+		MEMORY_SIZE,
+		Exit,
+		NewWrite,
+		Read,
+		OpenChannel,
+		CheckChannel,
+		Random,
+		Time,
+		Table,
+	> {
+		RuntimeBuilder {
+			exit: self.exit,
+			write,
+			read: self.read,
+			open_channel: self.open_channel,
+			check_channel: self.check_channel,
+			random: self.random,
+			time: self.time,
+			table: self.table,
+			max_ticks: self.max_ticks,
+		}
+	}
+
+	/// Serves the read ECALL with a [StdInputSystem], reading from the host's stdin.
+	pub fn with_stdin(
+		self,
+	) -> RuntimeBuilder<
+		MEMORY_SIZE,
+		Exit,
+		Write,
+		StdInputSystem<MEMORY_SIZE>,
+		OpenChannel,
+		CheckChannel,
+		Random,
+		Time,
+		Table,
+	> {
+		self.with_read_system(StdInputSystem::stdin())
+	}
+
+	pub fn with_read_system<NewRead: ReadSystemDispatcher<MEMORY_SIZE>>(
+		self,
+		read: NewRead,
+	) -> RuntimeBuilder<
+		MEMORY_SIZE,
+		Exit,
+		Write,
+		NewRead,
+		OpenChannel,
+		CheckChannel,
+		Random,
+		Time,
+		Table,
+	> {
+		RuntimeBuilder {
+			exit: self.exit,
+			write: self.write,
+			read,
+			open_channel: self.open_channel,
+			check_channel: self.check_channel,
+			random: self.random,
+			time: self.time,
+			table: self.table,
+			max_ticks: self.max_ticks,
+		}
+	}
+
+	/// Serves both channel ECALLs with the system.
+	///
+	/// The system is cloned into both slots, so it should share its state between clones, e.g.
+	/// by wrapping it in a [crate::SharedSystem].
+	pub fn with_channel_system<
+		System: OpenChannelSystemDispatcher<MEMORY_SIZE>
+			+ CheckChannelSystemDispatcher<MEMORY_SIZE>
+			+ Clone,
+	>(
+		self,
+		system: System,
+	) -> RuntimeBuilder<MEMORY_SIZE, Exit, Write, Read, System, System, Random, Time, Table> {
+		RuntimeBuilder {
+			exit: self.exit,
+			write: self.write,
+			read: self.read,
+			open_channel: system.clone(),
+			check_channel: system,
+			random: self.random,
+			time: self.time,
+			table: self.table,
+			max_ticks: self.max_ticks,
+		}
+	}
+
+	/// Serves the random ECALL with a ChaCha20 stream from the seed, so that every run with the
+	/// seed draws the same bytes.
+	pub fn with_seeded_random(
+		self,
+		seed: [u8; 32],
+	) -> RuntimeBuilder<
+		MEMORY_SIZE,
+		Exit,
+		Write,
+		Read,
+		OpenChannel,
+		CheckChannel,
+		StdRandomSystem<MEMORY_SIZE>,
+		Time,
+		Table,
+	> {
+		self.with_random_system(StdRandomSystem::seeded(seed))
+	}
+
+	/// Serves the random ECALL with the host's randomness.
+	pub fn with_os_random(
+		self,
+	) -> RuntimeBuilder<
+		MEMORY_SIZE,
+		Exit,
+		Write,
+		Read,
+		OpenChannel,
+		CheckChannel,
+		StdRandomSystem<MEMORY_SIZE>,
+		Time,
+		Table,
+	> {
+		self.with_random_system(StdRandomSystem::os())
+	}
+
+	pub fn with_random_system<NewRandom: RandomSystemDispatcher<MEMORY_SIZE>>(
+		self,
+		random: NewRandom,
+	) -> RuntimeBuilder<
+		MEMORY_SIZE,
+		Exit,
+		Write,
+		Read,
+		OpenChannel,
+		CheckChannel,
+		NewRandom,
+		Time,
+		Table,
+	> {
+		RuntimeBuilder {
+			exit: self.exit,
+			write: self.write,
+			read: self.read,
+			open_channel: self.open_channel,
+			check_channel: self.check_channel,
+			random,
+			time: self.time,
+			table: self.table,
+			max_ticks: self.max_ticks,
+		}
+	}
+
+	/// Serves the time ECALL with a [ClockSystem] telling the time with the clock, e.g. a fixed
+	/// [Clock::Fixed] transaction timestamp so that every run reads the same time.
+	pub fn with_clock(
+		self,
+		clock: Clock,
+	) -> RuntimeBuilder<
+		MEMORY_SIZE,
+		Exit,
+		Write,
+		Read,
+		OpenChannel,
+		CheckChannel,
+		Random,
+		ClockSystem<MEMORY_SIZE>,
+		Table,
+	> {
+		self.with_time_system(ClockSystem::new(clock))
+	}
+
+	pub fn with_time_system<NewTime: TimeSystemDispatcher<MEMORY_SIZE>>(
+		self,
+		time: NewTime,
+	) -> RuntimeBuilder<
+		MEMORY_SIZE,
+		Exit,
+		Write,
+		Read,
+		OpenChannel,
+		CheckChannel,
+		Random,
+		NewTime,
+		Table,
+	> {
+		RuntimeBuilder {
+			exit: self.exit,
+			write: self.write,
+			read: self.read,
+			open_channel: self.open_channel,
+			check_channel: self.check_channel,
+			random: self.random,
+			time,
+			table: self.table,
+			max_ticks: self.max_ticks,
+		}
+	}
+
+	/// Serves the ECALL numbered `ecall` with the handler, ahead of the systems in the fixed slots
+	/// and any handler registered for it before.
+	pub fn with_ecall<Handler: MachineSystem<MEMORY_SIZE>>(
+		self,
+		ecall: u32,
+		handler: Handler,
+	) -> RuntimeBuilder<
+		MEMORY_SIZE,
+		Exit,
+		Write,
+		Read,
+		OpenChannel,
+		CheckChannel,
+		Random,
+		Time,
+		WithEcallEntry<Handler, Table>,
+	> {
+		RuntimeBuilder {
+			exit: self.exit,
+			write: self.write,
+			read: self.read,
+			open_channel: self.open_channel,
+			check_channel: self.check_channel,
+			random: self.random,
+			time: self.time,
+			table: (EcallEntry::new(ecall, handler), self.table),
+			max_ticks: self.max_ticks,
+		}
+	}
+
+	/// Replaces the table of ECALL handlers, e.g. with a
+	/// [fuste_ecall_dispatcher::table::DynamicEcallTable].
+	pub fn with_ecall_table<NewTable: EcallTable<MEMORY_SIZE>>(
+		self,
+		table: NewTable,
+	) -> RuntimeBuilder<
+		MEMORY_SIZE,
+		Exit,
+		Write,
+		Read,
+		OpenChannel,
+		CheckChannel,
+		Random,
+		Time,
+		NewTable,
+	> {
+		RuntimeBuilder {
+			exit: self.exit,
+			write: self.write,
+			read: self.read,
+			open_channel: self.open_channel,
+			check_channel: self.check_channel,
+			random: self.random,
+			time: self.time,
+			table,
+			max_ticks: self.max_ticks,
+		}
+	}
+
+	/// Stops the run after the given number of ticks.
+	pub fn limit_ticks(mut self, max_ticks: u32) -> Self {
+		self.max_ticks = Some(max_ticks);
+		self
+	}
+
+	/// Builds the computer alone, for hosts which manage their own [fuste_riscv_core::machine::Machine].
+	pub fn build_computer(
+		self,
+	) -> EcallComputer<MEMORY_SIZE, Exit, Write, Read, OpenChannel, CheckChannel, Random, Time, Table>
+	{
+		InterruptHandler {
+			inner: Rv32iComputer,
+			ecall_dispatcher: EcallDispatcher {
+				exit_dispatcher: self.exit,
+				write_dispatcher: self.write,
+				read_dispatcher: self.read,
+				open_channel_dispatcher: self.open_channel,
+				check_channel_dispatcher: self.check_channel,
+				random_dispatcher: self.random,
+				time_dispatcher: self.time,
+				table: self.table,
+			},
+			ebreak_dispatcher: NoopEbreakDispatcher {},
+		}
+	}
+
+	pub fn build(
+		self,
+	) -> EcallRuntime<MEMORY_SIZE, Exit, Write, Read, OpenChannel, CheckChannel, Random, Time, Table>
+	{
+		let max_ticks = self.max_ticks;
+		let mut runtime = Runtime::new(self.build_computer());
+		runtime.max_ticks = max_ticks;
+		runtime
+	}
+}
@@ -0,0 +1,67 @@
+use crate::{RunOutcome, Runtime};
+use fuste_riscv_elf::{Elf32Loader, ElfLoaderError};
+use fuste_std_input_system::StdInputSystem;
+use std::path::Path;
+
+/// How a program's run ended and what it wrote to stdout and stderr, see [run_elf_captured].
+#[derive(Debug)]
+pub struct CapturedRun {
+	pub outcome: RunOutcome,
+	pub stdout: Vec<u8>,
+	pub stderr: Vec<u8>,
+}
+
+impl CapturedRun {
+	pub fn stdout_lossy(&self) -> String {
+		String::from_utf8_lossy(&self.stdout).into_owned()
+	}
+
+	pub fn stderr_lossy(&self) -> String {
+		String::from_utf8_lossy(&self.stderr).into_owned()
+	}
+}
+
+/// Runs the ELF file with its stdout and stderr captured and the input as its stdin, stopping
+/// after `max_ticks`, so tests can check what the program printed.
+pub fn run_elf_captured<const MEMORY_SIZE: usize>(
+	path: impl AsRef<Path>,
+	input: impl Into<Vec<u8>>,
+	max_ticks: u32,
+) -> Result<CapturedRun, ElfLoaderError> {
+	let mut runtime = Runtime::<MEMORY_SIZE>::builder()
+		.with_exit()
+		.with_captured_output()
+		.with_read_system(StdInputSystem::from_bytes(input))
+		.limit_ticks(max_ticks)
+		.build();
+	runtime.load_elf(&Elf32Loader::default(), path)?;
+	let outcome = runtime.run();
+
+	let output = &runtime.computer.ecall_dispatcher.write_dispatcher;
+	Ok(CapturedRun { outcome, stdout: output.stdout(), stderr: output.stderr() })
+}
+
+#[cfg(test)]
+pub mod tests {
+	use super::*;
+	use crate::{Termination, DEFAULT_MEMORY_SIZE};
+	use fuste_exit::ExitStatus;
+
+	#[test]
+	fn test_run_elf_captured() -> Result<(), ElfLoaderError> {
+		let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+			.join("../riscv-box/tests/abi/abi-conformance.elf");
+		let run = run_elf_captured::<DEFAULT_MEMORY_SIZE>(path, "vector", 1_000_000)?;
+
+		assert_eq!(run.outcome.termination, Termination::Exited(ExitStatus::Terminated));
+		// Nothing serves the random ECALL, so drawing random bytes fails.
+		assert_eq!(
+			run.stdout_lossy(),
+			"write: ok\ninvalid write: ok\nchannel: ignored\nchannel wait: ignored\nread: vector\nreturn data: ok\nrandom: failed\ntime: failed\n"
+		);
+		assert_eq!(run.stderr_lossy(), "stderr: ok\n");
+		assert_eq!(run.outcome.return_data.as_deref(), Some(&b"abi: ok"[..]));
+
+		Ok(())
+	}
+}
@@ -0,0 +1,903 @@
+//! Embeds the fuste machine in a host program, without going through fubox's command line.
+//!
+//! A [Runtime] owns a [Machine] and the computer which ticks it. The computer is assembled with a
+//! [RuntimeBuilder] from the systems the host wants to serve, e.g.
+//!
+//! ```no_run
+//! use fuste_riscv_elf::Elf32Loader;
+//! use fuste_runtime::{Runtime, DEFAULT_MEMORY_SIZE};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut runtime = Runtime::<DEFAULT_MEMORY_SIZE>::builder()
+//!     .with_exit()
+//!     .with_stdout()
+//!     .limit_ticks(1_000_000)
+//!     .build();
+//! runtime.load_elf(&Elf32Loader::default(), "program.elf")?;
+//! let outcome = runtime.run();
+//! println!("{:?}", outcome.termination);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The computer is a single concrete type, so the machine's inner loop is monomorphized over
+//! exactly the systems chosen.
+pub mod builder;
+pub mod capture;
+pub mod outcome;
+pub mod shared;
+
+pub use builder::{EcallComputer, EcallRuntime, RuntimeBuilder};
+pub use capture::{run_elf_captured, CapturedRun};
+pub use outcome::{RunOutcome, SystemRun, Termination};
+pub use shared::SharedSystem;
+
+use fuste_ecall_dispatcher::{
+	table::EcallTable, CheckChannelSystemDispatcher, EcallDispatcher, ExitSystemDispatcher,
+	NoopDispatcher, OpenChannelSystemDispatcher, RandomSystemDispatcher, ReadSystemDispatcher,
+	TimeSystemDispatcher, WriteSystemDispatcher,
+};
+use fuste_exit::ExitStatus;
+use fuste_exit_system::ExitSystem;
+use fuste_interrupt_handler::{EbreakDispatcherOps, InterruptHandler};
+use fuste_riscv_core::{
+	machine::{Machine, MachineSystem},
+	plugins::rv32i_computer::Rv32iComputer,
+};
+use fuste_riscv_elf::{program::LoadedProgram, Elf32Loader, ElfLoaderError};
+use fuste_tick_handler::{TickCounter, TickHandler};
+use std::path::Path;
+
+/// The memory a runtime has when none is chosen, 2 MiB.
+pub const DEFAULT_MEMORY_SIZE: usize = 1024 * 1024 * 2;
+
+/// A system in the exit ECALL's slot which records the status the program exited with.
+pub trait ExitStatusSource {
+	fn exit_status(&self) -> ExitStatus;
+
+	/// The message the program aborted with, if it did.
+	fn abort_message(&self) -> Option<String> {
+		None
+	}
+
+	/// The data the program handed back as its result, if any.
+	fn return_data(&self) -> Option<Vec<u8>> {
+		None
+	}
+}
+
+impl<const MEMORY_SIZE: usize> ExitStatusSource for ExitSystem<MEMORY_SIZE> {
+	fn exit_status(&self) -> ExitStatus {
+		self.syscall_status.clone()
+	}
+
+	fn abort_message(&self) -> Option<String> {
+		self.abort_message
+			.as_deref()
+			.map(|message| String::from_utf8_lossy(message).into_owned())
+	}
+
+	fn return_data(&self) -> Option<Vec<u8>> {
+		self.return_data.clone()
+	}
+}
+
+impl<const MEMORY_SIZE: usize> ExitStatusSource for NoopDispatcher<MEMORY_SIZE> {
+	fn exit_status(&self) -> ExitStatus {
+		ExitStatus::Unsupported
+	}
+}
+
+/// A computer a [Runtime] can run, which can tell the status the program exited with.
+pub trait RuntimeComputer<const MEMORY_SIZE: usize>: MachineSystem<MEMORY_SIZE> {
+	fn exit_status(&self) -> ExitStatus;
+
+	/// The message the program aborted with, if it did.
+	fn abort_message(&self) -> Option<String> {
+		None
+	}
+
+	/// The data the program handed back as its result, if any.
+	fn return_data(&self) -> Option<Vec<u8>> {
+		None
+	}
+}
+
+impl<const MEMORY_SIZE: usize, Computer: RuntimeComputer<MEMORY_SIZE> + ?Sized>
+	RuntimeComputer<MEMORY_SIZE> for &mut Computer
+{
+	fn exit_status(&self) -> ExitStatus {
+		(**self).exit_status()
+	}
+
+	fn abort_message(&self) -> Option<String> {
+		(**self).abort_message()
+	}
+
+	fn return_data(&self) -> Option<Vec<u8>> {
+		(**self).return_data()
+	}
+}
+
+/// Without an ECALL dispatcher programs have no way to exit.
+impl<const MEMORY_SIZE: usize> RuntimeComputer<MEMORY_SIZE> for Rv32iComputer {
+	fn exit_status(&self) -> ExitStatus {
+		ExitStatus::Unsupported
+	}
+}
+
+impl<
+		const MEMORY_SIZE: usize,
+		Inner: MachineSystem<MEMORY_SIZE>,
+		Exit: ExitSystemDispatcher<MEMORY_SIZE> + ExitStatusSource,
+		Write: WriteSystemDispatcher<MEMORY_SIZE>,
+		Read: ReadSystemDispatcher<MEMORY_SIZE>,
+		OpenChannel: OpenChannelSystemDispatcher<MEMORY_SIZE>,
+		CheckChannel: CheckChannelSystemDispatcher<MEMORY_SIZE>,
+		Random: RandomSystemDispatcher<MEMORY_SIZE>,
+		Time: TimeSystemDispatcher<MEMORY_SIZE>,
+		Table: EcallTable<MEMORY_SIZE>,
+		EbreakDispatcher: EbreakDispatcherOps<MEMORY_SIZE>,
+	> RuntimeComputer<MEMORY_SIZE>
+	for InterruptHandler<
+		MEMORY_SIZE,
+		Inner,
+		EcallDispatcher<
+			MEMORY_SIZE,
+			Exit,
+			Write,
+			Read,
+			OpenChannel,
+			CheckChannel,
+			Random,
+			Time,
+			Table,
+		>,
+		EbreakDispatcher,
+	>
+{
+	fn exit_status(&self) -> ExitStatus {
+		self.ecall_dispatcher.exit_dispatcher.exit_status()
+	}
+
+	fn abort_message(&self) -> Option<String> {
+		self.ecall_dispatcher.exit_dispatcher.abort_message()
+	}
+
+	fn return_data(&self) -> Option<Vec<u8>> {
+		self.ecall_dispatcher.exit_dispatcher.return_data()
+	}
+}
+
+/// Runs the system until it breaks or faults.
+///
+/// Faults are part of the run rather than an error, so that they can be reported.
+pub fn run_system<const MEMORY_SIZE: usize, System: MachineSystem<MEMORY_SIZE>>(
+	machine: &mut Machine<MEMORY_SIZE>,
+	mut system: System,
+) -> SystemRun {
+	let result = machine.run(&mut system);
+	SystemRun { result, ticks: None, exhausted: false }
+}
+
+/// Runs the system until it breaks, faults or has ticked `max_ticks` times, counting the ticks.
+pub fn run_system_with_ticks<const MEMORY_SIZE: usize, System: MachineSystem<MEMORY_SIZE>>(
+	machine: &mut Machine<MEMORY_SIZE>,
+	system: System,
+	max_ticks: u32,
+) -> SystemRun {
+	let mut tick_handler = TickHandler::new(system, max_ticks);
+	let result = machine.run(&mut tick_handler);
+	SystemRun {
+		result,
+		ticks: Some(u64::from(tick_handler.current_tick)),
+		exhausted: tick_handler.exhausted,
+	}
+}
+
+/// Runs the system until it breaks or faults, counting the ticks without limiting them.
+pub fn run_system_counting_ticks<const MEMORY_SIZE: usize, System: MachineSystem<MEMORY_SIZE>>(
+	machine: &mut Machine<MEMORY_SIZE>,
+	system: System,
+) -> SystemRun {
+	let mut tick_counter = TickCounter::new(system);
+	let result = machine.run(&mut tick_counter);
+	SystemRun { result, ticks: Some(tick_counter.current_tick), exhausted: false }
+}
+
+/// A machine and the computer which runs programs on it.
+pub struct Runtime<const MEMORY_SIZE: usize, Computer = Rv32iComputer> {
+	/// Boxed, since the machine holds all of its memory inline.
+	pub machine: Box<Machine<MEMORY_SIZE>>,
+	pub computer: Computer,
+	/// The number of ticks after which runs stop, if any.
+	pub max_ticks: Option<u32>,
+}
+
+impl<const MEMORY_SIZE: usize> Runtime<MEMORY_SIZE> {
+	/// Starts building a runtime whose ECALLs are served by the chosen systems.
+	pub fn builder() -> RuntimeBuilder<
+		MEMORY_SIZE,
+		NoopDispatcher<MEMORY_SIZE>,
+		NoopDispatcher<MEMORY_SIZE>,
+		NoopDispatcher<MEMORY_SIZE>,
+		NoopDispatcher<MEMORY_SIZE>,
+		NoopDispatcher<MEMORY_SIZE>,
+		NoopDispatcher<MEMORY_SIZE>,
+		NoopDispatcher<MEMORY_SIZE>,
+	> {
+		RuntimeBuilder::new()
+	}
+}
+
+impl<const MEMORY_SIZE: usize, Computer> Runtime<MEMORY_SIZE, Computer> {
+	/// Creates a runtime with a fresh machine, running the computer.
+	pub fn new(computer: Computer) -> Self {
+		Self { machine: Machine::new_boxed(), computer, max_ticks: None }
+	}
+
+	/// Hands the machine, with whatever was loaded into it, to another computer.
+	pub fn with_computer<Other>(self, computer: Other) -> Runtime<MEMORY_SIZE, Other> {
+		Runtime { machine: self.machine, computer, max_ticks: self.max_ticks }
+	}
+
+	/// Stops runs after the given number of ticks.
+	pub fn limit_ticks(mut self, max_ticks: u32) -> Self {
+		self.max_ticks = Some(max_ticks);
+		self
+	}
+
+	pub fn machine(&self) -> &Machine<MEMORY_SIZE> {
+		&self.machine
+	}
+
+	pub fn machine_mut(&mut self) -> &mut Machine<MEMORY_SIZE> {
+		&mut self.machine
+	}
+
+	/// Loads an ELF file into the machine.
+	pub fn load_elf(
+		&mut self,
+		loader: &Elf32Loader,
+		path: impl AsRef<Path>,
+	) -> Result<LoadedProgram, ElfLoaderError> {
+		loader.load_elf(&mut self.machine, path)
+	}
+
+	/// Loads an ELF file held in memory into the machine.
+	pub fn load_elf_bytes(
+		&mut self,
+		loader: &Elf32Loader,
+		bytes: &[u8],
+	) -> Result<LoadedProgram, ElfLoaderError> {
+		loader.load_elf_bytes(&mut self.machine, bytes)
+	}
+}
+
+impl<const MEMORY_SIZE: usize, Computer: RuntimeComputer<MEMORY_SIZE>>
+	Runtime<MEMORY_SIZE, Computer>
+{
+	/// Runs the loaded program until it exits, faults or runs out of ticks.
+	pub fn run(&mut self) -> RunOutcome {
+		let run = match self.max_ticks {
+			Some(max_ticks) => {
+				run_system_with_ticks(&mut self.machine, &mut self.computer, max_ticks)
+			}
+			None => run_system(&mut self.machine, &mut self.computer),
+		};
+		self.outcome(run)
+	}
+
+	/// The outcome of a run of this runtime's machine, for hosts which wrap the computer in
+	/// further systems and run it themselves.
+	pub fn outcome(&self, run: SystemRun) -> RunOutcome {
+		RunOutcome::new(
+			run,
+			self.computer.exit_status(),
+			self.machine.registers().program_counter(),
+		)
+		.with_abort_message(self.computer.abort_message())
+		.with_return_data(self.computer.return_data())
+	}
+}
+
+#[cfg(test)]
+pub mod tests {
+	use super::*;
+	use core::ops::ControlFlow;
+	use core::time::Duration;
+	use fuste_capture_output_system::{CaptureBuffer, CaptureOutputSystem};
+	use fuste_channel::{
+		ChannelError, ChannelStatus, ChannelStatusCode, ChannelSystemId, ChannelSystemStatus,
+	};
+	use fuste_clock_system::Clock;
+	use fuste_ecall_dispatcher::{
+		table::DynamicEcallTable, CheckChannelSystemDispatcher, OpenChannelSystemDispatcher,
+		SystemCapabilities,
+	};
+	use fuste_riscv_core::machine::MachineError;
+	use fuste_std_input_system::StdInputSystem;
+	use fuste_std_output_system::{StdOutputSystem, WriteTarget};
+	use fuste_std_transaction_system::{readiness::ChannelReadiness, ChannelSystem};
+	use fuste_write::WriteStatusCode;
+	use std::thread;
+
+	const MEMORY_SIZE: usize = 4096;
+
+	/// `li a7, 93`
+	const LOAD_EXIT_ECALL: u32 = 0x05D0_0893;
+	/// `li a7, 94`
+	const LOAD_ABORT_ECALL: u32 = 0x05E0_0893;
+	/// `li a0, 0x100`
+	const LOAD_ABORT_MESSAGE: u32 = 0x1000_0513;
+	/// `li a1, 3`
+	const LOAD_ABORT_MESSAGE_LENGTH: u32 = 0x0030_0593;
+	/// `li a7, 95`
+	const LOAD_RETURN_DATA_ECALL: u32 = 0x05F0_0893;
+	/// `li a0, 0x100`
+	const LOAD_RETURN_DATA: u32 = 0x1000_0513;
+	/// `li a1, 3`
+	const LOAD_RETURN_DATA_LENGTH: u32 = 0x0030_0593;
+	/// `li a7, 278`
+	const LOAD_RANDOM_ECALL: u32 = 0x1160_0893;
+	/// `li a0, 0x100`
+	const LOAD_RANDOM_BUFFER: u32 = 0x1000_0513;
+	/// `li a1, 16`
+	const LOAD_RANDOM_LENGTH: u32 = 0x0100_0593;
+	/// `li a7, 113`
+	const LOAD_TIME_ECALL: u32 = 0x0710_0893;
+	/// `li a7, 35`
+	const LOAD_WAIT_CHANNEL_ECALL: u32 = 0x0230_0893;
+	/// `li a0, 1`
+	const LOAD_CHANNEL_SYSTEM: u32 = 0x0010_0513;
+	/// `li a1, -1`
+	const LOAD_NO_TIMEOUT_LOW: u32 = 0xFFF0_0593;
+	/// `li a2, -1`
+	const LOAD_NO_TIMEOUT_HIGH: u32 = 0xFFF0_0613;
+	/// `li a1, 1000`
+	const LOAD_TIMEOUT_LOW: u32 = 0x3E80_0593;
+	/// `li a2, 0`
+	const LOAD_TIMEOUT_HIGH: u32 = 0x0000_0613;
+	/// `li a7, 63`
+	const LOAD_READ_ECALL: u32 = 0x03F0_0893;
+	/// `li a0, 0`
+	const LOAD_STDIN: u32 = 0x0000_0513;
+	/// `li a1, 0x100`
+	const LOAD_READ_BUFFER: u32 = 0x1000_0593;
+	/// `li a2, 4`
+	const LOAD_READ_LENGTH: u32 = 0x0040_0613;
+	/// `li a7, 64`
+	const LOAD_WRITE_ECALL: u32 = 0x0400_0893;
+	/// `li a0, 3`
+	const LOAD_WRITE_SYSTEM: u32 = 0x0030_0513;
+	/// `li a0, 4`
+	const LOAD_UNKNOWN_WRITE_SYSTEM: u32 = 0x0040_0513;
+	/// `li a2, 3`
+	const LOAD_WRITE_LENGTH: u32 = 0x0030_0613;
+	/// `li a7, 0x100`
+	const LOAD_HOST_ECALL: u32 = 0x1000_0893;
+	/// `li a0, 1`
+	const LOAD_ERROR_STATUS: u32 = 0x0010_0513;
+	/// `li a0, 42`
+	const LOAD_EXIT_CODE: u32 = 0x02A0_0513;
+	const ECALL: u32 = 0x0000_0073;
+	/// `j 0`
+	const LOOP: u32 = 0x0000_006F;
+
+	/// Serves an ECALL by answering 42 in a0.
+	struct Answer;
+
+	impl MachineSystem<MEMORY_SIZE> for Answer {
+		fn tick(
+			&mut self,
+			machine: &mut Machine<MEMORY_SIZE>,
+		) -> Result<ControlFlow<()>, MachineError> {
+			machine.csrs_mut().registers_mut().set(10, 42);
+			machine.csrs_mut().registers_mut().program_counter_mut().increment();
+			machine.commit_csrs();
+			Ok(ControlFlow::Continue(()))
+		}
+	}
+
+	/// Holds channel 1 until it's notified of progress through the readiness.
+	struct PendingChannel(ChannelReadiness);
+
+	impl PendingChannel {
+		fn holding() -> ChannelStatus {
+			ChannelStatus::new(0, ChannelStatusCode::Holding, ChannelSystemStatus::new(0))
+		}
+	}
+
+	impl ChannelSystem for PendingChannel {
+		fn handle_wait(
+			&mut self,
+			channel_system_id: ChannelSystemId,
+			timeout: Option<Duration>,
+		) -> Result<ChannelStatus, ChannelError> {
+			if !self.0.park(channel_system_id.to_u32(), timeout) {
+				return Ok(Self::holding());
+			}
+			Ok(ChannelStatus::new(0, ChannelStatusCode::Success, ChannelSystemStatus::new(0)))
+		}
+
+		fn handle_open(
+			&mut self,
+			_channel_system_id: ChannelSystemId,
+			_read_buffer: &[u8],
+			_write_buffer: &mut [u8],
+		) -> Result<ChannelStatus, ChannelError> {
+			Ok(Self::holding())
+		}
+
+		fn handle_check(
+			&mut self,
+			_channel_system_id: ChannelSystemId,
+			_read_buffer: &[u8],
+			_write_buffer: &mut [u8],
+		) -> Result<ChannelStatus, ChannelError> {
+			Ok(Self::holding())
+		}
+	}
+
+	impl MachineSystem<MEMORY_SIZE> for PendingChannel {
+		fn tick(
+			&mut self,
+			machine: &mut Machine<MEMORY_SIZE>,
+		) -> Result<ControlFlow<()>, MachineError> {
+			self.handle_channel_tick(machine)
+		}
+	}
+
+	impl SystemCapabilities for PendingChannel {
+		fn supports_system_id(&self, system_id: u32) -> bool {
+			system_id == 1
+		}
+	}
+
+	impl OpenChannelSystemDispatcher<MEMORY_SIZE> for PendingChannel {}
+
+	impl CheckChannelSystemDispatcher<MEMORY_SIZE> for PendingChannel {}
+
+	/// Completes channel 1's operations when they're opened, leaving nothing to wait for.
+	struct ImmediateChannel;
+
+	impl ChannelSystem for ImmediateChannel {
+		fn handle_open(
+			&mut self,
+			_channel_system_id: ChannelSystemId,
+			_read_buffer: &[u8],
+			_write_buffer: &mut [u8],
+		) -> Result<ChannelStatus, ChannelError> {
+			Ok(ChannelStatus::new(0, ChannelStatusCode::Success, ChannelSystemStatus::new(0)))
+		}
+
+		fn handle_check(
+			&mut self,
+			channel_system_id: ChannelSystemId,
+			read_buffer: &[u8],
+			write_buffer: &mut [u8],
+		) -> Result<ChannelStatus, ChannelError> {
+			self.handle_open(channel_system_id, read_buffer, write_buffer)
+		}
+	}
+
+	impl MachineSystem<MEMORY_SIZE> for ImmediateChannel {
+		fn tick(
+			&mut self,
+			machine: &mut Machine<MEMORY_SIZE>,
+		) -> Result<ControlFlow<()>, MachineError> {
+			self.handle_channel_tick(machine)
+		}
+	}
+
+	impl SystemCapabilities for ImmediateChannel {
+		fn supports_system_id(&self, system_id: u32) -> bool {
+			system_id == 1
+		}
+	}
+
+	impl OpenChannelSystemDispatcher<MEMORY_SIZE> for ImmediateChannel {}
+
+	impl CheckChannelSystemDispatcher<MEMORY_SIZE> for ImmediateChannel {}
+
+	fn load<Computer>(
+		runtime: &mut Runtime<MEMORY_SIZE, Computer>,
+		words: &[u32],
+	) -> Result<(), MachineError> {
+		for (index, word) in words.iter().enumerate() {
+			runtime
+				.machine_mut()
+				.memory_mut()
+				.write_word(index as u32 * 4, *word)
+				.map_err(MachineError::MemoryError)?;
+		}
+		Ok(())
+	}
+
+	#[test]
+	fn test_exit_status() -> Result<(), MachineError> {
+		let mut runtime = Runtime::<MEMORY_SIZE>::builder().with_exit().build();
+		load(&mut runtime, &[LOAD_EXIT_ECALL, LOAD_ERROR_STATUS, ECALL])?;
+
+		let outcome = runtime.run();
+		assert_eq!(outcome.termination, Termination::Exited(ExitStatus::Error));
+		assert_eq!(outcome.ticks, None);
+		assert_eq!(outcome.program_counter, 8);
+		assert!(!outcome.is_success());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_abort_message() -> Result<(), MachineError> {
+		let mut runtime = Runtime::<MEMORY_SIZE>::builder().with_exit().build();
+		load(
+			&mut runtime,
+			&[LOAD_ABORT_ECALL, LOAD_ABORT_MESSAGE, LOAD_ABORT_MESSAGE_LENGTH, ECALL],
+		)?;
+		runtime
+			.machine_mut()
+			.memory_mut()
+			.write_bytes(0x100, b"abc")
+			.map_err(MachineError::MemoryError)?;
+
+		let outcome = runtime.run();
+		assert_eq!(outcome.termination, Termination::Exited(ExitStatus::Error));
+		assert_eq!(outcome.abort_message.as_deref(), Some("abc"));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_return_data_and_exit_code() -> Result<(), MachineError> {
+		let mut runtime = Runtime::<MEMORY_SIZE>::builder().with_exit().build();
+		load(
+			&mut runtime,
+			&[
+				LOAD_RETURN_DATA_ECALL,
+				LOAD_RETURN_DATA,
+				LOAD_RETURN_DATA_LENGTH,
+				ECALL,
+				LOAD_EXIT_ECALL,
+				LOAD_EXIT_CODE,
+				ECALL,
+			],
+		)?;
+		runtime
+			.machine_mut()
+			.memory_mut()
+			.write_bytes(0x100, b"abc")
+			.map_err(MachineError::MemoryError)?;
+
+		let outcome = runtime.run();
+		assert_eq!(outcome.termination, Termination::Exited(ExitStatus::Code(42)));
+		assert_eq!(outcome.return_data.as_deref(), Some(&b"abc"[..]));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_seeded_random() -> Result<(), MachineError> {
+		let draw = |seed: [u8; 32]| -> Result<Vec<u8>, MachineError> {
+			let mut runtime = Runtime::<MEMORY_SIZE>::builder()
+				.with_seeded_random(seed)
+				.limit_ticks(4)
+				.build();
+			load(
+				&mut runtime,
+				&[LOAD_RANDOM_ECALL, LOAD_RANDOM_BUFFER, LOAD_RANDOM_LENGTH, ECALL],
+			)?;
+			runtime.run();
+			assert_eq!(runtime.machine().registers().get(12), 0);
+
+			let bytes = runtime.machine().memory().read_bytes(0x100, 16);
+			bytes.map(<[u8]>::to_vec).map_err(MachineError::MemoryError)
+		};
+
+		assert_eq!(draw([1; 32])?, draw([1; 32])?);
+		assert_ne!(draw([1; 32])?, draw([2; 32])?);
+		assert_ne!(draw([1; 32])?, vec![0; 16]);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_clocks() -> Result<(), MachineError> {
+		let read = |clock: Clock| -> Result<(u32, u64, u32), MachineError> {
+			let mut runtime =
+				Runtime::<MEMORY_SIZE>::builder().with_clock(clock).limit_ticks(3).build();
+			load(&mut runtime, &[LOAD_TIME_ECALL, LOAD_TIME_ECALL, ECALL])?;
+			runtime.run();
+
+			let registers = runtime.machine().registers();
+			let nanos = u64::from(registers.get(12)) | (u64::from(registers.get(13)) << 32);
+			Ok((registers.get(11), nanos, registers.get(14)))
+		};
+
+		assert_eq!(read(Clock::Fixed(1 << 40))?, (0, 1 << 40, 1));
+		// Two instructions were executed before the ECALL.
+		assert_eq!(
+			read(Clock::Virtual { start: 1_000, nanos_per_instruction: 10 })?,
+			(0, 1_020, 2)
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_wait_channel() -> Result<(), MachineError> {
+		let readiness = ChannelReadiness::new();
+		let wait = |timeout: [u32; 2]| -> Result<u32, MachineError> {
+			let mut runtime = Runtime::<MEMORY_SIZE>::builder()
+				.with_channel_system(SharedSystem::new(PendingChannel(readiness.clone())))
+				.limit_ticks(5)
+				.build();
+			load(
+				&mut runtime,
+				&[LOAD_WAIT_CHANNEL_ECALL, LOAD_CHANNEL_SYSTEM, timeout[0], timeout[1], ECALL],
+			)?;
+
+			let outcome = runtime.run();
+			assert_eq!(outcome.ticks, Some(5));
+			Ok(runtime.machine().registers().get(13))
+		};
+		let holding = ChannelStatusCode::Holding.to_u32();
+		let success = ChannelStatusCode::Success.to_u32();
+
+		// Nothing progresses the channel, so the wait ends with the timeout.
+		assert_eq!(wait([LOAD_TIMEOUT_LOW, LOAD_TIMEOUT_HIGH])?, holding);
+
+		// The program stays parked until another thread notifies the channel's progress.
+		let notifier = readiness.clone();
+		let progress = thread::spawn(move || {
+			thread::sleep(Duration::from_millis(10));
+			notifier.notify(1);
+		});
+		assert_eq!(wait([LOAD_NO_TIMEOUT_LOW, LOAD_NO_TIMEOUT_HIGH])?, success);
+		progress.join().map_err(|_| MachineError::SystemError("notifier panicked"))?;
+
+		// Progress made before the program waits isn't lost.
+		readiness.notify(1);
+		assert!(readiness.is_ready(1));
+		assert_eq!(wait([LOAD_NO_TIMEOUT_LOW, LOAD_NO_TIMEOUT_HIGH])?, success);
+		assert!(!readiness.is_ready(1));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_wait_channel_with_nothing_to_wait_for() -> Result<(), MachineError> {
+		let mut runtime = Runtime::<MEMORY_SIZE>::builder()
+			.with_channel_system(SharedSystem::new(ImmediateChannel))
+			.limit_ticks(5)
+			.build();
+		load(
+			&mut runtime,
+			&[
+				LOAD_WAIT_CHANNEL_ECALL,
+				LOAD_CHANNEL_SYSTEM,
+				LOAD_NO_TIMEOUT_LOW,
+				LOAD_NO_TIMEOUT_HIGH,
+				ECALL,
+			],
+		)?;
+
+		// The wait doesn't claim progress it didn't see, so the program checks the channel.
+		let _outcome = runtime.run();
+		assert_eq!(runtime.machine().registers().get(13), ChannelStatusCode::Holding.to_u32());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_ticks_exhausted() -> Result<(), MachineError> {
+		let mut runtime = Runtime::<MEMORY_SIZE>::builder().with_exit().limit_ticks(10).build();
+		load(&mut runtime, &[LOOP])?;
+
+		let outcome = runtime.run();
+		assert_eq!(outcome.termination, Termination::TicksExhausted);
+		assert_eq!(outcome.ticks, Some(10));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_counting_ticks() -> Result<(), MachineError> {
+		let mut runtime = Runtime::<MEMORY_SIZE>::builder().with_exit().build();
+		load(&mut runtime, &[LOAD_EXIT_ECALL, LOAD_ERROR_STATUS, ECALL])?;
+
+		let run = run_system_counting_ticks(&mut runtime.machine, &mut runtime.computer);
+		let outcome = runtime.outcome(run);
+		assert_eq!(outcome.termination, Termination::Exited(ExitStatus::Error));
+		assert_eq!(outcome.ticks, Some(3));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_fault() -> Result<(), MachineError> {
+		// Without ECALL dispatch, the ECALL itself is a fault.
+		let mut runtime = Runtime::<MEMORY_SIZE>::new(Rv32iComputer);
+		load(&mut runtime, &[LOAD_EXIT_ECALL, ECALL])?;
+
+		let outcome = runtime.run();
+		assert!(matches!(outcome.termination, Termination::Fault(_)));
+		assert_eq!(outcome.program_counter, 4);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_unhandled_ecall_is_not_implemented() -> Result<(), MachineError> {
+		let mut runtime = Runtime::<MEMORY_SIZE>::builder().with_exit().limit_ticks(3).build();
+		load(&mut runtime, &[LOAD_HOST_ECALL, ECALL, LOOP])?;
+
+		let outcome = runtime.run();
+		assert_eq!(outcome.termination, Termination::TicksExhausted);
+		assert_eq!(outcome.program_counter, 8);
+		assert_eq!(runtime.machine().registers().get(10), 2);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_unserved_exit_faults() -> Result<(), MachineError> {
+		for load_ecall in [LOAD_EXIT_ECALL, LOAD_ABORT_ECALL] {
+			let mut runtime = Runtime::<MEMORY_SIZE>::builder().limit_ticks(3).build();
+			load(&mut runtime, &[load_ecall, ECALL, LOOP])?;
+
+			// The program isn't resumed after the ECALL, since it doesn't expect to return.
+			let outcome = runtime.run();
+			assert!(matches!(
+				outcome.termination,
+				Termination::Fault(MachineError::SystemError(_))
+			));
+			assert_eq!(outcome.program_counter, 4);
+		}
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_ecall_handler() -> Result<(), MachineError> {
+		let mut runtime = Runtime::<MEMORY_SIZE>::builder()
+			.with_exit()
+			.with_ecall(0x100, Answer)
+			.limit_ticks(3)
+			.build();
+		load(&mut runtime, &[LOAD_HOST_ECALL, ECALL, LOOP])?;
+
+		let outcome = runtime.run();
+		assert_eq!(outcome.termination, Termination::TicksExhausted);
+		assert_eq!(runtime.machine().registers().get(10), 42);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_read_system() -> Result<(), MachineError> {
+		let mut runtime = Runtime::<MEMORY_SIZE>::builder()
+			.with_exit()
+			.with_read_system(StdInputSystem::from_bytes(b"abc".to_vec()))
+			.limit_ticks(6)
+			.build();
+		load(
+			&mut runtime,
+			&[LOAD_READ_ECALL, LOAD_STDIN, LOAD_READ_BUFFER, LOAD_READ_LENGTH, ECALL, LOOP],
+		)?;
+
+		let outcome = runtime.run();
+		assert_eq!(outcome.termination, Termination::TicksExhausted);
+		assert_eq!(runtime.machine().registers().get(15), 3);
+		assert_eq!(
+			runtime
+				.machine()
+				.memory()
+				.read_bytes(0x100, 3)
+				.map_err(MachineError::MemoryError)?,
+			b"abc"
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_write_targets() -> Result<(), MachineError> {
+		let mut runtime = Runtime::<MEMORY_SIZE>::builder()
+			.with_exit()
+			.with_write_system(
+				StdOutputSystem::empty().with_target(3, WriteTarget::Buffer(Vec::new())),
+			)
+			.limit_ticks(8)
+			.build();
+		load(
+			&mut runtime,
+			&[
+				LOAD_WRITE_ECALL,
+				LOAD_WRITE_SYSTEM,
+				LOAD_READ_BUFFER,
+				LOAD_WRITE_LENGTH,
+				ECALL,
+				LOAD_UNKNOWN_WRITE_SYSTEM,
+				ECALL,
+				LOOP,
+			],
+		)?;
+		runtime
+			.machine_mut()
+			.memory_mut()
+			.write_bytes(0x100, b"abc")
+			.map_err(MachineError::MemoryError)?;
+
+		let outcome = runtime.run();
+		assert_eq!(outcome.termination, Termination::TicksExhausted);
+		assert_eq!(outcome.program_counter, 28);
+		let write_dispatcher = &runtime.computer.ecall_dispatcher.write_dispatcher;
+		assert_eq!(write_dispatcher.buffer(3), Some(&b"abc"[..]));
+		// The write to the unknown system ID returns, so the program carries on.
+		assert_eq!(
+			runtime.machine().registers().get(13) as i32,
+			WriteStatusCode::InvalidSystem.to_i32()
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_capture_limit() -> Result<(), MachineError> {
+		let buffer = CaptureBuffer::new();
+		let mut runtime = Runtime::<MEMORY_SIZE>::builder()
+			.with_exit()
+			.with_write_system(
+				CaptureOutputSystem::new().with_buffer(3, buffer.clone()).with_limit(4),
+			)
+			.limit_ticks(7)
+			.build();
+		load(
+			&mut runtime,
+			&[
+				LOAD_WRITE_ECALL,
+				LOAD_WRITE_SYSTEM,
+				LOAD_READ_BUFFER,
+				LOAD_WRITE_LENGTH,
+				ECALL,
+				ECALL,
+				LOOP,
+			],
+		)?;
+		runtime
+			.machine_mut()
+			.memory_mut()
+			.write_bytes(0x100, b"abc")
+			.map_err(MachineError::MemoryError)?;
+
+		runtime.run();
+		// The second write would capture 6 bytes, more than the limit.
+		assert_eq!(buffer.contents(), b"abc");
+		assert_eq!(runtime.machine().registers().get(13) as i32, WriteStatusCode::Failure.to_i32());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_dynamic_ecall_table() -> Result<(), MachineError> {
+		let mut table = DynamicEcallTable::new();
+		table.register(0x100, Answer);
+		let mut runtime = Runtime::<MEMORY_SIZE>::builder()
+			.with_exit()
+			.with_ecall_table(table)
+			.limit_ticks(3)
+			.build();
+		load(&mut runtime, &[LOAD_HOST_ECALL, ECALL, LOOP])?;
+
+		runtime.run();
+		assert_eq!(runtime.machine().registers().get(10), 42);
+
+		runtime.computer.ecall_dispatcher.table.unregister(0x100);
+		runtime.machine_mut().registers_mut().program_counter_set(0);
+		runtime.run();
+		assert_eq!(runtime.machine().registers().get(10), 2);
+
+		Ok(())
+	}
+}
@@ -0,0 +1,76 @@
+use fuste_exit::ExitStatus;
+use fuste_riscv_core::machine::MachineError;
+
+/// How a run came to an end.
+#[derive(Debug, PartialEq)]
+pub enum Termination {
+	/// The program exited with the given status.
+	Exited(ExitStatus),
+	/// The program was still running when it reached the tick limit.
+	TicksExhausted,
+	/// The machine failed to execute the program.
+	Fault(MachineError),
+}
+
+/// The result of running a system on a machine, see [crate::run_system].
+#[derive(Debug)]
+pub struct SystemRun {
+	/// The error the machine stopped with, if it faulted.
+	pub result: Result<(), MachineError>,
+	/// The number of ticks executed, if they were counted.
+	pub ticks: Option<u64>,
+	/// Whether the run stopped at the tick limit.
+	pub exhausted: bool,
+}
+
+/// How a program's run ended and where it left the machine.
+#[derive(Debug, PartialEq)]
+pub struct RunOutcome {
+	pub termination: Termination,
+	/// The number of ticks executed, if they were counted.
+	pub ticks: Option<u64>,
+	/// The program counter when the run ended.
+	pub program_counter: u32,
+	/// The message the program aborted with, e.g. where it panicked.
+	pub abort_message: Option<String>,
+	/// The data the program handed back as its result.
+	pub return_data: Option<Vec<u8>>,
+}
+
+impl RunOutcome {
+	/// Builds the outcome of a run which ended at the given program counter.
+	///
+	/// The exit status is only used if the run neither faulted nor ran out of ticks.
+	pub fn new(run: SystemRun, exit_status: ExitStatus, program_counter: u32) -> Self {
+		let termination = match run.result {
+			Err(error) => Termination::Fault(error),
+			Ok(()) if run.exhausted => Termination::TicksExhausted,
+			Ok(()) => Termination::Exited(exit_status),
+		};
+
+		Self {
+			termination,
+			ticks: run.ticks,
+			program_counter,
+			abort_message: None,
+			return_data: None,
+		}
+	}
+
+	/// Records the data the program handed back, if any.
+	pub fn with_return_data(mut self, return_data: Option<Vec<u8>>) -> Self {
+		self.return_data = return_data;
+		self
+	}
+
+	/// Records the message the program aborted with, if it did.
+	pub fn with_abort_message(mut self, abort_message: Option<String>) -> Self {
+		self.abort_message = abort_message;
+		self
+	}
+
+	/// Whether the program exited successfully.
+	pub fn is_success(&self) -> bool {
+		self.termination == Termination::Exited(ExitStatus::Success)
+	}
+}
@@ -0,0 +1,91 @@
+use fuste_ecall_dispatcher::{
+	CheckChannelSystemDispatcher, ExitSystemDispatcher, OpenChannelSystemDispatcher,
+	RandomSystemDispatcher, ReadSystemDispatcher, SystemCapabilities, TimeSystemDispatcher,
+	WriteSystemDispatcher,
+};
+use fuste_riscv_core::machine::{Machine, MachineError, MachineSystem};
+use std::cell::{Ref, RefCell, RefMut};
+use std::ops::ControlFlow;
+use std::rc::Rc;
+
+/// A system shared between dispatcher slots, so that e.g. the open and check channel ECALLs are
+/// served by the same state.
+///
+/// The host can keep a clone to read the system's state once the run ends.
+pub struct SharedSystem<T>(Rc<RefCell<T>>);
+
+impl<T> SharedSystem<T> {
+	pub fn new(system: T) -> Self {
+		Self(Rc::new(RefCell::new(system)))
+	}
+
+	pub fn borrow(&self) -> Ref<'_, T> {
+		self.0.borrow()
+	}
+
+	pub fn borrow_mut(&self) -> RefMut<'_, T> {
+		self.0.borrow_mut()
+	}
+}
+
+impl<T> Clone for SharedSystem<T> {
+	fn clone(&self) -> Self {
+		Self(Rc::clone(&self.0))
+	}
+}
+
+impl<const MEMORY_SIZE: usize, T: MachineSystem<MEMORY_SIZE>> MachineSystem<MEMORY_SIZE>
+	for SharedSystem<T>
+{
+	fn tick(
+		&mut self,
+		machine: &mut Machine<MEMORY_SIZE>,
+	) -> Result<ControlFlow<()>, MachineError> {
+		self.0.borrow_mut().tick(machine)
+	}
+}
+
+impl<T: SystemCapabilities> SystemCapabilities for SharedSystem<T> {
+	fn is_supported(&self) -> bool {
+		self.0.borrow().is_supported()
+	}
+
+	fn supports_system_id(&self, system_id: u32) -> bool {
+		self.0.borrow().supports_system_id(system_id)
+	}
+}
+
+impl<const MEMORY_SIZE: usize, T: ExitSystemDispatcher<MEMORY_SIZE>>
+	ExitSystemDispatcher<MEMORY_SIZE> for SharedSystem<T>
+{
+}
+
+impl<const MEMORY_SIZE: usize, T: WriteSystemDispatcher<MEMORY_SIZE>>
+	WriteSystemDispatcher<MEMORY_SIZE> for SharedSystem<T>
+{
+}
+
+impl<const MEMORY_SIZE: usize, T: ReadSystemDispatcher<MEMORY_SIZE>>
+	ReadSystemDispatcher<MEMORY_SIZE> for SharedSystem<T>
+{
+}
+
+impl<const MEMORY_SIZE: usize, T: OpenChannelSystemDispatcher<MEMORY_SIZE>>
+	OpenChannelSystemDispatcher<MEMORY_SIZE> for SharedSystem<T>
+{
+}
+
+impl<const MEMORY_SIZE: usize, T: CheckChannelSystemDispatcher<MEMORY_SIZE>>
+	CheckChannelSystemDispatcher<MEMORY_SIZE> for SharedSystem<T>
+{
+}
+
+impl<const MEMORY_SIZE: usize, T: RandomSystemDispatcher<MEMORY_SIZE>>
+	RandomSystemDispatcher<MEMORY_SIZE> for SharedSystem<T>
+{
+}
+
+impl<const MEMORY_SIZE: usize, T: TimeSystemDispatcher<MEMORY_SIZE>>
+	TimeSystemDispatcher<MEMORY_SIZE> for SharedSystem<T>
+{
+}
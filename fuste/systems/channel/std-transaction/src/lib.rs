@@ -1,44 +1,46 @@
 pub mod containers;
+pub mod readiness;
 pub mod signer_stores;
 pub mod std_transaction;
 pub mod transaction_metadata;
 
-use fuste_channel::{ChannelError, ChannelStatus, ChannelSystemId};
+use core::ops::ControlFlow;
+use core::time::Duration;
+use fuste_abi::channel::{
+	ChannelArgs, ChannelReturns, WaitChannelArgs, WaitChannelReturns, NO_TIMEOUT,
+};
+use fuste_channel::{
+	ChannelError, ChannelStatus, ChannelStatusCode, ChannelSystemId, ChannelSystemStatus,
+};
 use fuste_ecall::Ecall;
+use fuste_ecall_dispatcher::{ecall_args, ecall_number, return_from_ecall};
 use fuste_riscv_core::machine::{Machine, MachineError};
 
 pub trait ChannelSystem {
 	fn handle_channel_tick<const MEMORY_SIZE: usize>(
 		&mut self,
 		machine: &mut Machine<MEMORY_SIZE>,
-	) -> Result<(), MachineError> {
-		// system id is in a0
-		let system_id = machine.csrs().registers().get(17);
-		let channel_system_id = ChannelSystemId::new(system_id);
-
-		// read buffer is in a1 with length in a2
-		let read_buffer_address = machine.csrs().registers().get(18);
-		let read_buffer_length = machine.csrs().registers().get(19);
-
-		let write_buffer_address = machine.csrs().registers().get(20);
-		let write_buffer_length = machine.csrs().registers().get(21);
+	) -> Result<ControlFlow<()>, MachineError> {
+		let ecall = Ecall::try_from_u32(ecall_number(machine))
+			.map_err(|_e| MachineError::SystemError("invalid ecall"))?;
+		if ecall == Ecall::WaitChannel {
+			return self.handle_wait_tick(machine);
+		}
 
-		let ecall = machine.csrs().registers().get(17);
-		let ecall =
-			Ecall::try_from_u32(ecall).map_err(|_e| MachineError::SystemError("invalid ecall"))?;
+		let args: ChannelArgs = ecall_args(machine);
+		let channel_system_id = ChannelSystemId::new(args.system_id);
 
 		// read the read buffer into a dynamic store
 		// this is just for debugging environment simplicity
 		let read_buffer = machine
 			.memory()
-			.read_bytes(read_buffer_address, read_buffer_length)
+			.read_bytes(args.read_buffer_address, args.read_buffer_length)
 			.map_err(MachineError::MemoryError)?
 			.to_vec();
 
-		// write buffer is in a3 with length in a4
 		let write_buffer = machine
 			.memory_mut()
-			.read_bytes_mut(write_buffer_address, write_buffer_length)
+			.read_bytes_mut(args.write_buffer_address, args.write_buffer_length)
 			.map_err(MachineError::MemoryError)?;
 
 		let channel_status = match ecall {
@@ -48,25 +50,51 @@ pub trait ChannelSystem {
 		}
 		.map_err(|_e| MachineError::SystemError("channel operation failed"))?;
 
-		// write the channel status code to a5
-		machine
-			.csrs_mut()
-			.registers_mut()
-			.set(17, channel_status.code().clone().to_u32());
-
-		// write the size of the buffer written to a4
-		machine.csrs_mut().registers_mut().set(18, channel_status.size());
-
-		// write the channel system status to a6
-		machine
-			.csrs_mut()
-			.registers_mut()
-			.set(19, channel_status.system_status().clone().to_u32());
+		return_from_ecall(
+			machine,
+			&ChannelReturns {
+				status: channel_status.code().clone().to_i32(),
+				size: channel_status.size(),
+				system_status: channel_status.system_status().clone().to_i32(),
+			},
+		)
+	}
 
-		// increment the program counter
-		machine.commit_csrs();
+	/// Parks the program on the wait channel ECALL until [ChannelSystem::handle_wait] returns.
+	fn handle_wait_tick<const MEMORY_SIZE: usize>(
+		&mut self,
+		machine: &mut Machine<MEMORY_SIZE>,
+	) -> Result<ControlFlow<()>, MachineError> {
+		let args: WaitChannelArgs = ecall_args(machine);
+		let timeout =
+			(args.timeout_nanos != NO_TIMEOUT).then(|| Duration::from_nanos(args.timeout_nanos));
+
+		let channel_status = self
+			.handle_wait(ChannelSystemId::new(args.system_id), timeout)
+			.map_err(|_e| MachineError::SystemError("channel wait failed"))?;
+
+		return_from_ecall(
+			machine,
+			&WaitChannelReturns {
+				status: channel_status.code().clone().to_i32(),
+				system_status: channel_status.system_status().clone().to_i32(),
+			},
+		)
+	}
 
-		Ok(())
+	/// Waits until the channel has progressed, returning [ChannelStatusCode::Success], or the
+	/// timeout has passed, returning [ChannelStatusCode::Holding].
+	///
+	/// Systems completing their operations elsewhere park the thread, e.g. with
+	/// [readiness::ChannelReadiness::park], so the machine isn't ticked until they progress. By
+	/// default there's nothing to wait for, so it returns [ChannelStatusCode::Holding] straight
+	/// away and the program checks the channel for its status.
+	fn handle_wait(
+		&mut self,
+		_channel_system_id: ChannelSystemId,
+		_timeout: Option<Duration>,
+	) -> Result<ChannelStatus, ChannelError> {
+		Ok(ChannelStatus::new(0, ChannelStatusCode::Holding, ChannelSystemStatus::new(0)))
 	}
 
 	fn handle_open(
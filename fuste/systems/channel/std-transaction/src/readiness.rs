@@ -0,0 +1,64 @@
+use std::collections::BTreeSet;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Signals that channels have progressed, waking a program parked waiting on one of them.
+///
+/// A channel system whose operations complete outside of the program, e.g. on another thread,
+/// shares a clone with whatever completes them, which calls [ChannelReadiness::notify] when it
+/// does. A notification is kept until it's waited for, so one sent before the program waits isn't
+/// lost.
+#[derive(Debug, Clone, Default)]
+pub struct ChannelReadiness(Arc<(Mutex<BTreeSet<u32>>, Condvar)>);
+
+impl ChannelReadiness {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Marks the channel system ID as having progressed, waking a program waiting on it.
+	pub fn notify(&self, system_id: u32) {
+		let (_, progressed) = &*self.0;
+		self.lock().insert(system_id);
+		progressed.notify_all();
+	}
+
+	/// Whether the channel system ID has progressed since it was last waited for.
+	pub fn is_ready(&self, system_id: u32) -> bool {
+		self.lock().contains(&system_id)
+	}
+
+	/// Parks the thread until the channel system ID progresses or the timeout passes, returning
+	/// whether it progressed.
+	///
+	/// Without a timeout the thread stays parked until something notifies the ID, however long that
+	/// takes. The machine isn't ticked meanwhile, so a tick limit can't end the run; hosts which
+	/// must bound it pass a timeout of their own.
+	pub fn park(&self, system_id: u32, timeout: Option<Duration>) -> bool {
+		let (_, progressed) = &*self.0;
+		// A timeout too far off to tell the time of is as good as none.
+		let deadline = timeout.and_then(|timeout| Instant::now().checked_add(timeout));
+		let mut ready = self.lock();
+
+		while !ready.remove(&system_id) {
+			ready = match deadline {
+				Some(deadline) => {
+					let Some(remaining) = deadline.checked_duration_since(Instant::now()) else {
+						return false;
+					};
+					progressed
+						.wait_timeout(ready, remaining)
+						.unwrap_or_else(PoisonError::into_inner)
+						.0
+				}
+				None => progressed.wait(ready).unwrap_or_else(PoisonError::into_inner),
+			};
+		}
+		true
+	}
+
+	fn lock(&self) -> MutexGuard<'_, BTreeSet<u32>> {
+		// The set is only inserted into and removed from, so it's still whole after a panic.
+		self.0 .0.lock().unwrap_or_else(PoisonError::into_inner)
+	}
+}
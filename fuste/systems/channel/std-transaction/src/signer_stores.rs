@@ -1,6 +1,8 @@
 pub mod store;
 
 use crate::containers::{HartIndex, UserSignerIndex};
+use std::cell::RefCell;
+use std::collections::HashMap;
 
 #[derive(thiserror::Error, Debug)]
 pub enum SignerStoreBackendError {
@@ -59,3 +61,38 @@ impl SignerStoreBackend for NoopSignerStoreBackend {
 		Ok(None)
 	}
 }
+
+/// Keeps values in memory for as long as the backend lives, e.g. for the length of a run.
+#[derive(Default, Debug, Clone)]
+pub struct MemorySignerStoreBackend {
+	values: RefCell<HashMap<(HartIndex, UserSignerIndex, Vec<u8>), Vec<u8>>>,
+}
+
+impl SignerStoreBackend for MemorySignerStoreBackend {
+	fn write(
+		&self,
+		hart_index: &HartIndex,
+		user_signer_index: &UserSignerIndex,
+		type_bytes: &[u8],
+		value_bytes: &[u8],
+	) -> Result<(), SignerStoreBackendError> {
+		self.values.borrow_mut().insert(
+			(hart_index.clone(), user_signer_index.clone(), type_bytes.to_vec()),
+			value_bytes.to_vec(),
+		);
+		Ok(())
+	}
+
+	fn read(
+		&self,
+		hart_index: &HartIndex,
+		user_signer_index: &UserSignerIndex,
+		type_bytes: &[u8],
+	) -> Result<Option<Vec<u8>>, SignerStoreBackendError> {
+		Ok(self
+			.values
+			.borrow()
+			.get(&(hart_index.clone(), user_signer_index.clone(), type_bytes.to_vec()))
+			.cloned())
+	}
+}
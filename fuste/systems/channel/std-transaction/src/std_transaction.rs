@@ -4,8 +4,11 @@ use crate::transaction_metadata::transaction_signer_at_index::TransactionSignerA
 use crate::transaction_metadata::TransactionMetadata;
 use crate::{ChannelSubsystem, ChannelSystem};
 use core::ops::ControlFlow;
-use fuste_channel::{ChannelError, ChannelStatus};
-use fuste_ecall_dispatcher::OpenChannelSystemDispatcher;
+use core::time::Duration;
+use fuste_channel::{ChannelError, ChannelStatus, ChannelStatusCode, ChannelSystemStatus};
+use fuste_ecall_dispatcher::{
+	CheckChannelSystemDispatcher, OpenChannelSystemDispatcher, SystemCapabilities,
+};
 use fuste_riscv_core::machine::MachineSystem;
 use fuste_riscv_core::machine::{Machine, MachineError};
 use fuste_std_transaction::signer::TransactionSignerAtIndex;
@@ -32,6 +35,11 @@ impl<
 		S: SignerStoreBackend,
 	> StdTransaction<ADDRESS_BYTES, PUBLIC_KEY_BYTES, SIGNER_COUNT, TYPE_NAME_BYTES, VALUE_BYTES, S>
 {
+	/// Creates a transaction system which stores signer values in the given backend.
+	pub fn new(backend: S) -> Self {
+		Self { transaction_metadata: TransactionMetadata::default(), backend }
+	}
+
 	pub fn as_signer_storage(
 		&self,
 	) -> SignerStorage<ADDRESS_BYTES, PUBLIC_KEY_BYTES, SIGNER_COUNT, TYPE_NAME_BYTES, VALUE_BYTES, S>
@@ -60,6 +68,20 @@ impl<
 	> ChannelSystem
 	for StdTransaction<ADDRESS_BYTES, PUBLIC_KEY_BYTES, SIGNER_COUNT, TYPE_NAME_BYTES, VALUE_BYTES, S>
 {
+	/// The signer channel completes when it's opened, so it has always progressed.
+	fn handle_wait(
+		&mut self,
+		channel_system_id: fuste_channel::ChannelSystemId,
+		_timeout: Option<Duration>,
+	) -> Result<ChannelStatus, ChannelError> {
+		match channel_system_id {
+			TransactionSignerAtIndex::CHANNEL_SYSTEM_ID => {
+				Ok(ChannelStatus::new(0, ChannelStatusCode::Success, ChannelSystemStatus::new(0)))
+			}
+			_ => Err(ChannelError::invalid_system()),
+		}
+	}
+
 	fn handle_check(
 		&mut self,
 		channel_system_id: fuste_channel::ChannelSystemId,
@@ -104,8 +126,22 @@ impl<
 		&mut self,
 		machine: &mut Machine<MEMORY_SIZE>,
 	) -> Result<ControlFlow<()>, MachineError> {
-		self.handle_channel_tick(machine)?;
-		Ok(ControlFlow::Continue(()))
+		self.handle_channel_tick(machine)
+	}
+}
+
+impl<
+		const ADDRESS_BYTES: usize,
+		const PUBLIC_KEY_BYTES: usize,
+		const SIGNER_COUNT: usize,
+		const TYPE_NAME_BYTES: usize,
+		const VALUE_BYTES: usize,
+		S: SignerStoreBackend,
+	> SystemCapabilities
+	for StdTransaction<ADDRESS_BYTES, PUBLIC_KEY_BYTES, SIGNER_COUNT, TYPE_NAME_BYTES, VALUE_BYTES, S>
+{
+	fn supports_system_id(&self, system_id: u32) -> bool {
+		system_id == TransactionSignerAtIndex::CHANNEL_SYSTEM_ID.to_u32()
 	}
 }
 
@@ -122,6 +158,19 @@ impl<
 {
 }
 
+impl<
+		const MEMORY_SIZE: usize,
+		const ADDRESS_BYTES: usize,
+		const PUBLIC_KEY_BYTES: usize,
+		const SIGNER_COUNT: usize,
+		const TYPE_NAME_BYTES: usize,
+		const VALUE_BYTES: usize,
+		S: SignerStoreBackend,
+	> CheckChannelSystemDispatcher<MEMORY_SIZE>
+	for StdTransaction<ADDRESS_BYTES, PUBLIC_KEY_BYTES, SIGNER_COUNT, TYPE_NAME_BYTES, VALUE_BYTES, S>
+{
+}
+
 impl<
 		const ADDRESS_BYTES: usize,
 		const PUBLIC_KEY_BYTES: usize,
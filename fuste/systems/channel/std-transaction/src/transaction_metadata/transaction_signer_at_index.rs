@@ -44,9 +44,9 @@ impl<'a, const ADDRESS_BYTES: usize, const PUBLIC_KEY_BYTES: usize> ChannelSubsy
 		};
 
 		// Serialize the transaction signer to the write buffer
-		signer.try_write_to_buffer(write_buffer).map_err(|_| ChannelError::Internal)?;
+		let size = signer.try_write_to_buffer(write_buffer).map_err(|_| ChannelError::Internal)?;
 
-		Ok(ChannelStatus::new(0, ChannelStatusCode::Success, ChannelSystemStatus::new(0)))
+		Ok(ChannelStatus::new(size as u32, ChannelStatusCode::Success, ChannelSystemStatus::new(0)))
 	}
 
 	fn handle_subsystem_check(
@@ -0,0 +1,149 @@
+pub mod report;
+
+pub use report::{FileCoverage, FunctionCoverage, InstructionSummary, LineCoverage};
+
+use core::ops::ControlFlow;
+use fuste_riscv_core::machine::{Machine, MachineError, MachineSystem};
+use std::collections::BTreeMap;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CoverageError {
+	#[error("Failed to write coverage report: {0}")]
+	Io(#[from] std::io::Error),
+}
+
+/// The program counters retired during execution and how often each was retired.
+#[derive(Debug, Clone, Default)]
+pub struct Coverage {
+	pub executed: BTreeMap<u32, u64>,
+}
+
+impl Coverage {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	#[inline(always)]
+	pub fn record(&mut self, pc: u32) {
+		*self.executed.entry(pc).or_insert(0) += 1;
+	}
+
+	/// The number of times the instruction at the address was retired.
+	pub fn hits(&self, pc: u32) -> u64 {
+		self.executed.get(&pc).copied().unwrap_or(0)
+	}
+}
+
+/// The [CoverageSystem] records each program counter retired by the inner computer.
+pub struct CoverageSystem<const MEMORY_SIZE: usize, Computer: MachineSystem<MEMORY_SIZE>> {
+	pub computer: Computer,
+	pub coverage: Coverage,
+}
+
+impl<const MEMORY_SIZE: usize, Computer: MachineSystem<MEMORY_SIZE>>
+	CoverageSystem<MEMORY_SIZE, Computer>
+{
+	pub fn new(computer: Computer) -> Self {
+		Self { computer, coverage: Coverage::new() }
+	}
+}
+
+impl<const MEMORY_SIZE: usize, Computer: MachineSystem<MEMORY_SIZE>> MachineSystem<MEMORY_SIZE>
+	for CoverageSystem<MEMORY_SIZE, Computer>
+{
+	fn tick(
+		&mut self,
+		machine: &mut Machine<MEMORY_SIZE>,
+	) -> Result<ControlFlow<()>, MachineError> {
+		let pc = machine.registers().program_counter();
+
+		let control_flow = self.computer.tick(machine)?;
+
+		self.coverage.record(pc);
+
+		Ok(control_flow)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use fuste_riscv_core::instructions::rv32i::{Addi, Beq, B};
+	use fuste_riscv_core::instructions::WordInstruction;
+	use fuste_riscv_core::plugins::rv32i_computer::Rv32iComputer;
+	use fuste_riscv_core::program;
+	use fuste_riscv_elf::lines::{LineRow, LineTable};
+	use fuste_riscv_elf::sections::{Section, SectionTable};
+	use fuste_riscv_elf::symbols::{Symbol, SymbolTable};
+	use std::path::PathBuf;
+
+	/// Skips over the instruction at 0x08, which is never executed.
+	fn covered_program() -> Result<Coverage, MachineError> {
+		let mut machine = Machine::<1024>::new();
+		let program = program![
+			Addi::of(1, 0, 1),                // 0x00: line 1
+			Beq::new(B::new(0b000, 0, 0, 8)), // 0x04: line 1
+			Addi::of(1, 1, 1),                // 0x08: line 2
+			Addi::of(2, 0, 2)                 // 0x0C: line 3
+		];
+		machine
+			.memory_mut()
+			.load_word_segment(0, &program)
+			.map_err(MachineError::MemoryError)?;
+
+		let mut coverage_system = CoverageSystem::new(Rv32iComputer);
+		for _ in 0..3 {
+			let _control_flow = coverage_system.tick(&mut machine)?;
+		}
+
+		Ok(coverage_system.coverage)
+	}
+
+	fn line_table() -> LineTable {
+		let row = |address, line, end_sequence| LineRow { address, file: 0, line, end_sequence };
+		LineTable::new(
+			vec![PathBuf::from("/src/main.rs")],
+			vec![row(0x00, 1, false), row(0x08, 2, false), row(0x0C, 3, false), row(0x10, 0, true)],
+		)
+	}
+
+	#[test]
+	fn test_records_executed_program_counters() -> Result<(), MachineError> {
+		let coverage = covered_program()?;
+
+		assert_eq!(coverage.executed.keys().copied().collect::<Vec<_>>(), vec![0x00, 0x04, 0x0C]);
+		assert_eq!(coverage.hits(0x08), 0);
+
+		let sections = SectionTable::new(vec![Section {
+			name: ".text".to_string(),
+			address: 0,
+			size: 0x10,
+			executable: true,
+		}]);
+		assert_eq!(
+			coverage.instruction_summary(&sections),
+			InstructionSummary { executed: 3, total: 4 }
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_lcov() -> Result<(), Box<dyn std::error::Error>> {
+		let coverage = covered_program()?;
+		let symbols =
+			SymbolTable::new(vec![Symbol { name: "main".to_string(), address: 0, size: 0x10 }]);
+
+		let line_coverage = coverage.line_coverage(&line_table(), &symbols);
+		let mut lcov = Vec::new();
+		line_coverage.write_lcov(&mut lcov)?;
+
+		assert_eq!(
+			String::from_utf8(lcov)?,
+			"TN:\nSF:/src/main.rs\nFN:1,main\nFNDA:1,main\nFNF:1\nFNH:1\n\
+			 DA:1,1\nDA:2,0\nDA:3,1\nLF:3\nLH:2\nend_of_record\n"
+		);
+
+		Ok(())
+	}
+}
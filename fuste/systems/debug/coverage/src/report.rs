@@ -0,0 +1,151 @@
+use crate::{Coverage, CoverageError};
+use fuste_riscv_elf::lines::LineTable;
+use fuste_riscv_elf::sections::SectionTable;
+use fuste_riscv_elf::symbols::SymbolTable;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// The number of instruction words in executable sections and how many of them were retired.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InstructionSummary {
+	pub executed: usize,
+	pub total: usize,
+}
+
+impl InstructionSummary {
+	pub fn percent(&self) -> f64 {
+		if self.total == 0 {
+			0.0
+		} else {
+			self.executed as f64 * 100.0 / self.total as f64
+		}
+	}
+}
+
+/// A function whose entry maps to a source line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionCoverage {
+	pub name: String,
+	pub line: u32,
+	/// The number of times the entry instruction was retired.
+	pub hits: u64,
+}
+
+/// The coverage of a single source file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileCoverage {
+	pub path: PathBuf,
+	/// The hit count of each line with instructions.
+	///
+	/// This is the number of times the most executed instruction of the line was retired.
+	pub lines: BTreeMap<u32, u64>,
+	pub functions: Vec<FunctionCoverage>,
+}
+
+/// Source line coverage, ordered by file path.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LineCoverage {
+	pub files: Vec<FileCoverage>,
+}
+
+fn file_coverage<'a>(
+	files: &'a mut BTreeMap<usize, FileCoverage>,
+	lines: &LineTable,
+	index: usize,
+) -> &'a mut FileCoverage {
+	files.entry(index).or_insert_with(|| FileCoverage {
+		path: lines.file(index).map(PathBuf::from).unwrap_or_default(),
+		..FileCoverage::default()
+	})
+}
+
+impl Coverage {
+	/// Summarizes the retired instructions against the executable sections.
+	pub fn instruction_summary(&self, sections: &SectionTable) -> InstructionSummary {
+		let mut summary = InstructionSummary { executed: 0, total: 0 };
+		for section in sections.executable() {
+			summary.total += section.size as usize / 4;
+			summary.executed += self.executed.range(section.range()).count();
+		}
+		summary
+	}
+
+	/// Maps the retired instructions to source lines.
+	///
+	/// Every line in the line table is reported, so lines which were never executed have a
+	/// hit count of zero.
+	pub fn line_coverage(&self, lines: &LineTable, symbols: &SymbolTable) -> LineCoverage {
+		let mut files = BTreeMap::<usize, FileCoverage>::new();
+
+		for row in lines.rows().filter(|row| row.line > 0) {
+			file_coverage(&mut files, lines, row.file).lines.entry(row.line).or_insert(0);
+		}
+
+		for (pc, hits) in &self.executed {
+			let Some(row) = lines.lookup(*pc).filter(|row| row.line > 0) else {
+				continue;
+			};
+			let line =
+				file_coverage(&mut files, lines, row.file).lines.entry(row.line).or_insert(0);
+			*line = (*line).max(*hits);
+		}
+
+		for symbol in symbols.iter().filter(|symbol| symbol.size > 0) {
+			let Some(row) = lines.lookup(symbol.address).filter(|row| row.line > 0) else {
+				continue;
+			};
+			file_coverage(&mut files, lines, row.file).functions.push(FunctionCoverage {
+				name: symbol.demangled_name(),
+				line: row.line,
+				hits: self.hits(symbol.address),
+			});
+		}
+
+		let mut files: Vec<FileCoverage> = files.into_values().collect();
+		files.sort_by(|a, b| a.path.cmp(&b.path));
+		LineCoverage { files }
+	}
+}
+
+impl LineCoverage {
+	pub fn lines_found(&self) -> usize {
+		self.files.iter().map(|file| file.lines.len()).sum()
+	}
+
+	pub fn lines_hit(&self) -> usize {
+		self.files
+			.iter()
+			.map(|file| file.lines.values().filter(|hits| **hits > 0).count())
+			.sum()
+	}
+
+	/// Writes the coverage in the lcov tracefile format read by `genhtml` and most CI tooling.
+	pub fn write_lcov(&self, writer: &mut impl Write) -> Result<(), CoverageError> {
+		for file in &self.files {
+			writeln!(writer, "TN:")?;
+			writeln!(writer, "SF:{}", file.path.display())?;
+
+			for function in &file.functions {
+				writeln!(writer, "FN:{},{}", function.line, function.name)?;
+			}
+			for function in &file.functions {
+				writeln!(writer, "FNDA:{},{}", function.hits, function.name)?;
+			}
+			writeln!(writer, "FNF:{}", file.functions.len())?;
+			writeln!(
+				writer,
+				"FNH:{}",
+				file.functions.iter().filter(|function| function.hits > 0).count()
+			)?;
+
+			for (line, hits) in &file.lines {
+				writeln!(writer, "DA:{line},{hits}")?;
+			}
+			writeln!(writer, "LF:{}", file.lines.len())?;
+			writeln!(writer, "LH:{}", file.lines.values().filter(|hits| **hits > 0).count())?;
+			writeln!(writer, "end_of_record")?;
+		}
+		Ok(())
+	}
+}
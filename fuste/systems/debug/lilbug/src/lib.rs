@@ -1,45 +1,48 @@
-pub mod exit;
-
 use core::ops::ControlFlow;
-use fuste_exit::ExitStatus;
 use fuste_riscv_core::instructions::Rv32iInstruction;
 use fuste_riscv_core::machine::{Machine, MachineError, MachineSystem};
+use fuste_riscv_elf::program::SymbolizedAddress;
+use fuste_riscv_elf::symbols::SymbolTable;
+use fuste_runtime::RuntimeComputer;
 
-pub trait LilBugComputer<const MEMORY_SIZE: usize>: MachineSystem<MEMORY_SIZE> {
-	fn exit_status(&self) -> ExitStatus;
-}
-
-pub struct LilBugSystem<const MEMORY_SIZE: usize, Computer: LilBugComputer<MEMORY_SIZE>> {
+pub struct LilBugSystem<const MEMORY_SIZE: usize, Computer: RuntimeComputer<MEMORY_SIZE>> {
 	pub computer: Computer,
 	pub log_program_counter: bool,
 	pub log_registers: bool,
 	pub log_instructions: bool,
 	pub log_registers_at_end: bool,
 	pub log_exit_status: bool,
+	/// Used to print addresses relative to the symbol containing them, may be empty.
+	pub symbols: SymbolTable,
 }
 
-impl<const MEMORY_SIZE: usize, Computer: LilBugComputer<MEMORY_SIZE>> MachineSystem<MEMORY_SIZE>
+impl<const MEMORY_SIZE: usize, Computer: RuntimeComputer<MEMORY_SIZE>> MachineSystem<MEMORY_SIZE>
 	for LilBugSystem<MEMORY_SIZE, Computer>
 {
 	fn tick(
 		&mut self,
 		machine: &mut Machine<MEMORY_SIZE>,
 	) -> Result<ControlFlow<()>, MachineError> {
+		let address = machine.registers().program_counter();
 		if self.log_program_counter {
-			println!("program counter: 0x{:X}", machine.registers().program_counter());
+			println!("program counter: {}", SymbolizedAddress::new(&self.symbols, address));
 		}
 		if self.log_registers {
 			println!("registers: {:?}", machine.registers());
 		}
 
-		let address = machine.registers().program_counter();
 		let instruction = machine.memory().read_word(address).map_err(MachineError::MemoryError)?;
 		if self.log_instructions {
 			let decoded_instruction = Rv32iInstruction::<MEMORY_SIZE>::from_word(instruction)
 				.map_err(|_e| {
 					MachineError::SystemError("Failed to decode instruction for debugger")
 				})?;
-			println!("0x{address:08X}: {:40} <- 0b{:032b}", decoded_instruction, instruction);
+			println!(
+				"{}: {:40} <- 0b{:032b}",
+				SymbolizedAddress::new(&self.symbols, address),
+				decoded_instruction,
+				instruction
+			);
 		}
 		let control_flow = self.computer.tick(machine)?;
 
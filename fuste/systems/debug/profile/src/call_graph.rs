@@ -0,0 +1,109 @@
+/// A node in the [CallGraph], identified by the path of calls leading to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallNode {
+	/// The address the function was entered at.
+	pub address: u32,
+	/// The calling node, or `None` for the root.
+	pub parent: Option<usize>,
+	/// The called nodes, in the order they were first called.
+	pub children: Vec<usize>,
+	/// The number of instructions retired while this node was on top of the stack.
+	pub self_ticks: u64,
+}
+
+/// A calling context tree built from the calls and returns observed during execution.
+///
+/// Each distinct stack of calls gets its own node, so recursion grows the tree.
+#[derive(Debug, Clone)]
+pub struct CallGraph {
+	nodes: Vec<CallNode>,
+	current: usize,
+}
+
+impl CallGraph {
+	/// The index of the root node.
+	pub const ROOT: usize = 0;
+
+	/// Creates a call graph rooted at the given entry address.
+	pub fn new(entry: u32) -> Self {
+		Self {
+			nodes: vec![CallNode {
+				address: entry,
+				parent: None,
+				children: Vec::new(),
+				self_ticks: 0,
+			}],
+			current: Self::ROOT,
+		}
+	}
+
+	pub fn nodes(&self) -> &[CallNode] {
+		&self.nodes
+	}
+
+	pub fn node(&self, index: usize) -> &CallNode {
+		&self.nodes[index]
+	}
+
+	/// The index of the node on top of the stack.
+	pub fn current(&self) -> usize {
+		self.current
+	}
+
+	/// Attributes a retired instruction to the node on top of the stack.
+	#[inline(always)]
+	pub fn tick(&mut self) {
+		self.nodes[self.current].self_ticks += 1;
+	}
+
+	/// Pushes a call to the given address.
+	pub fn call(&mut self, address: u32) {
+		let existing = self.nodes[self.current]
+			.children
+			.iter()
+			.copied()
+			.find(|child| self.nodes[*child].address == address);
+
+		self.current = match existing {
+			Some(child) => child,
+			None => {
+				let child = self.nodes.len();
+				self.nodes.push(CallNode {
+					address,
+					parent: Some(self.current),
+					children: Vec::new(),
+					self_ticks: 0,
+				});
+				self.nodes[self.current].children.push(child);
+				child
+			}
+		};
+	}
+
+	/// Pops the node on top of the stack.
+	///
+	/// Returning from the root is ignored, since the caller of the entrypoint is unknown.
+	pub fn ret(&mut self) {
+		if let Some(parent) = self.nodes[self.current].parent {
+			self.current = parent;
+		}
+	}
+
+	/// The addresses of the stack ending at the given node, from the root down.
+	pub fn stack(&self, index: usize) -> Vec<u32> {
+		let mut stack = Vec::new();
+		let mut node = Some(index);
+		while let Some(index) = node {
+			stack.push(self.nodes[index].address);
+			node = self.nodes[index].parent;
+		}
+		stack.reverse();
+		stack
+	}
+
+	/// The number of instructions retired within the given node and its callees.
+	pub fn total_ticks(&self, index: usize) -> u64 {
+		let node = &self.nodes[index];
+		node.self_ticks + node.children.iter().map(|child| self.total_ticks(*child)).sum::<u64>()
+	}
+}
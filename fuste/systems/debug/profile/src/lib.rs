@@ -0,0 +1,149 @@
+pub mod call_graph;
+pub mod pprof;
+pub mod profile;
+pub mod report;
+
+pub use call_graph::{CallGraph, CallNode};
+pub use profile::{Profile, StackEffect};
+pub use report::FlatEntry;
+
+use core::ops::ControlFlow;
+use fuste_riscv_core::machine::{Machine, MachineError, MachineSystem};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ProfileError {
+	#[error("Failed to write profile: {0}")]
+	Io(#[from] std::io::Error),
+}
+
+/// The [ProfileSystem] counts the instructions retired by the inner computer
+/// and tracks calls and returns to build a call graph.
+///
+/// Calls are `jal` and `jalr` instructions which write a link register;
+/// returns are `jalr` instructions which jump through one.
+pub struct ProfileSystem<const MEMORY_SIZE: usize, Computer: MachineSystem<MEMORY_SIZE>> {
+	pub computer: Computer,
+	pub profile: Profile,
+}
+
+impl<const MEMORY_SIZE: usize, Computer: MachineSystem<MEMORY_SIZE>>
+	ProfileSystem<MEMORY_SIZE, Computer>
+{
+	pub fn new(computer: Computer) -> Self {
+		Self { computer, profile: Profile::new() }
+	}
+}
+
+impl<const MEMORY_SIZE: usize, Computer: MachineSystem<MEMORY_SIZE>> MachineSystem<MEMORY_SIZE>
+	for ProfileSystem<MEMORY_SIZE, Computer>
+{
+	fn tick(
+		&mut self,
+		machine: &mut Machine<MEMORY_SIZE>,
+	) -> Result<ControlFlow<()>, MachineError> {
+		let pc = machine.registers().program_counter();
+		let word = machine.memory().read_word(pc).map_err(MachineError::MemoryError)?;
+
+		let control_flow = self.computer.tick(machine)?;
+
+		self.profile.record(pc, word, machine.registers().program_counter());
+
+		Ok(control_flow)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use fuste_riscv_core::instructions::rv32i::{Addi, Jal, Jalr, I};
+	use fuste_riscv_core::instructions::WordInstruction;
+	use fuste_riscv_core::plugins::rv32i_computer::Rv32iComputer;
+	use fuste_riscv_core::program;
+	use fuste_riscv_elf::symbols::{Symbol, SymbolTable};
+
+	/// `main` calls `leaf` twice, and `leaf` runs two instructions per call.
+	fn profiled_program() -> Result<Profile, MachineError> {
+		let mut machine = Machine::<1024>::new();
+		let program = program![
+			Jal::of(1, 16),                    // 0x00: main: call leaf
+			Jal::of(1, 12),                    // 0x04: call leaf
+			Addi::of(10, 0, 0),                // 0x08
+			Jal::of(0, 0),                     // 0x0C: spin
+			Addi::of(11, 11, 1),               // 0x10: leaf
+			Jalr::new(I::new(0, 0b000, 1, 0))  // 0x14: ret
+		];
+		machine
+			.memory_mut()
+			.load_word_segment(0, &program)
+			.map_err(MachineError::MemoryError)?;
+
+		let mut profiler = ProfileSystem::new(Rv32iComputer);
+		for _ in 0..8 {
+			let _control_flow = profiler.tick(&mut machine)?;
+		}
+
+		Ok(profiler.profile)
+	}
+
+	fn symbols() -> SymbolTable {
+		SymbolTable::new(vec![
+			Symbol { name: "main".to_string(), address: 0x00, size: 0x10 },
+			Symbol { name: "leaf".to_string(), address: 0x10, size: 0x08 },
+		])
+	}
+
+	#[test]
+	fn test_stack_effect() {
+		assert_eq!(StackEffect::of(Jal::of(1, 8).to_word()), StackEffect::Call);
+		assert_eq!(StackEffect::of(Jal::of(0, 8).to_word()), StackEffect::None);
+		assert_eq!(StackEffect::of(Jalr::new(I::new(1, 0, 6, 0)).to_word()), StackEffect::Call);
+		assert_eq!(StackEffect::of(Jalr::new(I::new(0, 0, 1, 0)).to_word()), StackEffect::Return);
+		assert_eq!(StackEffect::of(Jalr::new(I::new(0, 0, 6, 0)).to_word()), StackEffect::None);
+	}
+
+	#[test]
+	fn test_instruction_counts_and_call_graph() -> Result<(), MachineError> {
+		let profile = profiled_program()?;
+
+		assert_eq!(profile.total_ticks, 8);
+		assert_eq!(profile.instructions.get(&0x10), Some(&2));
+		assert_eq!(profile.instructions.get(&0x0C), Some(&1));
+
+		let call_graph = profile
+			.call_graph
+			.as_ref()
+			.ok_or(MachineError::SystemError("call graph should be created"))?;
+		let root = call_graph.node(CallGraph::ROOT);
+		assert_eq!(root.address, 0x00);
+		assert_eq!(root.self_ticks, 4);
+		assert_eq!(root.children.len(), 1);
+		assert_eq!(call_graph.node(root.children[0]).self_ticks, 4);
+		assert_eq!(call_graph.total_ticks(CallGraph::ROOT), 8);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_symbolized_reports() -> Result<(), MachineError> {
+		let profile = profiled_program()?;
+		let symbols = symbols();
+
+		assert_eq!(
+			profile.flat(&symbols),
+			vec![
+				FlatEntry { function: "main".to_string(), self_ticks: 4, total_ticks: 8 },
+				FlatEntry { function: "leaf".to_string(), self_ticks: 4, total_ticks: 4 },
+			]
+		);
+		assert_eq!(
+			profile.folded(&symbols),
+			vec![("main".to_string(), 4), ("main;leaf".to_string(), 4)]
+		);
+
+		let pprof = pprof::encode(&profile, &symbols);
+		assert!(pprof.windows(4).any(|window| window == b"leaf"));
+		assert!(pprof.windows(12).any(|window| window == b"instructions"));
+
+		Ok(())
+	}
+}
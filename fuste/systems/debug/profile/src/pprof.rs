@@ -0,0 +1,191 @@
+//! A minimal encoder for the [pprof protobuf format](https://github.com/google/pprof/blob/main/proto/profile.proto).
+//!
+//! Only the fields needed for a sampled call graph are written: one sample per call graph node,
+//! one location per called address and one function per symbol.
+
+use crate::report::function_name;
+use crate::Profile;
+use fuste_riscv_elf::symbols::SymbolTable;
+use std::collections::HashMap;
+
+const WIRE_VARINT: u8 = 0;
+const WIRE_LENGTH_DELIMITED: u8 = 2;
+
+mod field {
+	pub const PROFILE_SAMPLE_TYPE: u32 = 1;
+	pub const PROFILE_SAMPLE: u32 = 2;
+	pub const PROFILE_LOCATION: u32 = 4;
+	pub const PROFILE_FUNCTION: u32 = 5;
+	pub const PROFILE_STRING_TABLE: u32 = 6;
+	pub const PROFILE_PERIOD_TYPE: u32 = 11;
+	pub const PROFILE_PERIOD: u32 = 12;
+
+	pub const VALUE_TYPE_TYPE: u32 = 1;
+	pub const VALUE_TYPE_UNIT: u32 = 2;
+
+	pub const SAMPLE_LOCATION_ID: u32 = 1;
+	pub const SAMPLE_VALUE: u32 = 2;
+
+	pub const LOCATION_ID: u32 = 1;
+	pub const LOCATION_ADDRESS: u32 = 3;
+	pub const LOCATION_LINE: u32 = 4;
+
+	pub const LINE_FUNCTION_ID: u32 = 1;
+
+	pub const FUNCTION_ID: u32 = 1;
+	pub const FUNCTION_NAME: u32 = 2;
+	pub const FUNCTION_SYSTEM_NAME: u32 = 3;
+}
+
+/// A protobuf message under construction.
+#[derive(Default)]
+struct Message {
+	bytes: Vec<u8>,
+}
+
+impl Message {
+	fn varint(&mut self, mut value: u64) {
+		while value >= 0x80 {
+			self.bytes.push((value as u8) | 0x80);
+			value >>= 7;
+		}
+		self.bytes.push(value as u8);
+	}
+
+	fn key(&mut self, field: u32, wire_type: u8) {
+		self.varint(((field as u64) << 3) | wire_type as u64);
+	}
+
+	fn uint(&mut self, field: u32, value: u64) {
+		self.key(field, WIRE_VARINT);
+		self.varint(value);
+	}
+
+	fn bytes(&mut self, field: u32, bytes: &[u8]) {
+		self.key(field, WIRE_LENGTH_DELIMITED);
+		self.varint(bytes.len() as u64);
+		self.bytes.extend_from_slice(bytes);
+	}
+
+	fn message(&mut self, field: u32, message: Message) {
+		self.bytes(field, &message.bytes);
+	}
+
+	fn packed(&mut self, field: u32, values: impl IntoIterator<Item = u64>) {
+		let mut packed = Message::default();
+		for value in values {
+			packed.varint(value);
+		}
+		self.bytes(field, &packed.bytes);
+	}
+}
+
+/// Interns strings into the pprof string table, whose first entry must be empty.
+struct StringTable {
+	strings: Vec<String>,
+	indices: HashMap<String, u64>,
+}
+
+impl StringTable {
+	fn new() -> Self {
+		Self { strings: vec![String::new()], indices: HashMap::from([(String::new(), 0)]) }
+	}
+
+	fn intern(&mut self, string: &str) -> u64 {
+		if let Some(index) = self.indices.get(string) {
+			return *index;
+		}
+		let index = self.strings.len() as u64;
+		self.strings.push(string.to_string());
+		self.indices.insert(string.to_string(), index);
+		index
+	}
+}
+
+fn value_type(strings: &mut StringTable, r#type: &str, unit: &str) -> Message {
+	let mut message = Message::default();
+	message.uint(field::VALUE_TYPE_TYPE, strings.intern(r#type));
+	message.uint(field::VALUE_TYPE_UNIT, strings.intern(unit));
+	message
+}
+
+/// Encodes the profile as an uncompressed pprof protobuf.
+pub fn encode(profile: &Profile, symbols: &SymbolTable) -> Vec<u8> {
+	let mut strings = StringTable::new();
+	let mut message = Message::default();
+
+	message.message(field::PROFILE_SAMPLE_TYPE, value_type(&mut strings, "instructions", "count"));
+
+	let mut location_ids = HashMap::<u32, u64>::new();
+	let mut function_ids = HashMap::<String, u64>::new();
+	let mut locations = Vec::new();
+	let mut functions = Vec::new();
+
+	if let Some(call_graph) = &profile.call_graph {
+		for node in call_graph.nodes() {
+			if location_ids.contains_key(&node.address) {
+				continue;
+			}
+
+			let name = function_name(symbols, node.address);
+			let function_id = match function_ids.get(&name) {
+				Some(id) => *id,
+				None => {
+					let id = function_ids.len() as u64 + 1;
+					let system_name = symbols
+						.symbolize(node.address)
+						.map(|(symbol, _offset)| symbol.name.as_str())
+						.unwrap_or(name.as_str());
+					let mut function = Message::default();
+					function.uint(field::FUNCTION_ID, id);
+					function.uint(field::FUNCTION_NAME, strings.intern(&name));
+					function.uint(field::FUNCTION_SYSTEM_NAME, strings.intern(system_name));
+					functions.push(function);
+					function_ids.insert(name, id);
+					id
+				}
+			};
+
+			let location_id = location_ids.len() as u64 + 1;
+			let mut line = Message::default();
+			line.uint(field::LINE_FUNCTION_ID, function_id);
+			let mut location = Message::default();
+			location.uint(field::LOCATION_ID, location_id);
+			location.uint(field::LOCATION_ADDRESS, node.address as u64);
+			location.message(field::LOCATION_LINE, line);
+			locations.push(location);
+			location_ids.insert(node.address, location_id);
+		}
+
+		for (index, node) in call_graph.nodes().iter().enumerate() {
+			if node.self_ticks == 0 {
+				continue;
+			}
+			// pprof stacks are ordered from the leaf to the root.
+			let mut sample = Message::default();
+			sample.packed(
+				field::SAMPLE_LOCATION_ID,
+				call_graph.stack(index).iter().rev().map(|address| location_ids[address]),
+			);
+			sample.packed(field::SAMPLE_VALUE, [node.self_ticks]);
+			message.message(field::PROFILE_SAMPLE, sample);
+		}
+	}
+
+	for location in locations {
+		message.message(field::PROFILE_LOCATION, location);
+	}
+	for function in functions {
+		message.message(field::PROFILE_FUNCTION, function);
+	}
+
+	let period_type = value_type(&mut strings, "instructions", "count");
+	message.message(field::PROFILE_PERIOD_TYPE, period_type);
+	message.uint(field::PROFILE_PERIOD, 1);
+
+	for string in &strings.strings {
+		message.bytes(field::PROFILE_STRING_TABLE, string.as_bytes());
+	}
+
+	message.bytes
+}
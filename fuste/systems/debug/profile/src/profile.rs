@@ -0,0 +1,67 @@
+use crate::call_graph::CallGraph;
+use fuste_riscv_core::instructions::rv32i::{Jal, Jalr};
+use fuste_riscv_core::instructions::WordInstruction;
+use std::collections::BTreeMap;
+
+/// The link registers recognized by the RISC-V calling convention: `ra` and `t0`.
+const LINK_REGISTERS: [u8; 2] = [1, 5];
+
+/// How an instruction moves the call stack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StackEffect {
+	/// A `jal` or `jalr` which writes a link register.
+	Call,
+	/// A `jalr` which jumps through a link register without writing one, i.e. `ret`.
+	Return,
+	None,
+}
+
+impl StackEffect {
+	pub fn of(word: u32) -> Self {
+		match word & 0b1111111 {
+			Jal::OPCODE if LINK_REGISTERS.contains(&Jal::from_word(word).rd()) => Self::Call,
+			Jalr::OPCODE => {
+				let jalr = Jalr::from_word(word);
+				if LINK_REGISTERS.contains(&jalr.rd()) {
+					Self::Call
+				} else if jalr.rd() == 0 && LINK_REGISTERS.contains(&jalr.rs1()) {
+					Self::Return
+				} else {
+					Self::None
+				}
+			}
+			_ => Self::None,
+		}
+	}
+}
+
+/// The instruction counts and call graph collected by the [crate::ProfileSystem].
+#[derive(Debug, Clone, Default)]
+pub struct Profile {
+	/// The number of times each program counter was retired.
+	pub instructions: BTreeMap<u32, u64>,
+	/// The call graph, created on the first retired instruction.
+	pub call_graph: Option<CallGraph>,
+	/// The total number of retired instructions.
+	pub total_ticks: u64,
+}
+
+impl Profile {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Records a retired instruction and the program counter it transferred control to.
+	pub fn record(&mut self, pc: u32, word: u32, next_pc: u32) {
+		self.total_ticks += 1;
+		*self.instructions.entry(pc).or_insert(0) += 1;
+
+		let call_graph = self.call_graph.get_or_insert_with(|| CallGraph::new(pc));
+		call_graph.tick();
+		match StackEffect::of(word) {
+			StackEffect::Call => call_graph.call(next_pc),
+			StackEffect::Return => call_graph.ret(),
+			StackEffect::None => (),
+		}
+	}
+}
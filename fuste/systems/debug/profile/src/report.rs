@@ -0,0 +1,149 @@
+use crate::{pprof, Profile, ProfileError};
+use fuste_riscv_elf::symbols::SymbolTable;
+use std::collections::{BTreeSet, HashMap};
+use std::io::Write;
+
+/// The name of the function containing the address, or the address itself if unknown.
+pub fn function_name(symbols: &SymbolTable, address: u32) -> String {
+	match symbols.symbolize(address) {
+		Some((symbol, _offset)) => symbol.demangled_name(),
+		None => format!("0x{address:08x}"),
+	}
+}
+
+/// A row of the flat profile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlatEntry {
+	pub function: String,
+	/// Instructions retired within the function itself.
+	pub self_ticks: u64,
+	/// Instructions retired while the function was on the call stack.
+	pub total_ticks: u64,
+}
+
+impl Profile {
+	/// Aggregates the profile by function, ordered by self ticks.
+	pub fn flat(&self, symbols: &SymbolTable) -> Vec<FlatEntry> {
+		let mut self_ticks = HashMap::<String, u64>::new();
+		for (pc, ticks) in &self.instructions {
+			*self_ticks.entry(function_name(symbols, *pc)).or_insert(0) += ticks;
+		}
+
+		// A function is counted once per stack, so recursion doesn't inflate its total.
+		let mut total_ticks = HashMap::<String, u64>::new();
+		if let Some(call_graph) = &self.call_graph {
+			for (index, node) in call_graph.nodes().iter().enumerate() {
+				if node.self_ticks == 0 {
+					continue;
+				}
+				let functions: BTreeSet<String> = call_graph
+					.stack(index)
+					.into_iter()
+					.map(|address| function_name(symbols, address))
+					.collect();
+				for function in functions {
+					*total_ticks.entry(function).or_insert(0) += node.self_ticks;
+				}
+			}
+		}
+		// Code reached without a call, e.g. a tail jump, still counts toward its own total.
+		for (function, ticks) in &self_ticks {
+			let total = total_ticks.entry(function.clone()).or_insert(0);
+			*total = (*total).max(*ticks);
+		}
+
+		let mut entries: Vec<FlatEntry> = total_ticks
+			.into_iter()
+			.map(|(function, total_ticks)| FlatEntry {
+				self_ticks: self_ticks.get(&function).copied().unwrap_or(0),
+				function,
+				total_ticks,
+			})
+			.collect();
+		entries.sort_by(|a, b| {
+			b.self_ticks
+				.cmp(&a.self_ticks)
+				.then_with(|| b.total_ticks.cmp(&a.total_ticks))
+				.then_with(|| a.function.cmp(&b.function))
+		});
+		entries
+	}
+
+	/// Writes the flat profile as a table.
+	pub fn write_flat(
+		&self,
+		symbols: &SymbolTable,
+		writer: &mut impl Write,
+	) -> Result<(), ProfileError> {
+		let percent = |ticks: u64| {
+			if self.total_ticks == 0 {
+				0.0
+			} else {
+				ticks as f64 * 100.0 / self.total_ticks as f64
+			}
+		};
+
+		writeln!(
+			writer,
+			"{:>12} {:>7} {:>12} {:>7}  function",
+			"self", "self%", "total", "total%"
+		)?;
+		for entry in self.flat(symbols) {
+			writeln!(
+				writer,
+				"{:>12} {:>6.2}% {:>12} {:>6.2}%  {}",
+				entry.self_ticks,
+				percent(entry.self_ticks),
+				entry.total_ticks,
+				percent(entry.total_ticks),
+				entry.function
+			)?;
+		}
+		Ok(())
+	}
+
+	/// The folded stacks of the profile, one `root;caller;callee ticks` line per stack.
+	///
+	/// This is the input format of `flamegraph.pl` and `inferno-flamegraph`.
+	pub fn folded(&self, symbols: &SymbolTable) -> Vec<(String, u64)> {
+		let Some(call_graph) = &self.call_graph else {
+			return Vec::new();
+		};
+
+		let mut folded = Vec::new();
+		for (index, node) in call_graph.nodes().iter().enumerate() {
+			if node.self_ticks == 0 {
+				continue;
+			}
+			let stack: Vec<String> = call_graph
+				.stack(index)
+				.into_iter()
+				.map(|address| function_name(symbols, address))
+				.collect();
+			folded.push((stack.join(";"), node.self_ticks));
+		}
+		folded
+	}
+
+	/// Writes the folded stacks of the profile.
+	pub fn write_folded(
+		&self,
+		symbols: &SymbolTable,
+		writer: &mut impl Write,
+	) -> Result<(), ProfileError> {
+		for (stack, ticks) in self.folded(symbols) {
+			writeln!(writer, "{stack} {ticks}")?;
+		}
+		Ok(())
+	}
+
+	/// Writes the profile as an uncompressed pprof protobuf.
+	pub fn write_pprof(
+		&self,
+		symbols: &SymbolTable,
+		writer: &mut impl Write,
+	) -> Result<(), ProfileError> {
+		writer.write_all(&pprof::encode(self, symbols))?;
+		Ok(())
+	}
+}
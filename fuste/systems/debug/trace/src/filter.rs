@@ -0,0 +1,37 @@
+use crate::TraceError;
+use fuste_riscv_elf::symbols::SymbolTable;
+use std::ops::Range;
+
+/// Selects which program counters are traced.
+///
+/// An empty filter traces everything.
+#[derive(Debug, Clone, Default)]
+pub struct TraceFilter {
+	ranges: Vec<Range<u32>>,
+}
+
+impl TraceFilter {
+	/// A filter which traces every instruction.
+	pub fn all() -> Self {
+		Self::default()
+	}
+
+	/// Adds a range of program counters to trace.
+	pub fn with_pc_range(mut self, range: Range<u32>) -> Self {
+		self.ranges.push(range);
+		self
+	}
+
+	/// Adds the address range of the named symbol to trace.
+	pub fn with_symbol(self, symbols: &SymbolTable, name: &str) -> Result<Self, TraceError> {
+		let range = symbols
+			.range(name)
+			.ok_or_else(|| TraceError::SymbolNotFound(name.to_string()))?;
+		Ok(self.with_pc_range(range))
+	}
+
+	#[inline(always)]
+	pub fn contains(&self, pc: u32) -> bool {
+		self.ranges.is_empty() || self.ranges.iter().any(|range| range.contains(&pc))
+	}
+}
@@ -0,0 +1,171 @@
+pub mod filter;
+pub mod record;
+pub mod sink;
+
+pub use filter::TraceFilter;
+pub use record::{MemoryAccess, MemoryAccessKind, RegisterWrite, TraceRecord};
+pub use sink::{BinaryReader, BinarySink, JsonLinesSink, TraceSink};
+
+use core::ops::ControlFlow;
+use fuste_riscv_core::machine::{Machine, MachineError, MachineSystem};
+use record::PendingRecord;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TraceError {
+	#[error("Failed to write trace: {0}")]
+	Io(#[from] std::io::Error),
+	#[error("Failed to serialize trace record: {0}")]
+	Json(#[from] serde_json::Error),
+	#[error("Failed to read symbols for trace filter: {0}")]
+	Symbols(#[from] fuste_riscv_elf::ElfLoaderError),
+	#[error("Symbol \"{0}\" not found for trace filter")]
+	SymbolNotFound(String),
+	#[error("Invalid binary trace header")]
+	InvalidBinaryHeader,
+	#[error("Invalid memory access kind in binary trace: {0}")]
+	InvalidMemoryAccessKind(u8),
+}
+
+/// The [TraceSystem] records each instruction retired by the inner computer to a [TraceSink].
+///
+/// Unlike the machine log, the trace system is available in release builds.
+pub struct TraceSystem<
+	const MEMORY_SIZE: usize,
+	Computer: MachineSystem<MEMORY_SIZE>,
+	Sink: TraceSink,
+> {
+	pub computer: Computer,
+	pub sink: Sink,
+	pub filter: TraceFilter,
+	pub current_tick: u64,
+}
+
+impl<const MEMORY_SIZE: usize, Computer: MachineSystem<MEMORY_SIZE>, Sink: TraceSink>
+	TraceSystem<MEMORY_SIZE, Computer, Sink>
+{
+	pub fn new(computer: Computer, sink: Sink, filter: TraceFilter) -> Self {
+		Self { computer, sink, filter, current_tick: 0 }
+	}
+}
+
+impl<const MEMORY_SIZE: usize, Computer: MachineSystem<MEMORY_SIZE>, Sink: TraceSink>
+	MachineSystem<MEMORY_SIZE> for TraceSystem<MEMORY_SIZE, Computer, Sink>
+{
+	fn tick(
+		&mut self,
+		machine: &mut Machine<MEMORY_SIZE>,
+	) -> Result<ControlFlow<()>, MachineError> {
+		let tick = self.current_tick;
+		self.current_tick += 1;
+
+		let pc = machine.registers().program_counter();
+		if !self.filter.contains(pc) {
+			return self.computer.tick(machine);
+		}
+
+		let word = machine.memory().read_word(pc).map_err(MachineError::MemoryError)?;
+		let pending = PendingRecord::decode::<MEMORY_SIZE>(tick, pc, word, machine.registers());
+
+		let control_flow = self.computer.tick(machine)?;
+
+		let record = pending.complete(machine.registers(), &machine.memory().memory);
+		self.sink
+			.record(&record)
+			.map_err(|_e| MachineError::SystemError("failed to write trace record"))?;
+
+		if control_flow.is_break() {
+			self.sink
+				.flush()
+				.map_err(|_e| MachineError::SystemError("failed to flush trace sink"))?;
+		}
+
+		Ok(control_flow)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use fuste_riscv_core::instructions::rv32i::{Addi, Lw, Sw, I, S};
+	use fuste_riscv_core::instructions::WordInstruction;
+	use fuste_riscv_core::plugins::rv32i_computer::Rv32iComputer;
+	use fuste_riscv_core::program;
+
+	fn traced_program(filter: TraceFilter) -> Result<Vec<TraceRecord>, MachineError> {
+		let mut machine = Machine::<1024>::new();
+		let program = program![
+			Addi::of(1, 0, 42),                  // x1 = 42
+			Sw::new(S::new(0b010, 0, 1, 0x100)), // mem[0x100] = x1
+			Lw::new(I::new(2, 0b010, 0, 0x100))  // x2 = mem[0x100]
+		];
+		machine
+			.memory_mut()
+			.load_word_segment(0, &program)
+			.map_err(MachineError::MemoryError)?;
+
+		let mut trace = TraceSystem::new(Rv32iComputer, Vec::new(), filter);
+		for _ in 0..3 {
+			let _control_flow = trace.tick(&mut machine)?;
+		}
+
+		Ok(trace.sink)
+	}
+
+	#[test]
+	fn test_trace_records_effects() -> Result<(), MachineError> {
+		let records = traced_program(TraceFilter::all())?;
+
+		assert_eq!(records.len(), 3);
+		assert_eq!(records[0].op, "addi x1, x0, 42");
+		assert_eq!(records[0].register_writes, vec![RegisterWrite { register: 1, value: 42 }]);
+		assert_eq!(
+			records[1].memory,
+			Some(MemoryAccess {
+				kind: MemoryAccessKind::Store,
+				address: 0x100,
+				size: 4,
+				value: 42
+			})
+		);
+		assert!(records[1].register_writes.is_empty());
+		assert_eq!(records[2].register_writes, vec![RegisterWrite { register: 2, value: 42 }]);
+		assert_eq!(
+			records[2].memory.as_ref().map(|memory| memory.kind),
+			Some(MemoryAccessKind::Load)
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_trace_filter_by_pc_range() -> Result<(), MachineError> {
+		let records = traced_program(TraceFilter::all().with_pc_range(4..8))?;
+
+		assert_eq!(records.len(), 1);
+		assert_eq!(records[0].pc, 4);
+		assert_eq!(records[0].tick, 1);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_binary_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+		let records = traced_program(TraceFilter::all())?;
+
+		let mut sink = BinarySink::new(Vec::new());
+		for record in &records {
+			sink.record(record)?;
+		}
+		sink.flush()?;
+
+		let bytes = sink.into_inner();
+		let mut reader = BinaryReader::new(bytes.as_slice())?;
+		for record in &records {
+			let decoded = reader.read_record()?;
+			assert_eq!(decoded, Some(TraceRecord { op: String::new(), ..record.clone() }));
+		}
+		assert_eq!(reader.read_record()?, None);
+
+		Ok(())
+	}
+}
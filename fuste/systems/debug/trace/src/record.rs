@@ -0,0 +1,127 @@
+use fuste_riscv_core::instructions::Rv32iInstruction;
+use fuste_riscv_core::machine::Registers;
+use serde::{Deserialize, Serialize};
+
+/// A single retired instruction and its observable effects.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceRecord {
+	/// The tick at which the instruction was executed.
+	pub tick: u64,
+	/// The address of the instruction.
+	pub pc: u32,
+	/// The raw instruction word.
+	pub word: u32,
+	/// The decoded instruction, or `"invalid"` if the word does not decode.
+	pub op: String,
+	/// The general purpose registers whose values changed during the tick.
+	pub register_writes: Vec<RegisterWrite>,
+	/// The memory access performed by a load or store.
+	pub memory: Option<MemoryAccess>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegisterWrite {
+	pub register: u8,
+	pub value: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MemoryAccessKind {
+	Load,
+	Store,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryAccess {
+	pub kind: MemoryAccessKind,
+	pub address: u32,
+	/// The width of the access in bytes.
+	pub size: u8,
+	/// The value loaded or stored, zero-extended.
+	pub value: u32,
+}
+
+/// The part of an instruction which is known before it executes.
+pub struct PendingRecord {
+	pub tick: u64,
+	pub pc: u32,
+	pub word: u32,
+	pub op: String,
+	pub registers: [u32; 32],
+	pub memory: Option<(MemoryAccessKind, u32, u8)>,
+}
+
+impl PendingRecord {
+	/// Decodes the instruction at the program counter against the current registers.
+	pub fn decode<const MEMORY_SIZE: usize>(
+		tick: u64,
+		pc: u32,
+		word: u32,
+		registers: &Registers,
+	) -> Self {
+		let address = |rs1: u8, imm: i32| registers.get(rs1 as usize).wrapping_add(imm as u32);
+
+		let (op, memory) = match Rv32iInstruction::<MEMORY_SIZE>::from_word(word) {
+			Ok(instruction) => {
+				let memory = match &instruction {
+					Rv32iInstruction::Lb(lb) => {
+						Some((MemoryAccessKind::Load, address(lb.rs1(), lb.imm()), 1))
+					}
+					Rv32iInstruction::Lbu(lbu) => {
+						Some((MemoryAccessKind::Load, address(lbu.rs1(), lbu.imm()), 1))
+					}
+					Rv32iInstruction::Lh(lh) => {
+						Some((MemoryAccessKind::Load, address(lh.rs1(), lh.imm()), 2))
+					}
+					Rv32iInstruction::Lhu(lhu) => {
+						Some((MemoryAccessKind::Load, address(lhu.rs1(), lhu.imm()), 2))
+					}
+					Rv32iInstruction::Lw(lw) => {
+						Some((MemoryAccessKind::Load, address(lw.rs1(), lw.imm()), 4))
+					}
+					Rv32iInstruction::Sb(sb) => {
+						Some((MemoryAccessKind::Store, address(sb.rs1(), sb.imm()), 1))
+					}
+					Rv32iInstruction::Sh(sh) => {
+						Some((MemoryAccessKind::Store, address(sh.rs1(), sh.imm()), 2))
+					}
+					Rv32iInstruction::Sw(sw) => {
+						Some((MemoryAccessKind::Store, address(sw.rs1(), sw.imm()), 4))
+					}
+					_ => None,
+				};
+				(instruction.to_string(), memory)
+			}
+			Err(_e) => ("invalid".to_string(), None),
+		};
+
+		Self { tick, pc, word, op, registers: *registers.general_purpose(), memory }
+	}
+
+	/// Completes the record by diffing the registers and reading back the accessed memory.
+	pub fn complete(self, registers: &Registers, memory: &[u8]) -> TraceRecord {
+		let register_writes = (1..32)
+			.filter(|index| registers.get(*index) != self.registers[*index])
+			.map(|index| RegisterWrite { register: index as u8, value: registers.get(index) })
+			.collect();
+
+		let memory = self.memory.map(|(kind, address, size)| {
+			let mut bytes = [0u8; 4];
+			let start = address as usize;
+			if let Some(accessed) = memory.get(start..start + size as usize) {
+				bytes[..size as usize].copy_from_slice(accessed);
+			}
+			MemoryAccess { kind, address, size, value: u32::from_le_bytes(bytes) }
+		});
+
+		TraceRecord {
+			tick: self.tick,
+			pc: self.pc,
+			word: self.word,
+			op: self.op,
+			register_writes,
+			memory,
+		}
+	}
+}
@@ -0,0 +1,214 @@
+use crate::record::{MemoryAccess, MemoryAccessKind, RegisterWrite, TraceRecord};
+use crate::TraceError;
+use std::io::{Read, Write};
+
+/// A destination for [TraceRecord]s.
+pub trait TraceSink {
+	fn record(&mut self, record: &TraceRecord) -> Result<(), TraceError>;
+
+	fn flush(&mut self) -> Result<(), TraceError>;
+}
+
+impl<T: TraceSink + ?Sized> TraceSink for Box<T> {
+	fn record(&mut self, record: &TraceRecord) -> Result<(), TraceError> {
+		(**self).record(record)
+	}
+
+	fn flush(&mut self) -> Result<(), TraceError> {
+		(**self).flush()
+	}
+}
+
+/// Collects records in memory. Useful for tests and embedders.
+impl TraceSink for Vec<TraceRecord> {
+	fn record(&mut self, record: &TraceRecord) -> Result<(), TraceError> {
+		self.push(record.clone());
+		Ok(())
+	}
+
+	fn flush(&mut self) -> Result<(), TraceError> {
+		Ok(())
+	}
+}
+
+/// Writes one JSON object per line.
+pub struct JsonLinesSink<W: Write> {
+	writer: W,
+}
+
+impl<W: Write> JsonLinesSink<W> {
+	pub fn new(writer: W) -> Self {
+		Self { writer }
+	}
+
+	pub fn into_inner(self) -> W {
+		self.writer
+	}
+}
+
+impl<W: Write> TraceSink for JsonLinesSink<W> {
+	fn record(&mut self, record: &TraceRecord) -> Result<(), TraceError> {
+		serde_json::to_writer(&mut self.writer, record)?;
+		self.writer.write_all(b"\n")?;
+		Ok(())
+	}
+
+	fn flush(&mut self) -> Result<(), TraceError> {
+		self.writer.flush()?;
+		Ok(())
+	}
+}
+
+/// Writes records in a compact little-endian binary format.
+///
+/// The stream starts with the [BinarySink::MAGIC] bytes and a `u16` version.
+/// Each record is then laid out as:
+///
+/// | field                  | type  |
+/// |------------------------|-------|
+/// | tick                   | `u64` |
+/// | pc                     | `u32` |
+/// | word                   | `u32` |
+/// | register write count   | `u8`  |
+/// | memory access kind     | `u8` (0 = none, 1 = load, 2 = store) |
+/// | register writes        | count x (`u8` register, `u32` value) |
+/// | memory access          | `u32` address, `u8` size, `u32` value, if present |
+///
+/// The decoded op is not stored since it can be recovered from the word.
+pub struct BinarySink<W: Write> {
+	writer: W,
+	header_written: bool,
+}
+
+impl<W: Write> BinarySink<W> {
+	pub const MAGIC: [u8; 4] = *b"FTRC";
+	pub const VERSION: u16 = 1;
+
+	pub fn new(writer: W) -> Self {
+		Self { writer, header_written: false }
+	}
+
+	pub fn into_inner(self) -> W {
+		self.writer
+	}
+
+	fn write_header(&mut self) -> Result<(), TraceError> {
+		self.writer.write_all(&Self::MAGIC)?;
+		self.writer.write_all(&Self::VERSION.to_le_bytes())?;
+		self.header_written = true;
+		Ok(())
+	}
+}
+
+impl<W: Write> TraceSink for BinarySink<W> {
+	fn record(&mut self, record: &TraceRecord) -> Result<(), TraceError> {
+		if !self.header_written {
+			self.write_header()?;
+		}
+
+		let memory_kind = match &record.memory {
+			None => 0u8,
+			Some(MemoryAccess { kind: MemoryAccessKind::Load, .. }) => 1,
+			Some(MemoryAccess { kind: MemoryAccessKind::Store, .. }) => 2,
+		};
+
+		self.writer.write_all(&record.tick.to_le_bytes())?;
+		self.writer.write_all(&record.pc.to_le_bytes())?;
+		self.writer.write_all(&record.word.to_le_bytes())?;
+		self.writer.write_all(&[record.register_writes.len() as u8, memory_kind])?;
+		for write in &record.register_writes {
+			self.writer.write_all(&[write.register])?;
+			self.writer.write_all(&write.value.to_le_bytes())?;
+		}
+		if let Some(memory) = &record.memory {
+			self.writer.write_all(&memory.address.to_le_bytes())?;
+			self.writer.write_all(&[memory.size])?;
+			self.writer.write_all(&memory.value.to_le_bytes())?;
+		}
+
+		Ok(())
+	}
+
+	fn flush(&mut self) -> Result<(), TraceError> {
+		if !self.header_written {
+			self.write_header()?;
+		}
+		self.writer.flush()?;
+		Ok(())
+	}
+}
+
+/// Reads records written by a [BinarySink].
+///
+/// The `op` of each record is left empty.
+pub struct BinaryReader<R: Read> {
+	reader: R,
+}
+
+impl<R: Read> BinaryReader<R> {
+	pub fn new(mut reader: R) -> Result<Self, TraceError> {
+		let mut magic = [0u8; 4];
+		reader.read_exact(&mut magic)?;
+		let mut version = [0u8; 2];
+		reader.read_exact(&mut version)?;
+		if magic != BinarySink::<Vec<u8>>::MAGIC
+			|| u16::from_le_bytes(version) != BinarySink::<Vec<u8>>::VERSION
+		{
+			return Err(TraceError::InvalidBinaryHeader);
+		}
+
+		Ok(Self { reader })
+	}
+
+	fn read_array<const N: usize>(&mut self) -> Result<[u8; N], TraceError> {
+		let mut bytes = [0u8; N];
+		self.reader.read_exact(&mut bytes)?;
+		Ok(bytes)
+	}
+
+	/// Reads the next record, returning `None` at the end of the stream.
+	pub fn read_record(&mut self) -> Result<Option<TraceRecord>, TraceError> {
+		let mut tick = [0u8; 8];
+		match self.reader.read_exact(&mut tick) {
+			Ok(()) => (),
+			Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+			Err(e) => return Err(e.into()),
+		}
+
+		let pc = u32::from_le_bytes(self.read_array()?);
+		let word = u32::from_le_bytes(self.read_array()?);
+		let [write_count, memory_kind] = self.read_array()?;
+
+		let mut register_writes = Vec::with_capacity(write_count as usize);
+		for _ in 0..write_count {
+			let [register] = self.read_array()?;
+			let value = u32::from_le_bytes(self.read_array()?);
+			register_writes.push(RegisterWrite { register, value });
+		}
+
+		let kind = match memory_kind {
+			0 => None,
+			1 => Some(MemoryAccessKind::Load),
+			2 => Some(MemoryAccessKind::Store),
+			other => return Err(TraceError::InvalidMemoryAccessKind(other)),
+		};
+		let memory = match kind {
+			Some(kind) => {
+				let address = u32::from_le_bytes(self.read_array()?);
+				let [size] = self.read_array()?;
+				let value = u32::from_le_bytes(self.read_array()?);
+				Some(MemoryAccess { kind, address, size, value })
+			}
+			None => None,
+		};
+
+		Ok(Some(TraceRecord {
+			tick: u64::from_le_bytes(tick),
+			pc,
+			word,
+			op: String::new(),
+			register_writes,
+			memory,
+		}))
+	}
+}
@@ -1,15 +1,93 @@
 #![no_std]
 
+extern crate alloc;
+
+pub mod table;
+
 use core::ops::ControlFlow;
-use fuste_ecall::Ecall;
+use fuste_abi::{EcallRegisters, Register, RegisterLayout, ECALL_NUMBER_REGISTER};
+use fuste_ecall::{Ecall, EcallStatus};
 use fuste_interrupt_handler::EcallDispatcherOps;
 use fuste_riscv_core::{
 	instructions::EcallInterrupt,
 	machine::{Machine, MachineError, MachineSystem},
 };
+use table::EcallTable;
 
-/// Marker trait for exit system dispatchers.
-pub trait ExitSystemDispatcher<const MEMORY_SIZE: usize>: MachineSystem<MEMORY_SIZE> {}
+/// The number of the ECALL the machine trapped on.
+pub fn ecall_number<const MEMORY_SIZE: usize>(machine: &Machine<MEMORY_SIZE>) -> u32 {
+	machine.csrs().registers().get(ECALL_NUMBER_REGISTER as usize)
+}
+
+/// Reads the arguments of the ECALL the machine trapped on, laid out as `fuste_abi` defines.
+pub fn ecall_args<Args: RegisterLayout, const MEMORY_SIZE: usize>(
+	machine: &Machine<MEMORY_SIZE>,
+) -> Args {
+	let registers = EcallRegisters::from_fn(|register| {
+		machine.csrs().registers().get(register.index() as usize)
+	});
+	Args::read_registers(&registers)
+}
+
+/// Returns the results to the program, laid out as `fuste_abi` defines, and resumes it after the
+/// ECALL.
+pub fn return_from_ecall<Returns: RegisterLayout, const MEMORY_SIZE: usize>(
+	machine: &mut Machine<MEMORY_SIZE>,
+	returns: &Returns,
+) -> Result<ControlFlow<()>, MachineError> {
+	// Start from the registers the program trapped with, so those the results don't use are kept.
+	let mut registers = EcallRegisters::from_fn(|register| {
+		machine.csrs().registers().get(register.index() as usize)
+	});
+	returns.write_registers(&mut registers);
+	for (register, value) in registers.iter() {
+		machine.csrs_mut().registers_mut().set(register.index(), value);
+	}
+	machine.csrs_mut().registers_mut().program_counter_mut().increment();
+	machine.commit_csrs();
+	Ok(ControlFlow::Continue(()))
+}
+
+/// Returns the status to the program in a0 and resumes it after the ECALL.
+pub fn resume_with_status<const MEMORY_SIZE: usize>(
+	machine: &mut Machine<MEMORY_SIZE>,
+	status: EcallStatus,
+) -> Result<ControlFlow<()>, MachineError> {
+	machine.csrs_mut().registers_mut().set(Register::A0.index(), status.to_u32());
+	machine.csrs_mut().registers_mut().program_counter_mut().increment();
+	machine.commit_csrs();
+	Ok(ControlFlow::Continue(()))
+}
+
+/// Reports what a system can serve, so that programs can be checked before they run.
+pub trait SystemCapabilities {
+	/// Whether the system serves its ECALL at all. Placeholders like [NoopDispatcher] don't.
+	fn is_supported(&self) -> bool {
+		true
+	}
+
+	/// Whether the system serves the given write, read or channel system ID.
+	fn supports_system_id(&self, _system_id: u32) -> bool {
+		false
+	}
+}
+
+/// [None] serves nothing, [Some] serves what the inner system does.
+impl<T: SystemCapabilities> SystemCapabilities for Option<T> {
+	fn is_supported(&self) -> bool {
+		self.as_ref().is_some_and(T::is_supported)
+	}
+
+	fn supports_system_id(&self, system_id: u32) -> bool {
+		self.as_ref().is_some_and(|system| system.supports_system_id(system_id))
+	}
+}
+
+/// Marker trait for exit system dispatchers, which serve the exit, abort and return data ECALLs.
+pub trait ExitSystemDispatcher<const MEMORY_SIZE: usize>:
+	MachineSystem<MEMORY_SIZE> + SystemCapabilities
+{
+}
 
 /// Implement ExitSystemDispatcher for Option<T: ExitSystemDispatcher<MEMORY_SIZE>>
 impl<const MEMORY_SIZE: usize, T: ExitSystemDispatcher<MEMORY_SIZE>>
@@ -18,7 +96,10 @@ impl<const MEMORY_SIZE: usize, T: ExitSystemDispatcher<MEMORY_SIZE>>
 }
 
 /// Marker trait for write system dispatchers.
-pub trait WriteSystemDispatcher<const MEMORY_SIZE: usize>: MachineSystem<MEMORY_SIZE> {}
+pub trait WriteSystemDispatcher<const MEMORY_SIZE: usize>:
+	MachineSystem<MEMORY_SIZE> + SystemCapabilities
+{
+}
 
 /// Implement WriteSystemDispatcher for Option<T: WriteSystemDispatcher<MEMORY_SIZE>>
 impl<const MEMORY_SIZE: usize, T: WriteSystemDispatcher<MEMORY_SIZE>>
@@ -26,30 +107,67 @@ impl<const MEMORY_SIZE: usize, T: WriteSystemDispatcher<MEMORY_SIZE>>
 {
 }
 
-/// Marker trait for write channel system dispatchers.
+/// Marker trait for read system dispatchers.
+pub trait ReadSystemDispatcher<const MEMORY_SIZE: usize>:
+	MachineSystem<MEMORY_SIZE> + SystemCapabilities
+{
+}
+
+/// Implement ReadSystemDispatcher for Option<T: ReadSystemDispatcher<MEMORY_SIZE>>
+impl<const MEMORY_SIZE: usize, T: ReadSystemDispatcher<MEMORY_SIZE>>
+	ReadSystemDispatcher<MEMORY_SIZE> for Option<T>
+{
+}
+
+/// Marker trait for open channel system dispatchers.
 pub trait OpenChannelSystemDispatcher<const MEMORY_SIZE: usize>:
-	MachineSystem<MEMORY_SIZE>
+	MachineSystem<MEMORY_SIZE> + SystemCapabilities
 {
 }
 
-/// Implement WriteChannelSystemDispatcher for Option<T: WriteChannelSystemDispatcher<MEMORY_SIZE>>
+/// Implement OpenChannelSystemDispatcher for Option<T: OpenChannelSystemDispatcher<MEMORY_SIZE>>
 impl<const MEMORY_SIZE: usize, T: OpenChannelSystemDispatcher<MEMORY_SIZE>>
 	OpenChannelSystemDispatcher<MEMORY_SIZE> for Option<T>
 {
 }
 
-/// Marker trait for read channel system dispatchers.
+/// Marker trait for check channel system dispatchers, which serve the check and wait channel
+/// ECALLs.
 pub trait CheckChannelSystemDispatcher<const MEMORY_SIZE: usize>:
-	MachineSystem<MEMORY_SIZE>
+	MachineSystem<MEMORY_SIZE> + SystemCapabilities
 {
 }
 
-/// Implement ReadChannelSystemDispatcher for Option<T: ReadChannelSystemDispatcher<MEMORY_SIZE>>
+/// Implement CheckChannelSystemDispatcher for Option<T: CheckChannelSystemDispatcher<MEMORY_SIZE>>
 impl<const MEMORY_SIZE: usize, T: CheckChannelSystemDispatcher<MEMORY_SIZE>>
 	CheckChannelSystemDispatcher<MEMORY_SIZE> for Option<T>
 {
 }
 
+/// Marker trait for random system dispatchers.
+pub trait RandomSystemDispatcher<const MEMORY_SIZE: usize>:
+	MachineSystem<MEMORY_SIZE> + SystemCapabilities
+{
+}
+
+/// Implement RandomSystemDispatcher for Option<T: RandomSystemDispatcher<MEMORY_SIZE>>
+impl<const MEMORY_SIZE: usize, T: RandomSystemDispatcher<MEMORY_SIZE>>
+	RandomSystemDispatcher<MEMORY_SIZE> for Option<T>
+{
+}
+
+/// Marker trait for time system dispatchers.
+pub trait TimeSystemDispatcher<const MEMORY_SIZE: usize>:
+	MachineSystem<MEMORY_SIZE> + SystemCapabilities
+{
+}
+
+/// Implement TimeSystemDispatcher for Option<T: TimeSystemDispatcher<MEMORY_SIZE>>
+impl<const MEMORY_SIZE: usize, T: TimeSystemDispatcher<MEMORY_SIZE>>
+	TimeSystemDispatcher<MEMORY_SIZE> for Option<T>
+{
+}
+
 pub struct NoopDispatcher<const MEMORY_SIZE: usize> {}
 
 impl<const MEMORY_SIZE: usize> MachineSystem<MEMORY_SIZE> for NoopDispatcher<MEMORY_SIZE> {
@@ -61,8 +179,15 @@ impl<const MEMORY_SIZE: usize> MachineSystem<MEMORY_SIZE> for NoopDispatcher<MEM
 	}
 }
 
+impl<const MEMORY_SIZE: usize> SystemCapabilities for NoopDispatcher<MEMORY_SIZE> {
+	fn is_supported(&self) -> bool {
+		false
+	}
+}
+
 impl<const MEMORY_SIZE: usize> ExitSystemDispatcher<MEMORY_SIZE> for NoopDispatcher<MEMORY_SIZE> {}
 impl<const MEMORY_SIZE: usize> WriteSystemDispatcher<MEMORY_SIZE> for NoopDispatcher<MEMORY_SIZE> {}
+impl<const MEMORY_SIZE: usize> ReadSystemDispatcher<MEMORY_SIZE> for NoopDispatcher<MEMORY_SIZE> {}
 impl<const MEMORY_SIZE: usize> OpenChannelSystemDispatcher<MEMORY_SIZE>
 	for NoopDispatcher<MEMORY_SIZE>
 {
@@ -71,36 +196,118 @@ impl<const MEMORY_SIZE: usize> CheckChannelSystemDispatcher<MEMORY_SIZE>
 	for NoopDispatcher<MEMORY_SIZE>
 {
 }
+impl<const MEMORY_SIZE: usize> RandomSystemDispatcher<MEMORY_SIZE> for NoopDispatcher<MEMORY_SIZE> {}
+impl<const MEMORY_SIZE: usize> TimeSystemDispatcher<MEMORY_SIZE> for NoopDispatcher<MEMORY_SIZE> {}
 
 /// The [EcallDispatcher] plugin handles ecall interrupts ticking and inner machine then delegating to the appropriate dispatcher.
 ///
 /// The dispatchers are the plugins that will be ticked when the appropriate ecall is encountered.
+/// ECALLs with a handler in the table are served by it first, and ECALLs which neither the table
+/// nor a supported dispatcher serve return [EcallStatus::NotImplemented] to the program, apart
+/// from exit and abort, which fault the machine since the program can't be resumed after them.
 pub struct EcallDispatcher<
 	const MEMORY_SIZE: usize,
 	ExitDispatcher: ExitSystemDispatcher<MEMORY_SIZE>,
 	WriteDispatcher: WriteSystemDispatcher<MEMORY_SIZE>,
+	ReadDispatcher: ReadSystemDispatcher<MEMORY_SIZE>,
 	OpenChannelDispatcher: OpenChannelSystemDispatcher<MEMORY_SIZE>,
 	CheckChannelDispatcher: CheckChannelSystemDispatcher<MEMORY_SIZE>,
+	RandomDispatcher: RandomSystemDispatcher<MEMORY_SIZE>,
+	TimeDispatcher: TimeSystemDispatcher<MEMORY_SIZE>,
+	Table: EcallTable<MEMORY_SIZE> = (),
 > {
 	pub exit_dispatcher: ExitDispatcher,
 	pub write_dispatcher: WriteDispatcher,
+	pub read_dispatcher: ReadDispatcher,
 	pub open_channel_dispatcher: OpenChannelDispatcher,
 	pub check_channel_dispatcher: CheckChannelDispatcher,
+	pub random_dispatcher: RandomDispatcher,
+	pub time_dispatcher: TimeDispatcher,
+	pub table: Table,
+}
+
+impl<
+		const MEMORY_SIZE: usize,
+		ExitDispatcher: ExitSystemDispatcher<MEMORY_SIZE>,
+		WriteDispatcher: WriteSystemDispatcher<MEMORY_SIZE>,
+		ReadDispatcher: ReadSystemDispatcher<MEMORY_SIZE>,
+		OpenChannelDispatcher: OpenChannelSystemDispatcher<MEMORY_SIZE>,
+		CheckChannelDispatcher: CheckChannelSystemDispatcher<MEMORY_SIZE>,
+		RandomDispatcher: RandomSystemDispatcher<MEMORY_SIZE>,
+		TimeDispatcher: TimeSystemDispatcher<MEMORY_SIZE>,
+		Table: EcallTable<MEMORY_SIZE>,
+	>
+	EcallDispatcher<
+		MEMORY_SIZE,
+		ExitDispatcher,
+		WriteDispatcher,
+		ReadDispatcher,
+		OpenChannelDispatcher,
+		CheckChannelDispatcher,
+		RandomDispatcher,
+		TimeDispatcher,
+		Table,
+	>
+{
+	/// Whether the table or a dispatcher serves the ECALL number.
+	pub fn supports_ecall_number(&self, ecall: u32) -> bool {
+		self.table.handles(ecall)
+			|| Ecall::try_from_u32(ecall).is_ok_and(|ecall| self.supports_ecall(ecall))
+	}
+
+	/// Whether a dispatcher serving the ECALL is configured.
+	pub fn supports_ecall(&self, ecall: Ecall) -> bool {
+		match ecall {
+			Ecall::Exit | Ecall::Abort | Ecall::ReturnData => self.exit_dispatcher.is_supported(),
+			Ecall::Read => self.read_dispatcher.is_supported(),
+			Ecall::Write => self.write_dispatcher.is_supported(),
+			Ecall::OpenChannel => self.open_channel_dispatcher.is_supported(),
+			Ecall::CheckChannel | Ecall::WaitChannel => {
+				self.check_channel_dispatcher.is_supported()
+			}
+			Ecall::Random => self.random_dispatcher.is_supported(),
+			Ecall::Time => self.time_dispatcher.is_supported(),
+		}
+	}
+
+	/// Whether the write dispatcher serves the write system ID.
+	pub fn supports_write_system(&self, system_id: u32) -> bool {
+		self.write_dispatcher.supports_system_id(system_id)
+	}
+
+	/// Whether the read dispatcher serves the read system ID.
+	pub fn supports_read_system(&self, system_id: u32) -> bool {
+		self.read_dispatcher.supports_system_id(system_id)
+	}
+
+	/// Whether both channel dispatchers serve the channel system ID.
+	pub fn supports_channel_system(&self, system_id: u32) -> bool {
+		self.open_channel_dispatcher.supports_system_id(system_id)
+			&& self.check_channel_dispatcher.supports_system_id(system_id)
+	}
 }
 
 impl<
 		const MEMORY_SIZE: usize,
 		ExitDispatcher: ExitSystemDispatcher<MEMORY_SIZE>,
 		WriteDispatcher: WriteSystemDispatcher<MEMORY_SIZE>,
+		ReadDispatcher: ReadSystemDispatcher<MEMORY_SIZE>,
 		OpenChannelDispatcher: OpenChannelSystemDispatcher<MEMORY_SIZE>,
 		CheckChannelDispatcher: CheckChannelSystemDispatcher<MEMORY_SIZE>,
+		RandomDispatcher: RandomSystemDispatcher<MEMORY_SIZE>,
+		TimeDispatcher: TimeSystemDispatcher<MEMORY_SIZE>,
+		Table: EcallTable<MEMORY_SIZE>,
 	> MachineSystem<MEMORY_SIZE>
 	for EcallDispatcher<
 		MEMORY_SIZE,
 		ExitDispatcher,
 		WriteDispatcher,
+		ReadDispatcher,
 		OpenChannelDispatcher,
 		CheckChannelDispatcher,
+		RandomDispatcher,
+		TimeDispatcher,
+		Table,
 	>
 {
 	/// Ticks the ecall dispatcher and delegates to the appropriate dispatcher based on the ecall word.
@@ -113,15 +320,30 @@ impl<
 		&mut self,
 		machine: &mut Machine<MEMORY_SIZE>,
 	) -> Result<ControlFlow<()>, MachineError> {
-		let ecall_word = machine.csrs().registers().get(17);
-		let ecall = Ecall::try_from_u32(ecall_word)
-			.map_err(|_e| MachineError::SystemError("invalid ecall word"))?;
+		let ecall_word = ecall_number(machine);
+		if let Some(result) = self.table.dispatch(ecall_word, machine) {
+			return result;
+		}
 
-		match ecall {
-			Ecall::Exit => self.exit_dispatcher.tick(machine),
-			Ecall::Write => self.write_dispatcher.tick(machine),
-			Ecall::OpenChannel => self.open_channel_dispatcher.tick(machine),
-			Ecall::CheckChannel => self.check_channel_dispatcher.tick(machine),
+		match Ecall::try_from_u32(ecall_word) {
+			Ok(ecall) if self.supports_ecall(ecall.clone()) => match ecall {
+				Ecall::Exit | Ecall::Abort | Ecall::ReturnData => {
+					self.exit_dispatcher.tick(machine)
+				}
+				Ecall::Read => self.read_dispatcher.tick(machine),
+				Ecall::Write => self.write_dispatcher.tick(machine),
+				Ecall::OpenChannel => self.open_channel_dispatcher.tick(machine),
+				Ecall::CheckChannel | Ecall::WaitChannel => {
+					self.check_channel_dispatcher.tick(machine)
+				}
+				Ecall::Random => self.random_dispatcher.tick(machine),
+				Ecall::Time => self.time_dispatcher.tick(machine),
+			},
+			// Programs don't expect exit and abort to return, so they can't be resumed.
+			Ok(Ecall::Exit | Ecall::Abort) => {
+				Err(MachineError::SystemError("nothing serves the exit and abort ECALLs"))
+			}
+			_ => resume_with_status(machine, EcallStatus::NotImplemented),
 		}
 	}
 }
@@ -130,15 +352,23 @@ impl<
 		const MEMORY_SIZE: usize,
 		ExitDispatcher: ExitSystemDispatcher<MEMORY_SIZE>,
 		WriteDispatcher: WriteSystemDispatcher<MEMORY_SIZE>,
+		ReadDispatcher: ReadSystemDispatcher<MEMORY_SIZE>,
 		OpenChannelDispatcher: OpenChannelSystemDispatcher<MEMORY_SIZE>,
 		CheckChannelDispatcher: CheckChannelSystemDispatcher<MEMORY_SIZE>,
+		RandomDispatcher: RandomSystemDispatcher<MEMORY_SIZE>,
+		TimeDispatcher: TimeSystemDispatcher<MEMORY_SIZE>,
+		Table: EcallTable<MEMORY_SIZE>,
 	> EcallDispatcherOps<MEMORY_SIZE>
 	for EcallDispatcher<
 		MEMORY_SIZE,
 		ExitDispatcher,
 		WriteDispatcher,
+		ReadDispatcher,
 		OpenChannelDispatcher,
 		CheckChannelDispatcher,
+		RandomDispatcher,
+		TimeDispatcher,
+		Table,
 	>
 {
 	#[inline(always)]
@@ -148,3 +378,75 @@ impl<
 		Ok(())
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use table::EcallEntry;
+
+	type Noop = NoopDispatcher<1024>;
+
+	fn dispatcher<Table: EcallTable<1024>>(
+		table: Table,
+	) -> EcallDispatcher<1024, Noop, Noop, Noop, Noop, Noop, Noop, Noop, Table> {
+		EcallDispatcher {
+			exit_dispatcher: NoopDispatcher {},
+			write_dispatcher: NoopDispatcher {},
+			read_dispatcher: NoopDispatcher {},
+			open_channel_dispatcher: NoopDispatcher {},
+			check_channel_dispatcher: NoopDispatcher {},
+			random_dispatcher: NoopDispatcher {},
+			time_dispatcher: NoopDispatcher {},
+			table,
+		}
+	}
+
+	fn trapped_on(ecall: u32) -> Machine<1024> {
+		let mut machine = Machine::new();
+		machine.csrs_mut().registers_mut().set(ECALL_NUMBER_REGISTER, ecall);
+		machine.csrs_mut().registers_mut().program_counter_set(0x100);
+		machine
+	}
+
+	/// Stops the machine, so it's plain when it served an ECALL.
+	struct Stop;
+
+	impl MachineSystem<1024> for Stop {
+		fn tick(&mut self, _machine: &mut Machine<1024>) -> Result<ControlFlow<()>, MachineError> {
+			Ok(ControlFlow::Break(()))
+		}
+	}
+
+	#[test]
+	fn test_unhandled_ecall_is_not_implemented() -> Result<(), MachineError> {
+		for ecall in [Ecall::Write.to_u32(), Ecall::ReturnData.to_u32(), 0x999] {
+			let mut machine = trapped_on(ecall);
+			assert_eq!(dispatcher(()).tick(&mut machine)?, ControlFlow::Continue(()));
+			assert_eq!(
+				machine.registers().get(Register::A0.index() as usize),
+				EcallStatus::NotImplemented.to_u32()
+			);
+			assert_eq!(machine.registers().program_counter(), 0x104);
+		}
+		Ok(())
+	}
+
+	#[test]
+	fn test_unhandled_exit_and_abort_fault() {
+		for ecall in [Ecall::Exit, Ecall::Abort] {
+			let mut machine = trapped_on(ecall.to_u32());
+			assert!(matches!(dispatcher(()).tick(&mut machine), Err(MachineError::SystemError(_))));
+		}
+	}
+
+	#[test]
+	fn test_table_is_consulted_first() -> Result<(), MachineError> {
+		let mut dispatcher = dispatcher((EcallEntry::new(Ecall::Exit.to_u32(), Stop), ()));
+		assert!(dispatcher.supports_ecall_number(Ecall::Exit.to_u32()));
+		assert!(!dispatcher.supports_ecall_number(Ecall::Abort.to_u32()));
+
+		let mut machine = trapped_on(Ecall::Exit.to_u32());
+		assert_eq!(dispatcher.tick(&mut machine)?, ControlFlow::Break(()));
+		Ok(())
+	}
+}
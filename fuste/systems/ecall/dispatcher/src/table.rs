@@ -0,0 +1,187 @@
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use core::ops::ControlFlow;
+use fuste_riscv_core::machine::{Machine, MachineError, MachineSystem};
+
+/// A table of handlers for arbitrary ECALL numbers, consulted by the [crate::EcallDispatcher]
+/// before its fixed slots.
+///
+/// Tables are composed statically as a list of [EcallEntry]s ending in `()`, e.g.
+/// `(EcallEntry<A>, (EcallEntry<B>, ()))`, which the compiler can inline into a chain of
+/// comparisons, or held dynamically in a [DynamicEcallTable].
+pub trait EcallTable<const MEMORY_SIZE: usize> {
+	/// Ticks the handler for the ECALL, or returns [None] if the table has none.
+	fn dispatch(
+		&mut self,
+		ecall: u32,
+		machine: &mut Machine<MEMORY_SIZE>,
+	) -> Option<Result<ControlFlow<()>, MachineError>>;
+
+	/// Whether the table has a handler for the ECALL.
+	fn handles(&self, ecall: u32) -> bool;
+}
+
+/// The empty table.
+impl<const MEMORY_SIZE: usize> EcallTable<MEMORY_SIZE> for () {
+	#[inline(always)]
+	fn dispatch(
+		&mut self,
+		_ecall: u32,
+		_machine: &mut Machine<MEMORY_SIZE>,
+	) -> Option<Result<ControlFlow<()>, MachineError>> {
+		None
+	}
+
+	fn handles(&self, _ecall: u32) -> bool {
+		false
+	}
+}
+
+/// A handler registered for an ECALL number.
+pub struct EcallEntry<Handler> {
+	pub ecall: u32,
+	pub handler: Handler,
+}
+
+impl<Handler> EcallEntry<Handler> {
+	pub fn new(ecall: u32, handler: Handler) -> Self {
+		Self { ecall, handler }
+	}
+}
+
+/// An entry in front of the rest of the table, so entries added later take precedence.
+impl<
+		const MEMORY_SIZE: usize,
+		Handler: MachineSystem<MEMORY_SIZE>,
+		Tail: EcallTable<MEMORY_SIZE>,
+	> EcallTable<MEMORY_SIZE> for (EcallEntry<Handler>, Tail)
+{
+	#[inline(always)]
+	fn dispatch(
+		&mut self,
+		ecall: u32,
+		machine: &mut Machine<MEMORY_SIZE>,
+	) -> Option<Result<ControlFlow<()>, MachineError>> {
+		if ecall == self.0.ecall {
+			Some(self.0.handler.tick(machine))
+		} else {
+			self.1.dispatch(ecall, machine)
+		}
+	}
+
+	fn handles(&self, ecall: u32) -> bool {
+		ecall == self.0.ecall || self.1.handles(ecall)
+	}
+}
+
+/// A table of boxed handlers which can be changed at runtime, e.g. by hosts registering handlers
+/// through an FFI.
+pub struct DynamicEcallTable<const MEMORY_SIZE: usize> {
+	handlers: BTreeMap<u32, Box<dyn MachineSystem<MEMORY_SIZE>>>,
+}
+
+impl<const MEMORY_SIZE: usize> DynamicEcallTable<MEMORY_SIZE> {
+	pub fn new() -> Self {
+		Self { handlers: BTreeMap::new() }
+	}
+
+	/// Registers the handler for the ECALL, replacing any registered before.
+	pub fn register(&mut self, ecall: u32, handler: impl MachineSystem<MEMORY_SIZE> + 'static) {
+		self.handlers.insert(ecall, Box::new(handler));
+	}
+
+	/// Removes the handler for the ECALL, returning whether there was one.
+	pub fn unregister(&mut self, ecall: u32) -> bool {
+		self.handlers.remove(&ecall).is_some()
+	}
+}
+
+impl<const MEMORY_SIZE: usize> Default for DynamicEcallTable<MEMORY_SIZE> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<const MEMORY_SIZE: usize> EcallTable<MEMORY_SIZE> for DynamicEcallTable<MEMORY_SIZE> {
+	fn dispatch(
+		&mut self,
+		ecall: u32,
+		machine: &mut Machine<MEMORY_SIZE>,
+	) -> Option<Result<ControlFlow<()>, MachineError>> {
+		self.handlers.get_mut(&ecall).map(|handler| handler.tick(machine))
+	}
+
+	fn handles(&self, ecall: u32) -> bool {
+		self.handlers.contains_key(&ecall)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use fuste_abi::Register;
+
+	/// Writes its tag to a0, so it's plain which handler served an ECALL.
+	struct Tag(u32);
+
+	impl MachineSystem<1024> for Tag {
+		fn tick(&mut self, machine: &mut Machine<1024>) -> Result<ControlFlow<()>, MachineError> {
+			machine.registers_mut().set(Register::A0.index(), self.0);
+			Ok(ControlFlow::Continue(()))
+		}
+	}
+
+	/// The tag of the handler which served the ECALL, if any did.
+	fn served_by(
+		table: &mut impl EcallTable<1024>,
+		ecall: u32,
+	) -> Result<Option<u32>, MachineError> {
+		let mut machine = Machine::new();
+		match table.dispatch(ecall, &mut machine) {
+			Some(result) => {
+				assert_eq!(result?, ControlFlow::Continue(()));
+				Ok(Some(machine.registers().get(Register::A0.index() as usize)))
+			}
+			None => Ok(None),
+		}
+	}
+
+	#[test]
+	fn test_static_table_precedence() -> Result<(), MachineError> {
+		let mut table = (
+			EcallEntry::new(7, Tag(1)),
+			(EcallEntry::new(7, Tag(2)), (EcallEntry::new(8, Tag(3)), ())),
+		);
+
+		assert_eq!(served_by(&mut table, 7)?, Some(1));
+		assert_eq!(served_by(&mut table, 8)?, Some(3));
+		assert_eq!(served_by(&mut table, 9)?, None);
+
+		assert!(table.handles(7));
+		assert!(table.handles(8));
+		assert!(!table.handles(9));
+		assert!(!EcallTable::<1024>::handles(&(), 7));
+		Ok(())
+	}
+
+	#[test]
+	fn test_dynamic_table_register_and_unregister() -> Result<(), MachineError> {
+		let mut table = DynamicEcallTable::<1024>::new();
+		assert!(!table.handles(7));
+		assert_eq!(served_by(&mut table, 7)?, None);
+
+		table.register(7, Tag(1));
+		assert!(table.handles(7));
+		assert_eq!(served_by(&mut table, 7)?, Some(1));
+
+		// Registering again replaces the handler.
+		table.register(7, Tag(2));
+		assert_eq!(served_by(&mut table, 7)?, Some(2));
+
+		assert!(table.unregister(7));
+		assert!(!table.handles(7));
+		assert_eq!(served_by(&mut table, 7)?, None);
+		assert!(!table.unregister(7));
+		Ok(())
+	}
+}
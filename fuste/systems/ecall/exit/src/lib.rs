@@ -1,18 +1,53 @@
 #![no_std]
 
+extern crate alloc;
+
+use alloc::vec::Vec;
 use core::ops::ControlFlow;
-use fuste_ecall_dispatcher::ExitSystemDispatcher;
-use fuste_exit::ExitStatus;
+use fuste_abi::abort::AbortArgs;
+use fuste_abi::exit::ExitArgs;
+use fuste_abi::return_data::{ReturnDataArgs, ReturnDataReturns};
+use fuste_ecall::Ecall;
+use fuste_ecall_dispatcher::{
+	ecall_args, ecall_number, return_from_ecall, ExitSystemDispatcher, SystemCapabilities,
+};
+use fuste_exit::{ExitStatus, ReturnDataStatusCode};
 use fuste_riscv_core::machine::{Machine, MachineError, MachineSystem};
 
+/// The most return data a program can hand back, 64 KiB.
+pub const MAX_RETURN_DATA: u32 = 64 * 1024;
+
 pub struct ExitSystem<const MEMORY_SIZE: usize> {
 	pub syscall_status: ExitStatus,
+	/// The message the program aborted with, e.g. where it panicked.
+	pub abort_message: Option<Vec<u8>>,
+	/// The data the program last handed back as its result.
+	pub return_data: Option<Vec<u8>>,
 }
 
 impl<const MEMORY_SIZE: usize> ExitSystem<MEMORY_SIZE> {
 	pub fn new() -> Self {
 		// Initially this is successful.
-		Self { syscall_status: ExitStatus::Success }
+		Self { syscall_status: ExitStatus::Success, abort_message: None, return_data: None }
+	}
+
+	/// Keeps the data the program hands back, or returns [ReturnDataStatusCode::TooLarge].
+	fn keep_return_data(
+		&mut self,
+		machine: &mut Machine<MEMORY_SIZE>,
+	) -> Result<ControlFlow<()>, MachineError> {
+		let ReturnDataArgs { data_address, data_length } = ecall_args(machine);
+		let status = if data_length > MAX_RETURN_DATA {
+			ReturnDataStatusCode::TooLarge
+		} else {
+			let data = machine
+				.memory()
+				.read_bytes(data_address, data_length)
+				.map_err(MachineError::MemoryError)?;
+			self.return_data = Some(data.to_vec());
+			ReturnDataStatusCode::Success
+		};
+		return_from_ecall(machine, &ReturnDataReturns { status: status.to_i32() })
 	}
 }
 
@@ -21,17 +56,52 @@ impl<const MEMORY_SIZE: usize> MachineSystem<MEMORY_SIZE> for ExitSystem<MEMORY_
 		&mut self,
 		machine: &mut Machine<MEMORY_SIZE>,
 	) -> Result<ControlFlow<()>, MachineError> {
+		let ecall = ecall_number(machine);
+		if ecall == Ecall::ReturnData.to_u32() {
+			return self.keep_return_data(machine);
+		}
+
+		if ecall == Ecall::Abort.to_u32() {
+			let AbortArgs { message_address, message_length } = ecall_args(machine);
+			let message = machine
+				.memory()
+				.read_bytes(message_address, message_length)
+				.map_err(MachineError::MemoryError)?;
+			self.abort_message = Some(message.to_vec());
+			self.syscall_status = ExitStatus::Error;
+			return Ok(ControlFlow::Break(()));
+		}
+
 		// Assume this has correctly been called by whatever higher order system.
 		// We just need to store the status and break.
-		let syscall_status_address = machine.csrs().registers().get(10);
-		let syscall_status = machine
-			.memory()
-			.read_word(syscall_status_address)
-			.map_err(MachineError::MemoryError)?;
-		self.syscall_status = ExitStatus::try_from_u32(syscall_status)
-			.map_err(|_e| MachineError::SystemError("invalid exit status"))?;
+		let ExitArgs { status } = ecall_args(machine);
+		self.syscall_status = ExitStatus::from_u32(status);
 		Ok(ControlFlow::Break(()))
 	}
 }
 
+impl<const MEMORY_SIZE: usize> SystemCapabilities for ExitSystem<MEMORY_SIZE> {}
+
 impl<const MEMORY_SIZE: usize> ExitSystemDispatcher<MEMORY_SIZE> for ExitSystem<MEMORY_SIZE> {}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use fuste_abi::{Register, ECALL_NUMBER_REGISTER};
+
+	#[test]
+	fn test_exit_takes_its_status_by_value() -> Result<(), MachineError> {
+		let mut machine = Machine::<1024>::new();
+		// Put a different status where a0 would point, so dereferencing it would be caught.
+		machine.memory_mut().write_word(0x100, 7).map_err(MachineError::MemoryError)?;
+		let registers = machine.csrs_mut().registers_mut();
+		registers.set(ECALL_NUMBER_REGISTER, Ecall::Exit.to_u32());
+		registers.set(Register::A0.index(), 0x100);
+
+		let mut exit = ExitSystem::new();
+		assert_eq!(exit.tick(&mut machine)?, ControlFlow::Break(()));
+		assert_eq!(exit.syscall_status, ExitStatus::Code(0x100));
+
+		Ok(())
+	}
+}
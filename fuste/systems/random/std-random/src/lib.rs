@@ -0,0 +1,231 @@
+use chacha20::{
+	cipher::{KeyIvInit, StreamCipher},
+	ChaCha20,
+};
+use core::fmt;
+use core::ops::ControlFlow;
+use fuste_abi::random::{RandomArgs, RandomReturns};
+use fuste_ecall_dispatcher::{
+	ecall_args, return_from_ecall, RandomSystemDispatcher, SystemCapabilities,
+};
+use fuste_random::RandomStatusCode;
+use fuste_riscv_core::machine::{Machine, MachineError, MachineSystem};
+
+/// A seed from the number's little-endian bytes, padded with zeros, e.g. for a seed given on the
+/// command line.
+pub fn seed_from_u64(seed: u64) -> [u8; 32] {
+	let mut bytes = [0; 32];
+	bytes[..8].copy_from_slice(&seed.to_le_bytes());
+	bytes
+}
+
+/// Fills the buffer from the start of the ChaCha20 keystream keyed with the seed and the nonce.
+///
+/// Seeded [RandomSource]s use a zero nonce, so other nonces give a host bytes apart from the ones
+/// the program draws, e.g. for its auxiliary vector. The keystream runs out after 256 GiB, which
+/// the buffer must be smaller than.
+pub fn fill_from_seed(seed: [u8; 32], nonce: [u8; 12], buffer: &mut [u8]) {
+	buffer.fill(0);
+	ChaCha20::new(&seed.into(), &nonce.into()).apply_keystream(buffer);
+}
+
+/// Where a [StdRandomSystem] draws the program's random bytes from.
+pub enum RandomSource {
+	/// The keystream of ChaCha20 keyed with a seed, so every run with the same seed draws the same
+	/// bytes
+	Seeded(Box<ChaCha20>),
+	/// The host's randomness, for development
+	Os,
+}
+
+impl RandomSource {
+	/// The keystream of ChaCha20 keyed with the seed and a zero nonce.
+	pub fn seeded(seed: [u8; 32]) -> Self {
+		RandomSource::Seeded(Box::new(ChaCha20::new(&seed.into(), &[0; 12].into())))
+	}
+
+	/// Fills the buffer, returning the host's status if it fails.
+	///
+	/// A seeded stream fails once it runs out, after 256 GiB.
+	fn fill_bytes(&mut self, buffer: &mut [u8]) -> Result<(), i32> {
+		match self {
+			RandomSource::Seeded(cipher) => {
+				buffer.fill(0);
+				cipher.try_apply_keystream(buffer).map_err(|_| -1)
+			}
+			RandomSource::Os => {
+				getrandom::getrandom(buffer).map_err(|error| error.raw_os_error().unwrap_or(-1))
+			}
+		}
+	}
+}
+
+impl fmt::Debug for RandomSource {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			// The keystream's state would give the rest of it away.
+			RandomSource::Seeded(_) => write!(f, "Seeded"),
+			RandomSource::Os => write!(f, "Os"),
+		}
+	}
+}
+
+/// Serves the random ECALL with the bytes from its [RandomSource].
+#[derive(Debug)]
+pub struct StdRandomSystem<const MEMORY_SIZE: usize> {
+	source: RandomSource,
+	/// The number of random bytes the program has drawn.
+	pub bytes_drawn: usize,
+}
+
+impl<const MEMORY_SIZE: usize> StdRandomSystem<MEMORY_SIZE> {
+	pub fn new(source: RandomSource) -> Self {
+		Self { source, bytes_drawn: 0 }
+	}
+
+	/// Draws from a ChaCha20 stream seeded with the seed.
+	pub fn seeded(seed: [u8; 32]) -> Self {
+		Self::new(RandomSource::seeded(seed))
+	}
+
+	/// Draws from a ChaCha20 stream seeded with [seed_from_u64].
+	pub fn seeded_from_u64(seed: u64) -> Self {
+		Self::seeded(seed_from_u64(seed))
+	}
+
+	/// Draws from the host's randomness.
+	pub fn os() -> Self {
+		Self::new(RandomSource::Os)
+	}
+}
+
+impl<const MEMORY_SIZE: usize> Default for StdRandomSystem<MEMORY_SIZE> {
+	fn default() -> Self {
+		Self::os()
+	}
+}
+
+impl<const MEMORY_SIZE: usize> MachineSystem<MEMORY_SIZE> for StdRandomSystem<MEMORY_SIZE> {
+	fn tick(
+		&mut self,
+		machine: &mut Machine<MEMORY_SIZE>,
+	) -> Result<ControlFlow<()>, MachineError> {
+		let args: RandomArgs = ecall_args(machine);
+		let buffer = machine
+			.memory_mut()
+			.read_bytes_mut(args.buffer_address, args.buffer_length)
+			.map_err(MachineError::MemoryError)?;
+
+		let returns = match self.source.fill_bytes(buffer) {
+			Ok(()) => {
+				self.bytes_drawn += buffer.len();
+				RandomReturns { status: RandomStatusCode::Success.to_i32(), system_status: 0 }
+			}
+			Err(system_status) => {
+				RandomReturns { status: RandomStatusCode::SystemError.to_i32(), system_status }
+			}
+		};
+		return_from_ecall(machine, &returns)
+	}
+}
+
+impl<const MEMORY_SIZE: usize> SystemCapabilities for StdRandomSystem<MEMORY_SIZE> {}
+
+impl<const MEMORY_SIZE: usize> RandomSystemDispatcher<MEMORY_SIZE>
+	for StdRandomSystem<MEMORY_SIZE>
+{
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use fuste_abi::{EcallRegisters, RegisterLayout};
+	use std::error::Error;
+
+	const BUFFER_ADDRESS: u32 = 0x100;
+
+	/// Makes the random ECALL for the number of bytes, returning its results and the bytes drawn.
+	fn draw(
+		system: &mut StdRandomSystem<1024>,
+		buffer_length: u32,
+	) -> Result<(RandomReturns, Vec<u8>), Box<dyn Error>> {
+		let mut machine = Machine::<1024>::new();
+		let mut registers = EcallRegisters::new();
+		RandomArgs { buffer_address: BUFFER_ADDRESS, buffer_length }
+			.write_registers(&mut registers);
+		for (register, value) in registers.iter() {
+			machine.csrs_mut().registers_mut().set(register.index(), value);
+		}
+
+		assert_eq!(system.tick(&mut machine)?, ControlFlow::Continue(()));
+		let returns: RandomReturns = ecall_args(&machine);
+		let bytes = machine.memory().read_bytes(BUFFER_ADDRESS, buffer_length)?;
+		Ok((returns, bytes.to_vec()))
+	}
+
+	fn success() -> RandomReturns {
+		RandomReturns { status: RandomStatusCode::Success.to_i32(), system_status: 0 }
+	}
+
+	#[test]
+	fn test_seed_from_u64() {
+		let seed = seed_from_u64(0x0102_0304_0506_0708);
+		assert_eq!(seed[..8], [8, 7, 6, 5, 4, 3, 2, 1]);
+		assert_eq!(seed[8..], [0; 24]);
+	}
+
+	#[test]
+	fn test_fill_from_seed_is_chacha20() {
+		// The first keystream block of ChaCha20 with a zero key and nonce, from RFC 8439.
+		let mut bytes = [0xff; 16];
+		fill_from_seed([0; 32], [0; 12], &mut bytes);
+		assert_eq!(
+			bytes,
+			[
+				0x76, 0xb8, 0xe0, 0xad, 0xa0, 0xf1, 0x3d, 0x90, 0x40, 0x5d, 0x6a, 0xe5, 0x53, 0x86,
+				0xbd, 0x28
+			]
+		);
+	}
+
+	#[test]
+	fn test_streams_are_separated_by_seed_and_nonce() {
+		let fill = |seed: u64, nonce: [u8; 12]| {
+			let mut bytes = [0; 32];
+			fill_from_seed(seed_from_u64(seed), nonce, &mut bytes);
+			bytes
+		};
+		let other_nonce = [1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+
+		assert_eq!(fill(7, [0; 12]), fill(7, [0; 12]));
+		assert_ne!(fill(7, [0; 12]), fill(8, [0; 12]));
+		assert_ne!(fill(7, [0; 12]), fill(7, other_nonce));
+	}
+
+	#[test]
+	fn test_seeded_draws_are_deterministic() -> Result<(), Box<dyn Error>> {
+		let mut expected = [0; 48];
+		fill_from_seed(seed_from_u64(7), [0; 12], &mut expected);
+
+		// Every system with the seed draws the same bytes, which continue the stream across draws.
+		let mut system = StdRandomSystem::seeded_from_u64(7);
+		assert_eq!(draw(&mut system, 48)?, (success(), expected.to_vec()));
+
+		let mut system = StdRandomSystem::seeded_from_u64(7);
+		assert_eq!(draw(&mut system, 16)?, (success(), expected[..16].to_vec()));
+		assert_eq!(draw(&mut system, 32)?, (success(), expected[16..].to_vec()));
+		assert_eq!(system.bytes_drawn, 48);
+
+		let mut system = StdRandomSystem::seeded_from_u64(8);
+		assert_ne!(draw(&mut system, 48)?.1, expected);
+		Ok(())
+	}
+
+	#[test]
+	fn test_os_draws() -> Result<(), Box<dyn Error>> {
+		let mut system = StdRandomSystem::os();
+		assert_eq!(draw(&mut system, 32)?.0, success());
+		assert_eq!(system.bytes_drawn, 32);
+		Ok(())
+	}
+}
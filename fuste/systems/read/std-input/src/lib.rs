@@ -0,0 +1,201 @@
+use core::ops::ControlFlow;
+use fuste_abi::read::{ReadArgs, ReadReturns};
+use fuste_ecall_dispatcher::{
+	ecall_args, return_from_ecall, ReadSystemDispatcher, SystemCapabilities,
+};
+use fuste_read::ReadStatusCode;
+use fuste_riscv_core::machine::{Machine, MachineError, MachineSystem};
+use fuste_std_input::Stdin;
+use std::fs::File;
+use std::io::{self, Cursor, Read};
+use std::path::Path;
+
+/// Where a [StdInputSystem] reads the program's input from.
+#[derive(Debug)]
+pub enum InputSource {
+	/// The host's stdin
+	Stdin(io::Stdin),
+	File(File),
+	/// Bytes held in memory, e.g. test vectors
+	Bytes(Cursor<Vec<u8>>),
+}
+
+impl Read for InputSource {
+	fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+		match self {
+			InputSource::Stdin(stdin) => stdin.read(buffer),
+			InputSource::File(file) => file.read(buffer),
+			InputSource::Bytes(bytes) => bytes.read(buffer),
+		}
+	}
+}
+
+/// Serves reads from [Stdin] with the input from its [InputSource].
+#[derive(Debug)]
+pub struct StdInputSystem<const MEMORY_SIZE: usize> {
+	source: InputSource,
+	/// The number of bytes the program has read from stdin.
+	pub bytes_read: usize,
+}
+
+impl<const MEMORY_SIZE: usize> StdInputSystem<MEMORY_SIZE> {
+	pub fn new(source: InputSource) -> Self {
+		Self { source, bytes_read: 0 }
+	}
+
+	/// Serves the host's stdin.
+	pub fn stdin() -> Self {
+		Self::new(InputSource::Stdin(io::stdin()))
+	}
+
+	/// Serves the contents of the file.
+	pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+		Ok(Self::new(InputSource::File(File::open(path)?)))
+	}
+
+	/// Serves the bytes.
+	pub fn from_bytes(bytes: impl Into<Vec<u8>>) -> Self {
+		Self::new(InputSource::Bytes(Cursor::new(bytes.into())))
+	}
+}
+
+impl<const MEMORY_SIZE: usize> Default for StdInputSystem<MEMORY_SIZE> {
+	fn default() -> Self {
+		Self::stdin()
+	}
+}
+
+impl<const MEMORY_SIZE: usize> MachineSystem<MEMORY_SIZE> for StdInputSystem<MEMORY_SIZE> {
+	fn tick(
+		&mut self,
+		machine: &mut Machine<MEMORY_SIZE>,
+	) -> Result<ControlFlow<()>, MachineError> {
+		let args: ReadArgs = ecall_args(machine);
+		if args.system_id != Stdin::to_const_u32() {
+			let returns = ReadReturns {
+				status: ReadStatusCode::InvalidSystem.to_i32(),
+				system_status: 0,
+				size: 0,
+			};
+			return return_from_ecall(machine, &returns);
+		}
+
+		let read_buffer = machine
+			.memory_mut()
+			.read_bytes_mut(args.buffer_address, args.buffer_length)
+			.map_err(MachineError::MemoryError)?;
+
+		let returns = match self.source.read(read_buffer) {
+			Ok(size) => {
+				self.bytes_read += size;
+				ReadReturns {
+					status: ReadStatusCode::Success.to_i32(),
+					system_status: 0,
+					size: size as u32,
+				}
+			}
+			Err(error) => ReadReturns {
+				status: ReadStatusCode::SystemError.to_i32(),
+				system_status: error.raw_os_error().unwrap_or(-1),
+				size: 0,
+			},
+		};
+		return_from_ecall(machine, &returns)
+	}
+}
+
+impl<const MEMORY_SIZE: usize> SystemCapabilities for StdInputSystem<MEMORY_SIZE> {
+	fn supports_system_id(&self, system_id: u32) -> bool {
+		system_id == Stdin::to_const_u32()
+	}
+}
+
+impl<const MEMORY_SIZE: usize> ReadSystemDispatcher<MEMORY_SIZE> for StdInputSystem<MEMORY_SIZE> {}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use fuste_abi::{EcallRegisters, RegisterLayout};
+	use std::error::Error;
+
+	const BUFFER_ADDRESS: u32 = 0x100;
+
+	/// Makes the read ECALL into a buffer of the length, returning its results and the bytes read.
+	fn read(
+		system: &mut StdInputSystem<1024>,
+		system_id: u32,
+		buffer_length: u32,
+	) -> Result<(ReadReturns, Vec<u8>), Box<dyn Error>> {
+		let mut machine = Machine::<1024>::new();
+		let mut registers = EcallRegisters::new();
+		ReadArgs { system_id, buffer_address: BUFFER_ADDRESS, buffer_length }
+			.write_registers(&mut registers);
+		for (register, value) in registers.iter() {
+			machine.csrs_mut().registers_mut().set(register.index(), value);
+		}
+
+		assert_eq!(system.tick(&mut machine)?, ControlFlow::Continue(()));
+		let returns: ReadReturns = ecall_args(&machine);
+		let bytes = machine.memory().read_bytes(BUFFER_ADDRESS, returns.size)?;
+		Ok((returns, bytes.to_vec()))
+	}
+
+	fn success(size: u32) -> ReadReturns {
+		ReadReturns { status: ReadStatusCode::Success.to_i32(), system_status: 0, size }
+	}
+
+	#[test]
+	fn test_reads_bytes_until_the_end_of_input() -> Result<(), Box<dyn Error>> {
+		let mut system = StdInputSystem::from_bytes("hello");
+		let stdin = Stdin::to_const_u32();
+
+		assert_eq!(read(&mut system, stdin, 16)?, (success(5), b"hello".to_vec()));
+		// At the end of the input reads succeed with nothing read.
+		assert_eq!(read(&mut system, stdin, 16)?, (success(0), Vec::new()));
+		assert_eq!(system.bytes_read, 5);
+		Ok(())
+	}
+
+	#[test]
+	fn test_short_buffers_read_the_input_in_parts() -> Result<(), Box<dyn Error>> {
+		let mut system = StdInputSystem::from_bytes("hello");
+		let stdin = Stdin::to_const_u32();
+
+		assert_eq!(read(&mut system, stdin, 2)?, (success(2), b"he".to_vec()));
+		assert_eq!(read(&mut system, stdin, 2)?, (success(2), b"ll".to_vec()));
+		assert_eq!(read(&mut system, stdin, 2)?, (success(1), b"o".to_vec()));
+		assert_eq!(read(&mut system, stdin, 0)?, (success(0), Vec::new()));
+		assert_eq!(system.bytes_read, 5);
+		Ok(())
+	}
+
+	#[test]
+	fn test_reads_files() -> Result<(), Box<dyn Error>> {
+		let path = std::env::temp_dir().join(format!("fuste-std-input-{}", std::process::id()));
+		std::fs::write(&path, "from a file")?;
+		let mut system = StdInputSystem::from_file(&path)?;
+		let stdin = Stdin::to_const_u32();
+
+		let reads = [read(&mut system, stdin, 64)?, read(&mut system, stdin, 64)?];
+		drop(system);
+		std::fs::remove_file(&path)?;
+		assert_eq!(reads, [(success(11), b"from a file".to_vec()), (success(0), Vec::new())]);
+		Ok(())
+	}
+
+	#[test]
+	fn test_rejects_other_systems() -> Result<(), Box<dyn Error>> {
+		let mut system = StdInputSystem::from_bytes("hello");
+		let other = Stdin::to_const_u32() + 1;
+		assert!(!system.supports_system_id(other));
+
+		let invalid = ReadReturns {
+			status: ReadStatusCode::InvalidSystem.to_i32(),
+			system_status: 0,
+			size: 0,
+		};
+		assert_eq!(read(&mut system, other, 16)?, (invalid, Vec::new()));
+		assert_eq!(system.bytes_read, 0);
+		Ok(())
+	}
+}
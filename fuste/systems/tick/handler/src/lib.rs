@@ -8,6 +8,15 @@ pub struct TickHandler<const MEMORY_SIZE: usize, Inner: MachineSystem<MEMORY_SIZ
 	pub inner: Inner,
 	pub current_tick: u32,
 	pub max_ticks: u32,
+	/// Whether the run stopped because it reached the max ticks, rather than the inner machine
+	/// breaking.
+	pub exhausted: bool,
+}
+
+impl<const MEMORY_SIZE: usize, Inner: MachineSystem<MEMORY_SIZE>> TickHandler<MEMORY_SIZE, Inner> {
+	pub fn new(inner: Inner, max_ticks: u32) -> Self {
+		Self { inner, current_tick: 0, max_ticks, exhausted: false }
+	}
 }
 
 impl<const MEMORY_SIZE: usize, Inner: MachineSystem<MEMORY_SIZE>> MachineSystem<MEMORY_SIZE>
@@ -22,10 +31,38 @@ impl<const MEMORY_SIZE: usize, Inner: MachineSystem<MEMORY_SIZE>> MachineSystem<
 		let result = self.inner.tick(machine)?;
 
 		self.current_tick += 1;
-		if self.current_tick >= self.max_ticks {
-			return Ok(ControlFlow::Break(()));
+		if result.is_continue() && self.current_tick >= self.max_ticks {
+			self.exhausted = true;
+			Ok(ControlFlow::Break(()))
 		} else {
-			return Ok(result);
+			Ok(result)
 		}
 	}
 }
+
+/// The [TickCounter] plugin counts the ticks of the inner machine without limiting them.
+pub struct TickCounter<const MEMORY_SIZE: usize, Inner: MachineSystem<MEMORY_SIZE>> {
+	pub inner: Inner,
+	pub current_tick: u64,
+}
+
+impl<const MEMORY_SIZE: usize, Inner: MachineSystem<MEMORY_SIZE>> TickCounter<MEMORY_SIZE, Inner> {
+	pub fn new(inner: Inner) -> Self {
+		Self { inner, current_tick: 0 }
+	}
+}
+
+impl<const MEMORY_SIZE: usize, Inner: MachineSystem<MEMORY_SIZE>> MachineSystem<MEMORY_SIZE>
+	for TickCounter<MEMORY_SIZE, Inner>
+{
+	/// Ticks the inner machine, counting the tick.
+	#[inline(always)]
+	fn tick(
+		&mut self,
+		machine: &mut Machine<MEMORY_SIZE>,
+	) -> Result<ControlFlow<()>, MachineError> {
+		let result = self.inner.tick(machine)?;
+		self.current_tick += 1;
+		Ok(result)
+	}
+}
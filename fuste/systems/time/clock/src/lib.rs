@@ -0,0 +1,174 @@
+use core::ops::ControlFlow;
+use fuste_abi::time::TimeReturns;
+use fuste_ecall_dispatcher::{return_from_ecall, SystemCapabilities, TimeSystemDispatcher};
+use fuste_riscv_core::machine::{Machine, MachineError, MachineSystem};
+use fuste_time::{ClockKind, TimeStatusCode};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The clock a [ClockSystem] tells the program the time with, in nanoseconds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Clock {
+	/// The host's wall clock, since the Unix epoch
+	Wall,
+	/// A time which doesn't pass, e.g. the timestamp of the transaction the program runs in
+	Fixed(u64),
+	/// A time which starts at `start` and passes `nanos_per_instruction` for each instruction the
+	/// machine executes, so it reads the same on every run
+	Virtual { start: u64, nanos_per_instruction: u64 },
+}
+
+impl Clock {
+	pub fn kind(&self) -> ClockKind {
+		match self {
+			Clock::Wall => ClockKind::Wall,
+			Clock::Fixed(_) => ClockKind::Fixed,
+			Clock::Virtual { .. } => ClockKind::Virtual,
+		}
+	}
+
+	/// The time on the clock for the machine, or [None] if the wall clock is before the epoch or
+	/// too far past it.
+	pub fn nanos<const MEMORY_SIZE: usize>(&self, machine: &Machine<MEMORY_SIZE>) -> Option<u64> {
+		match self {
+			Clock::Wall => SystemTime::now()
+				.duration_since(UNIX_EPOCH)
+				.ok()
+				.and_then(|since_epoch| u64::try_from(since_epoch.as_nanos()).ok()),
+			Clock::Fixed(nanos) => Some(*nanos),
+			Clock::Virtual { start, nanos_per_instruction } => Some(start.saturating_add(
+				machine.instructions_retired().saturating_mul(*nanos_per_instruction),
+			)),
+		}
+	}
+}
+
+/// Serves the time ECALL with the time on its [Clock].
+#[derive(Debug)]
+pub struct ClockSystem<const MEMORY_SIZE: usize> {
+	clock: Clock,
+}
+
+impl<const MEMORY_SIZE: usize> ClockSystem<MEMORY_SIZE> {
+	pub fn new(clock: Clock) -> Self {
+		Self { clock }
+	}
+
+	/// Tells the time with the host's wall clock.
+	pub fn wall() -> Self {
+		Self::new(Clock::Wall)
+	}
+
+	/// Tells the same time on every reading, e.g. a transaction's timestamp.
+	pub fn fixed(nanos: u64) -> Self {
+		Self::new(Clock::Fixed(nanos))
+	}
+
+	/// Tells a time which passes with the instructions the machine executes.
+	pub fn virtual_clock(start: u64, nanos_per_instruction: u64) -> Self {
+		Self::new(Clock::Virtual { start, nanos_per_instruction })
+	}
+
+	pub fn clock(&self) -> &Clock {
+		&self.clock
+	}
+}
+
+impl<const MEMORY_SIZE: usize> Default for ClockSystem<MEMORY_SIZE> {
+	fn default() -> Self {
+		Self::wall()
+	}
+}
+
+impl<const MEMORY_SIZE: usize> MachineSystem<MEMORY_SIZE> for ClockSystem<MEMORY_SIZE> {
+	fn tick(
+		&mut self,
+		machine: &mut Machine<MEMORY_SIZE>,
+	) -> Result<ControlFlow<()>, MachineError> {
+		let clock = self.clock.kind().to_u32();
+		let returns = match self.clock.nanos(machine) {
+			Some(nanos) => TimeReturns { status: TimeStatusCode::Success.to_i32(), nanos, clock },
+			None => TimeReturns { status: TimeStatusCode::SystemError.to_i32(), nanos: 0, clock },
+		};
+		return_from_ecall(machine, &returns)
+	}
+}
+
+impl<const MEMORY_SIZE: usize> SystemCapabilities for ClockSystem<MEMORY_SIZE> {}
+
+impl<const MEMORY_SIZE: usize> TimeSystemDispatcher<MEMORY_SIZE> for ClockSystem<MEMORY_SIZE> {}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use fuste_ecall_dispatcher::ecall_args;
+	use std::error::Error;
+
+	/// Makes the time ECALL on the machine, returning its results.
+	fn time(
+		system: &mut ClockSystem<1024>,
+		machine: &mut Machine<1024>,
+	) -> Result<TimeReturns, MachineError> {
+		assert_eq!(system.tick(machine)?, ControlFlow::Continue(()));
+		Ok(ecall_args(machine))
+	}
+
+	fn success(nanos: u64, clock: ClockKind) -> TimeReturns {
+		TimeReturns { status: TimeStatusCode::Success.to_i32(), nanos, clock: clock.to_u32() }
+	}
+
+	#[test]
+	fn test_fixed_clock_does_not_pass() -> Result<(), MachineError> {
+		let mut system = ClockSystem::fixed(1_700_000_000);
+		let mut machine = Machine::new();
+
+		assert_eq!(time(&mut system, &mut machine)?, success(1_700_000_000, ClockKind::Fixed));
+		machine.retire_instruction();
+		assert_eq!(time(&mut system, &mut machine)?, success(1_700_000_000, ClockKind::Fixed));
+		Ok(())
+	}
+
+	#[test]
+	fn test_virtual_clock_passes_with_retired_instructions() -> Result<(), MachineError> {
+		let mut system = ClockSystem::virtual_clock(1_000, 10);
+		let mut machine = Machine::new();
+
+		assert_eq!(time(&mut system, &mut machine)?, success(1_000, ClockKind::Virtual));
+		for _ in 0..3 {
+			machine.retire_instruction();
+		}
+		assert_eq!(time(&mut system, &mut machine)?, success(1_030, ClockKind::Virtual));
+
+		// The time saturates rather than wrapping around.
+		let mut system = ClockSystem::virtual_clock(u64::MAX - 1, u64::MAX);
+		assert_eq!(time(&mut system, &mut machine)?, success(u64::MAX, ClockKind::Virtual));
+		Ok(())
+	}
+
+	#[test]
+	fn test_wall_clock_reads_the_host_time() -> Result<(), Box<dyn Error>> {
+		let now = || -> Result<u64, Box<dyn Error>> {
+			Ok(u64::try_from(SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos())?)
+		};
+		let mut system = ClockSystem::wall();
+		let mut machine = Machine::new();
+
+		let before = now()?;
+		let returns = time(&mut system, &mut machine)?;
+		let after = now()?;
+
+		assert_eq!(returns.status, TimeStatusCode::Success.to_i32());
+		assert_eq!(returns.clock, ClockKind::Wall.to_u32());
+		assert!((before..=after).contains(&returns.nanos));
+		Ok(())
+	}
+
+	#[test]
+	fn test_clock_kinds() {
+		assert_eq!(Clock::Wall.kind(), ClockKind::Wall);
+		assert_eq!(Clock::Fixed(0).kind(), ClockKind::Fixed);
+		assert_eq!(
+			Clock::Virtual { start: 0, nanos_per_instruction: 1 }.kind(),
+			ClockKind::Virtual
+		);
+	}
+}
@@ -0,0 +1,256 @@
+use core::ops::ControlFlow;
+use fuste_abi::write::{WriteArgs, WriteReturns};
+use fuste_ecall_dispatcher::{
+	ecall_args, return_from_ecall, SystemCapabilities, WriteSystemDispatcher,
+};
+use fuste_riscv_core::machine::{Machine, MachineError, MachineSystem};
+use fuste_std_output::{Stderr, Stdout};
+use fuste_write::WriteStatusCode;
+use std::collections::BTreeMap;
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// A buffer writes are captured in, shared with whoever reads them back.
+#[derive(Debug, Clone, Default)]
+pub struct CaptureBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl CaptureBuffer {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// A copy of the bytes captured so far.
+	pub fn contents(&self) -> Vec<u8> {
+		self.lock().clone()
+	}
+
+	/// Takes the bytes captured so far, leaving the buffer empty.
+	pub fn take(&self) -> Vec<u8> {
+		std::mem::take(&mut *self.lock())
+	}
+
+	fn lock(&self) -> MutexGuard<'_, Vec<u8>> {
+		// The buffer is only appended to, so it's still whole after a panic elsewhere.
+		self.0.lock().unwrap_or_else(PoisonError::into_inner)
+	}
+}
+
+/// Where the writes to a write system ID are captured.
+#[derive(Debug)]
+pub enum CaptureSink {
+	Buffer(CaptureBuffer),
+	/// Sends each write as it's made
+	Channel(Sender<Vec<u8>>),
+}
+
+#[derive(Debug)]
+struct Capture {
+	sink: CaptureSink,
+	/// The number of bytes captured for the system ID.
+	captured: usize,
+}
+
+/// Captures the writes to each write system ID instead of writing them out, e.g. so tests can
+/// check what a program printed.
+///
+/// Writes to an ID which isn't captured return [WriteStatusCode::InvalidSystem], and writes which
+/// would take an ID past the limit return [WriteStatusCode::Failure] without capturing anything.
+#[derive(Debug)]
+pub struct CaptureOutputSystem<const MEMORY_SIZE: usize> {
+	captures: BTreeMap<u32, Capture>,
+	/// The most bytes captured for each system ID, if limited.
+	limit: Option<usize>,
+}
+
+impl<const MEMORY_SIZE: usize> CaptureOutputSystem<MEMORY_SIZE> {
+	/// Captures no write system IDs until sinks are added.
+	pub fn new() -> Self {
+		Self { captures: BTreeMap::new(), limit: None }
+	}
+
+	/// Captures [Stdout] and [Stderr] in buffers.
+	pub fn std() -> Self {
+		Self::new()
+			.with_buffer(Stdout::to_const_u32(), CaptureBuffer::new())
+			.with_buffer(Stderr::to_const_u32(), CaptureBuffer::new())
+	}
+
+	/// Captures the writes to the system ID in the sink, replacing any it had before.
+	pub fn with_sink(mut self, system_id: u32, sink: CaptureSink) -> Self {
+		self.captures.insert(system_id, Capture { sink, captured: 0 });
+		self
+	}
+
+	pub fn with_buffer(self, system_id: u32, buffer: CaptureBuffer) -> Self {
+		self.with_sink(system_id, CaptureSink::Buffer(buffer))
+	}
+
+	pub fn with_channel(self, system_id: u32, sender: Sender<Vec<u8>>) -> Self {
+		self.with_sink(system_id, CaptureSink::Channel(sender))
+	}
+
+	/// Captures at most the given number of bytes for each system ID.
+	pub fn with_limit(mut self, limit: usize) -> Self {
+		self.limit = Some(limit);
+		self
+	}
+
+	/// The buffer the system ID's writes are captured in, if it has one.
+	pub fn buffer(&self, system_id: u32) -> Option<CaptureBuffer> {
+		match self.captures.get(&system_id) {
+			Some(Capture { sink: CaptureSink::Buffer(buffer), .. }) => Some(buffer.clone()),
+			_ => None,
+		}
+	}
+
+	/// The bytes captured for [Stdout], if it's captured in a buffer.
+	pub fn stdout(&self) -> Vec<u8> {
+		self.buffer(Stdout::to_const_u32())
+			.map(|buffer| buffer.contents())
+			.unwrap_or_default()
+	}
+
+	/// The bytes captured for [Stderr], if it's captured in a buffer.
+	pub fn stderr(&self) -> Vec<u8> {
+		self.buffer(Stderr::to_const_u32())
+			.map(|buffer| buffer.contents())
+			.unwrap_or_default()
+	}
+}
+
+impl<const MEMORY_SIZE: usize> Default for CaptureOutputSystem<MEMORY_SIZE> {
+	fn default() -> Self {
+		Self::std()
+	}
+}
+
+impl<const MEMORY_SIZE: usize> MachineSystem<MEMORY_SIZE> for CaptureOutputSystem<MEMORY_SIZE> {
+	fn tick(
+		&mut self,
+		machine: &mut Machine<MEMORY_SIZE>,
+	) -> Result<ControlFlow<()>, MachineError> {
+		let args: WriteArgs = ecall_args(machine);
+		let Some(capture) = self.captures.get_mut(&args.system_id) else {
+			let returns =
+				WriteReturns { status: WriteStatusCode::InvalidSystem.to_i32(), system_status: 0 };
+			return return_from_ecall(machine, &returns);
+		};
+
+		let write_buffer = machine
+			.memory()
+			.read_bytes(args.buffer_address, args.buffer_length)
+			.map_err(MachineError::MemoryError)?;
+
+		let within_limit =
+			self.limit.is_none_or(|limit| capture.captured + write_buffer.len() <= limit);
+		let captured = within_limit
+			&& match &capture.sink {
+				CaptureSink::Buffer(buffer) => {
+					buffer.lock().extend_from_slice(write_buffer);
+					true
+				}
+				CaptureSink::Channel(sender) => sender.send(write_buffer.to_vec()).is_ok(),
+			};
+
+		let status = if captured {
+			capture.captured += write_buffer.len();
+			WriteStatusCode::Success
+		} else {
+			WriteStatusCode::Failure
+		};
+		return_from_ecall(machine, &WriteReturns { status: status.to_i32(), system_status: 0 })
+	}
+}
+
+impl<const MEMORY_SIZE: usize> SystemCapabilities for CaptureOutputSystem<MEMORY_SIZE> {
+	fn supports_system_id(&self, system_id: u32) -> bool {
+		self.captures.contains_key(&system_id)
+	}
+}
+
+impl<const MEMORY_SIZE: usize> WriteSystemDispatcher<MEMORY_SIZE>
+	for CaptureOutputSystem<MEMORY_SIZE>
+{
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use fuste_abi::{EcallRegisters, RegisterLayout};
+	use std::error::Error;
+	use std::sync::mpsc;
+
+	const BUFFER_ADDRESS: u32 = 0x100;
+
+	/// Makes the write ECALL with the bytes, returning its status.
+	fn write(
+		system: &mut CaptureOutputSystem<1024>,
+		system_id: u32,
+		bytes: &[u8],
+	) -> Result<WriteStatusCode, Box<dyn Error>> {
+		let mut machine = Machine::<1024>::new();
+		machine.memory_mut().write_bytes(BUFFER_ADDRESS, bytes)?;
+		let mut registers = EcallRegisters::new();
+		WriteArgs { system_id, buffer_address: BUFFER_ADDRESS, buffer_length: bytes.len() as u32 }
+			.write_registers(&mut registers);
+		for (register, value) in registers.iter() {
+			machine.csrs_mut().registers_mut().set(register.index(), value);
+		}
+
+		assert_eq!(system.tick(&mut machine)?, ControlFlow::Continue(()));
+		let returns: WriteReturns = ecall_args(&machine);
+		Ok(WriteStatusCode::try_from_i32(returns.status).map_err(|error| error.to_string())?)
+	}
+
+	#[test]
+	fn test_captures_each_id_apart() -> Result<(), Box<dyn Error>> {
+		let (sender, receiver) = mpsc::channel();
+		let mut system = CaptureOutputSystem::std().with_channel(5, sender);
+		let (stdout, stderr) = (Stdout::to_const_u32(), Stderr::to_const_u32());
+
+		assert_eq!(write(&mut system, stdout, b"out ")?, WriteStatusCode::Success);
+		assert_eq!(write(&mut system, stderr, b"err")?, WriteStatusCode::Success);
+		assert_eq!(write(&mut system, stdout, b"again")?, WriteStatusCode::Success);
+		assert_eq!(write(&mut system, 5, b"sent")?, WriteStatusCode::Success);
+		assert_eq!(write(&mut system, 6, b"lost")?, WriteStatusCode::InvalidSystem);
+
+		assert_eq!(system.stdout(), b"out again");
+		assert_eq!(system.stderr(), b"err");
+		assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [b"sent".to_vec()]);
+		assert!(system.buffer(5).is_none());
+
+		// Taking a buffer's contents empties it for everyone sharing it.
+		let buffer = system.buffer(stdout).ok_or("stdout isn't buffered")?;
+		assert_eq!(buffer.take(), b"out again");
+		assert_eq!(system.stdout(), b"");
+		Ok(())
+	}
+
+	#[test]
+	fn test_writes_past_the_limit_fail() -> Result<(), Box<dyn Error>> {
+		let mut system = CaptureOutputSystem::std().with_limit(8);
+		let (stdout, stderr) = (Stdout::to_const_u32(), Stderr::to_const_u32());
+
+		assert_eq!(write(&mut system, stdout, b"12345")?, WriteStatusCode::Success);
+		// A write which would go past the limit captures nothing, but smaller ones still fit.
+		assert_eq!(write(&mut system, stdout, b"6789")?, WriteStatusCode::Failure);
+		assert_eq!(write(&mut system, stdout, b"678")?, WriteStatusCode::Success);
+		assert_eq!(write(&mut system, stdout, b"9")?, WriteStatusCode::Failure);
+		assert_eq!(system.stdout(), b"12345678");
+
+		// Each ID has a limit of its own.
+		assert_eq!(write(&mut system, stderr, b"12345678")?, WriteStatusCode::Success);
+		assert_eq!(system.stderr(), b"12345678");
+		Ok(())
+	}
+
+	#[test]
+	fn test_closed_channels_fail() -> Result<(), Box<dyn Error>> {
+		let (sender, receiver) = mpsc::channel();
+		let mut system = CaptureOutputSystem::new().with_channel(5, sender);
+		drop(receiver);
+
+		assert_eq!(write(&mut system, 5, b"nobody")?, WriteStatusCode::Failure);
+		Ok(())
+	}
+}
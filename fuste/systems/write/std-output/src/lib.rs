@@ -1,34 +1,249 @@
+use core::fmt;
 use core::ops::ControlFlow;
-use fuste_ecall_dispatcher::WriteSystemDispatcher;
+use fuste_abi::write::{WriteArgs, WriteReturns};
+use fuste_ecall_dispatcher::{
+	ecall_args, return_from_ecall, SystemCapabilities, WriteSystemDispatcher,
+};
 use fuste_riscv_core::machine::{Machine, MachineError, MachineSystem};
-use fuste_std_output::Stdout;
+use fuste_std_output::{Stderr, Stdout};
+use fuste_write::WriteStatusCode;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, Write};
 
-pub struct StdOutputSystem<const MEMORY_SIZE: usize>;
+/// A function a host handles writes with.
+pub type WriteCallback = Box<dyn FnMut(&[u8]) -> io::Result<()>>;
+
+/// Where the bytes written to a write system ID go.
+pub enum WriteTarget {
+	/// The host's stdout
+	Stdout,
+	/// The host's stderr
+	Stderr,
+	File(File),
+	/// Bytes kept in memory, read back with [StdOutputSystem::buffer]
+	Buffer(Vec<u8>),
+	/// A function the host handles each write with
+	Callback(WriteCallback),
+}
+
+impl WriteTarget {
+	pub fn callback(callback: impl FnMut(&[u8]) -> io::Result<()> + 'static) -> Self {
+		Self::Callback(Box::new(callback))
+	}
+
+	fn write_all(&mut self, bytes: &[u8]) -> io::Result<()> {
+		match self {
+			WriteTarget::Stdout => io::stdout().lock().write_all(bytes),
+			WriteTarget::Stderr => io::stderr().lock().write_all(bytes),
+			WriteTarget::File(file) => file.write_all(bytes),
+			WriteTarget::Buffer(buffer) => {
+				buffer.extend_from_slice(bytes);
+				Ok(())
+			}
+			WriteTarget::Callback(callback) => callback(bytes),
+		}
+	}
+}
+
+impl fmt::Debug for WriteTarget {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			WriteTarget::Stdout => write!(f, "Stdout"),
+			WriteTarget::Stderr => write!(f, "Stderr"),
+			WriteTarget::File(file) => f.debug_tuple("File").field(file).finish(),
+			WriteTarget::Buffer(buffer) => f.debug_tuple("Buffer").field(buffer).finish(),
+			WriteTarget::Callback(_) => write!(f, "Callback"),
+		}
+	}
+}
+
+/// Serves writes to each write system ID with its [WriteTarget].
+///
+/// Writes to an ID without a target return [WriteStatusCode::InvalidSystem].
+#[derive(Debug)]
+pub struct StdOutputSystem<const MEMORY_SIZE: usize> {
+	targets: BTreeMap<u32, WriteTarget>,
+	/// The number of bytes the program has written to the host's stdout, which run reports give as
+	/// its output. Writes to other targets, stderr among them, aren't counted.
+	pub bytes_written: usize,
+}
+
+impl<const MEMORY_SIZE: usize> StdOutputSystem<MEMORY_SIZE> {
+	/// Serves [Stdout] and [Stderr] with the host's stdout and stderr.
+	pub fn new() -> Self {
+		Self::empty()
+			.with_target(Stdout::to_const_u32(), WriteTarget::Stdout)
+			.with_target(Stderr::to_const_u32(), WriteTarget::Stderr)
+	}
+
+	/// Serves no write system IDs until targets are added.
+	pub fn empty() -> Self {
+		Self { targets: BTreeMap::new(), bytes_written: 0 }
+	}
+
+	/// Sends writes to the system ID to the target, replacing any it had before.
+	pub fn with_target(mut self, system_id: u32, target: WriteTarget) -> Self {
+		self.targets.insert(system_id, target);
+		self
+	}
+
+	/// The bytes written to the system ID, if its target is a [WriteTarget::Buffer].
+	pub fn buffer(&self, system_id: u32) -> Option<&[u8]> {
+		match self.targets.get(&system_id) {
+			Some(WriteTarget::Buffer(buffer)) => Some(buffer),
+			_ => None,
+		}
+	}
+}
+
+impl<const MEMORY_SIZE: usize> Default for StdOutputSystem<MEMORY_SIZE> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
 
 impl<const MEMORY_SIZE: usize> MachineSystem<MEMORY_SIZE> for StdOutputSystem<MEMORY_SIZE> {
 	fn tick(
 		&mut self,
 		machine: &mut Machine<MEMORY_SIZE>,
 	) -> Result<ControlFlow<()>, MachineError> {
-		let write_fd = machine.csrs().registers().get(10);
-		let write_buffer_address = machine.csrs().registers().get(11);
-		let write_buffer_length = machine.csrs().registers().get(12);
+		let args: WriteArgs = ecall_args(machine);
+		let Some(target) = self.targets.get_mut(&args.system_id) else {
+			let returns =
+				WriteReturns { status: WriteStatusCode::InvalidSystem.to_i32(), system_status: 0 };
+			return return_from_ecall(machine, &returns);
+		};
+
 		let write_buffer = machine
 			.memory()
-			.read_bytes(write_buffer_address, write_buffer_length)
+			.read_bytes(args.buffer_address, args.buffer_length)
 			.map_err(MachineError::MemoryError)?;
 
-		if write_fd == Stdout::to_const_u32() {
-			// print the write buffer to stdout
-			print!("{}", String::from_utf8_lossy(write_buffer));
-			// write 0 to the result register a3
-			machine.csrs_mut().registers_mut().set(13, 0);
-			machine.csrs_mut().registers_mut().program_counter_mut().increment();
-			machine.commit_csrs();
-		}
+		let returns = match target.write_all(write_buffer) {
+			Ok(()) => {
+				if matches!(target, WriteTarget::Stdout) {
+					self.bytes_written += write_buffer.len();
+				}
+				WriteReturns { status: WriteStatusCode::Success.to_i32(), system_status: 0 }
+			}
+			Err(error) => WriteReturns {
+				status: WriteStatusCode::SystemError.to_i32(),
+				system_status: error.raw_os_error().unwrap_or(-1),
+			},
+		};
+		return_from_ecall(machine, &returns)
+	}
+}
 
-		Ok(ControlFlow::Continue(()))
+impl<const MEMORY_SIZE: usize> SystemCapabilities for StdOutputSystem<MEMORY_SIZE> {
+	fn supports_system_id(&self, system_id: u32) -> bool {
+		self.targets.contains_key(&system_id)
 	}
 }
 
 impl<const MEMORY_SIZE: usize> WriteSystemDispatcher<MEMORY_SIZE> for StdOutputSystem<MEMORY_SIZE> {}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use fuste_abi::{EcallRegisters, RegisterLayout};
+	use std::cell::RefCell;
+	use std::error::Error;
+	use std::rc::Rc;
+
+	const BUFFER_ADDRESS: u32 = 0x100;
+
+	/// Makes the write ECALL with the bytes, returning its results.
+	fn write(
+		system: &mut StdOutputSystem<1024>,
+		system_id: u32,
+		bytes: &[u8],
+	) -> Result<WriteReturns, Box<dyn Error>> {
+		let mut machine = Machine::<1024>::new();
+		machine.memory_mut().write_bytes(BUFFER_ADDRESS, bytes)?;
+		let mut registers = EcallRegisters::new();
+		WriteArgs { system_id, buffer_address: BUFFER_ADDRESS, buffer_length: bytes.len() as u32 }
+			.write_registers(&mut registers);
+		for (register, value) in registers.iter() {
+			machine.csrs_mut().registers_mut().set(register.index(), value);
+		}
+
+		assert_eq!(system.tick(&mut machine)?, ControlFlow::Continue(()));
+		Ok(ecall_args(&machine))
+	}
+
+	fn status(code: WriteStatusCode) -> WriteReturns {
+		WriteReturns { status: code.to_i32(), system_status: 0 }
+	}
+
+	#[test]
+	fn test_writes_to_each_target() -> Result<(), Box<dyn Error>> {
+		let path = std::env::temp_dir().join(format!("fuste-std-output-{}", std::process::id()));
+		let called = Rc::new(RefCell::new(Vec::new()));
+		let calls = called.clone();
+		let mut system = StdOutputSystem::empty()
+			.with_target(5, WriteTarget::Buffer(Vec::new()))
+			.with_target(
+				6,
+				WriteTarget::callback(move |bytes| {
+					calls.borrow_mut().extend_from_slice(bytes);
+					Ok(())
+				}),
+			)
+			.with_target(7, WriteTarget::File(File::create(&path)?));
+
+		for system_id in [5, 6, 7] {
+			assert_eq!(write(&mut system, system_id, b"hello ")?, status(WriteStatusCode::Success));
+			assert_eq!(write(&mut system, system_id, b"there")?, status(WriteStatusCode::Success));
+		}
+		drop(system.targets.remove(&7));
+		let file = std::fs::read(&path)?;
+		std::fs::remove_file(&path)?;
+
+		assert_eq!(system.buffer(5), Some(&b"hello there"[..]));
+		assert_eq!(called.borrow().as_slice(), b"hello there");
+		assert_eq!(file, b"hello there");
+		// Only buffers can be read back.
+		assert_eq!(system.buffer(6), None);
+		Ok(())
+	}
+
+	#[test]
+	fn test_failed_writes_are_system_errors() -> Result<(), Box<dyn Error>> {
+		let mut system = StdOutputSystem::empty()
+			.with_target(5, WriteTarget::callback(|_| Err(io::Error::from_raw_os_error(28))));
+
+		assert_eq!(
+			write(&mut system, 5, b"full")?,
+			WriteReturns { status: WriteStatusCode::SystemError.to_i32(), system_status: 28 }
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn test_rejects_unknown_ids() -> Result<(), Box<dyn Error>> {
+		let mut system = StdOutputSystem::empty().with_target(5, WriteTarget::Buffer(Vec::new()));
+		let stdout = Stdout::to_const_u32();
+		assert!(system.supports_system_id(5));
+		assert!(!system.supports_system_id(stdout));
+
+		assert_eq!(write(&mut system, stdout, b"lost")?, status(WriteStatusCode::InvalidSystem));
+		assert_eq!(system.buffer(5), Some(&[][..]));
+		Ok(())
+	}
+
+	#[test]
+	fn test_bytes_written_counts_only_stdout() -> Result<(), Box<dyn Error>> {
+		let mut system = StdOutputSystem::new().with_target(5, WriteTarget::Buffer(Vec::new()));
+
+		write(&mut system, Stdout::to_const_u32(), b"\n")?;
+		assert_eq!(system.bytes_written, 1);
+
+		write(&mut system, Stderr::to_const_u32(), b"\n")?;
+		write(&mut system, 5, b"buffered")?;
+		write(&mut system, 6, b"invalid")?;
+		assert_eq!(system.bytes_written, 1);
+		Ok(())
+	}
+}
@@ -0,0 +1,66 @@
+// The fuste test environment for riscv-tests.
+//
+// Fuste implements only the unprivileged RV32I ISA, so unlike the `p` environment this does
+// not touch any CSRs or trap vectors. Tests are linked low in memory by `link.ld` and report
+// their result with an exit ECALL, which the conformance harness intercepts.
+//
+// The M and A suites are built against the same environment, but the machine doesn't implement
+// their extensions yet, so they stop on their first M or A instruction. The harness lists them
+// as expected failures in `Suite::expected_failures` and fails on any other outcome.
+
+#ifndef _ENV_FUSTE_H
+#define _ENV_FUSTE_H
+
+#define RVTEST_RV32U                                                    \
+  .macro init;                                                          \
+  .endm
+
+#define RVTEST_RV64U RVTEST_RV32U
+#define RVTEST_RV32M RVTEST_RV32U
+#define RVTEST_RV64M RVTEST_RV32U
+
+#define TESTNUM gp
+
+#define RVTEST_CODE_BEGIN                                               \
+        .section .text.init;                                            \
+        .align  6;                                                      \
+        .globl _start;                                                  \
+_start:                                                                 \
+        la sp, _stack_end;                                              \
+        li TESTNUM, 0;                                                  \
+        init;
+
+#define RVTEST_CODE_END                                                 \
+        unimp
+
+// Exit with a0 = 0 on success.
+#define RVTEST_PASS                                                     \
+        fence;                                                          \
+        li TESTNUM, 1;                                                  \
+        li a7, 93;                                                      \
+        li a0, 0;                                                       \
+        ecall
+
+// Exit with a0 = (TESTNUM << 1) | 1 on failure, as the `p` environment writes to tohost.
+#define RVTEST_FAIL                                                     \
+        fence;                                                          \
+1:      beqz TESTNUM, 1b;                                               \
+        sll TESTNUM, TESTNUM, 1;                                        \
+        or TESTNUM, TESTNUM, 1;                                         \
+        li a7, 93;                                                      \
+        addi a0, TESTNUM, 0;                                            \
+        ecall
+
+#define EXTRA_DATA
+
+#define RVTEST_DATA_BEGIN                                               \
+        EXTRA_DATA                                                      \
+        .pushsection .tohost,"aw",@progbits;                            \
+        .align 6; .global tohost; tohost: .dword 0; .size tohost, 8;    \
+        .align 6; .global fromhost; fromhost: .dword 0; .size fromhost, 8; \
+        .popsection;                                                    \
+        .align 4; .global begin_signature; begin_signature:
+
+#define RVTEST_DATA_END .align 4; .global end_signature; end_signature:
+
+#endif
@@ -0,0 +1,322 @@
+use core::fmt::{self, Display};
+use core::ops::ControlFlow;
+use fuste_riscv_core::instructions::rv32i::{Ecall, I};
+use fuste_riscv_core::instructions::WordInstruction;
+use fuste_riscv_core::machine::{Machine, MachineError, MachineSystem};
+use fuste_riscv_core::plugins::rv32i_computer::Rv32iComputer;
+use fuste_riscv_elf::Elf32Loader;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The memory of the machine each test runs in.
+///
+/// Tests built with `env/fuste/link.ld` place their stack at the top of this memory.
+pub const MEMORY_SIZE: usize = 256 * 1024;
+
+/// The number of ticks after which a test which hasn't reported a result is abandoned.
+pub const MAX_TICKS: u64 = 1_000_000;
+
+/// The exit ECALL number used by `RVTEST_PASS` and `RVTEST_FAIL`.
+const EXIT_ECALL: u32 = 93;
+
+/// The suites vendored under `isa`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Suite {
+	Rv32ui,
+	Rv32um,
+	Rv32ua,
+}
+
+impl Suite {
+	pub const ALL: [Suite; 3] = [Suite::Rv32ui, Suite::Rv32um, Suite::Rv32ua];
+
+	pub fn name(&self) -> &'static str {
+		match self {
+			Suite::Rv32ui => "rv32ui",
+			Suite::Rv32um => "rv32um",
+			Suite::Rv32ua => "rv32ua",
+		}
+	}
+
+	/// The tests of the suite which are expected to fail, because the machine doesn't implement
+	/// the extension they cover yet.
+	///
+	/// They're expected to stop on an invalid instruction. Any other test failing, or one of these
+	/// doing anything else, fails the run, so the list has to shrink as extensions are implemented.
+	pub fn expected_failures(&self) -> &'static [&'static str] {
+		match self {
+			Suite::Rv32ui => &[],
+			Suite::Rv32um => &["div", "divu", "mul", "mulh", "mulhsu", "mulhu", "rem", "remu"],
+			Suite::Rv32ua => &[
+				"amoadd_w",
+				"amoand_w",
+				"amomax_w",
+				"amomaxu_w",
+				"amomin_w",
+				"amominu_w",
+				"amoor_w",
+				"amoswap_w",
+				"amoxor_w",
+				"lrsc",
+			],
+		}
+	}
+
+	/// The name of a vendored test of the suite, e.g. `add` for `rv32ui-fuste-add`.
+	pub fn test_name<'a>(&self, path: &'a Path) -> Option<&'a str> {
+		let file_name = path.file_name()?.to_str()?;
+		file_name.strip_prefix(self.name())?.strip_prefix("-fuste-")
+	}
+
+	/// Whether the outcome is the one expected of the named test.
+	pub fn is_expected(&self, name: &str, outcome: &TestOutcome) -> bool {
+		if !self.expected_failures().contains(&name) {
+			return *outcome == TestOutcome::Pass;
+		}
+		matches!(outcome, TestOutcome::Error(error) if error.contains("InvalidInstruction"))
+	}
+}
+
+/// The result reported by a test.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TestOutcome {
+	Pass,
+	/// The test reported a failure in the numbered test case.
+	Fail {
+		test_case: u32,
+	},
+	/// The test did not report a result within [MAX_TICKS].
+	Timeout,
+	/// The test could not be loaded or the machine failed while running it.
+	Error(String),
+}
+
+impl TestOutcome {
+	/// Decodes the `tohost` convention: `1` passes and `(TESTNUM << 1) | 1` fails.
+	pub fn from_tohost(value: u32) -> Self {
+		if value == 1 {
+			TestOutcome::Pass
+		} else {
+			TestOutcome::Fail { test_case: value >> 1 }
+		}
+	}
+
+	/// Decodes the exit ECALL convention: `0` passes and `(TESTNUM << 1) | 1` fails.
+	pub fn from_exit_code(code: u32) -> Self {
+		if code == 0 {
+			TestOutcome::Pass
+		} else {
+			TestOutcome::Fail { test_case: code >> 1 }
+		}
+	}
+}
+
+impl Display for TestOutcome {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			TestOutcome::Pass => write!(f, "pass"),
+			TestOutcome::Fail { test_case } => write!(f, "fail (test case {test_case})"),
+			TestOutcome::Timeout => write!(f, "timeout after {MAX_TICKS} ticks"),
+			TestOutcome::Error(error) => write!(f, "error: {error}"),
+		}
+	}
+}
+
+/// The [TohostSystem] implements the riscv-tests pass/fail convention around the inner computer.
+///
+/// An exit ECALL is intercepted before it executes, and a nonzero write to `tohost`
+/// is observed after each tick.
+pub struct TohostSystem<const MEMORY_SIZE: usize, Computer: MachineSystem<MEMORY_SIZE>> {
+	pub computer: Computer,
+	/// The address of the `tohost` symbol, if the test has one.
+	pub tohost: Option<u32>,
+	pub outcome: Option<TestOutcome>,
+	ecall_word: u32,
+}
+
+impl<const MEMORY_SIZE: usize, Computer: MachineSystem<MEMORY_SIZE>>
+	TohostSystem<MEMORY_SIZE, Computer>
+{
+	pub fn new(computer: Computer, tohost: Option<u32>) -> Self {
+		Self {
+			computer,
+			tohost,
+			outcome: None,
+			ecall_word: Ecall::new(I::new(0, 0, 0, Ecall::IMM)).to_word(),
+		}
+	}
+}
+
+impl<const MEMORY_SIZE: usize, Computer: MachineSystem<MEMORY_SIZE>> MachineSystem<MEMORY_SIZE>
+	for TohostSystem<MEMORY_SIZE, Computer>
+{
+	fn tick(
+		&mut self,
+		machine: &mut Machine<MEMORY_SIZE>,
+	) -> Result<ControlFlow<()>, MachineError> {
+		let pc = machine.registers().program_counter();
+		let word = machine.memory().read_word(pc).map_err(MachineError::MemoryError)?;
+		if word == self.ecall_word && machine.registers().get(17) == EXIT_ECALL {
+			self.outcome = Some(TestOutcome::from_exit_code(machine.registers().get(10)));
+			return Ok(ControlFlow::Break(()));
+		}
+
+		let control_flow = self.computer.tick(machine)?;
+
+		if let Some(tohost) = self.tohost {
+			let value = machine.memory().read_word(tohost).map_err(MachineError::MemoryError)?;
+			if value != 0 {
+				self.outcome = Some(TestOutcome::from_tohost(value));
+				return Ok(ControlFlow::Break(()));
+			}
+		}
+
+		Ok(control_flow)
+	}
+}
+
+/// Runs the machine until the test reports a result or [MAX_TICKS] elapse.
+pub fn run<const MEMORY_SIZE: usize>(
+	machine: &mut Machine<MEMORY_SIZE>,
+	tohost: Option<u32>,
+) -> TestOutcome {
+	let mut system = TohostSystem::new(Rv32iComputer, tohost);
+	for _ in 0..MAX_TICKS {
+		match system.tick(machine) {
+			Ok(ControlFlow::Continue(())) => (),
+			Ok(ControlFlow::Break(())) => break,
+			Err(error) => return TestOutcome::Error(error.to_string()),
+		}
+	}
+	system.outcome.unwrap_or(TestOutcome::Timeout)
+}
+
+/// Loads the ELF at the given path through [Elf32Loader] and runs it.
+pub fn run_elf(path: &Path) -> TestOutcome {
+	let mut machine = Machine::<MEMORY_SIZE>::new();
+	let program = match Elf32Loader::default().load_elf(&mut machine, path) {
+		Ok(program) => program,
+		Err(error) => return TestOutcome::Error(error.to_string()),
+	};
+
+	let tohost = program.symbol("tohost").map(|symbol| symbol.address);
+	run(&mut machine, tohost)
+}
+
+/// The directory the suites are vendored into.
+pub fn isa_dir() -> PathBuf {
+	Path::new(env!("CARGO_MANIFEST_DIR")).join("isa")
+}
+
+/// Lists the vendored test ELFs of a suite in name order.
+///
+/// Hidden files are skipped.
+pub fn vendored_tests(isa_dir: &Path, suite: Suite) -> std::io::Result<Vec<PathBuf>> {
+	let suite_dir = isa_dir.join(suite.name());
+	if !suite_dir.exists() {
+		return Ok(Vec::new());
+	}
+
+	let mut tests = Vec::new();
+	for entry in fs::read_dir(suite_dir)? {
+		let path = entry?.path();
+		let hidden = path
+			.file_name()
+			.and_then(|name| name.to_str())
+			.is_none_or(|name| name.starts_with('.'));
+		if path.is_file() && !hidden {
+			tests.push(path);
+		}
+	}
+	tests.sort();
+
+	Ok(tests)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use fuste_riscv_core::instructions::rv32i::{Addi, Sw, S};
+	use fuste_riscv_core::program;
+
+	fn run_program(program: &[u32], tohost: Option<u32>) -> Result<TestOutcome, MachineError> {
+		let mut machine = Machine::<1024>::new();
+		machine
+			.memory_mut()
+			.load_word_segment(0, program)
+			.map_err(MachineError::MemoryError)?;
+		Ok(run(&mut machine, tohost))
+	}
+
+	#[test]
+	fn test_exit_ecall_convention() -> Result<(), MachineError> {
+		let ecall = Ecall::new(I::new(0, 0, 0, Ecall::IMM));
+
+		let pass = program![Addi::of(17, 0, 93), Addi::of(10, 0, 0), ecall];
+		assert_eq!(run_program(&pass, None)?, TestOutcome::Pass);
+
+		let ecall = Ecall::new(I::new(0, 0, 0, Ecall::IMM));
+		let fail = program![Addi::of(17, 0, 93), Addi::of(10, 0, (3 << 1) | 1), ecall];
+		assert_eq!(run_program(&fail, None)?, TestOutcome::Fail { test_case: 3 });
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_tohost_convention() -> Result<(), MachineError> {
+		let pass = program![Addi::of(1, 0, 1), Sw::new(S::new(0b010, 0, 1, 0x100))];
+		assert_eq!(run_program(&pass, Some(0x100))?, TestOutcome::Pass);
+
+		let fail = program![Addi::of(1, 0, (5 << 1) | 1), Sw::new(S::new(0b010, 0, 1, 0x100))];
+		assert_eq!(run_program(&fail, Some(0x100))?, TestOutcome::Fail { test_case: 5 });
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_expected_failures() {
+		let path = Path::new("isa/rv32um/rv32um-fuste-mul");
+		assert_eq!(Suite::Rv32um.test_name(path), Some("mul"));
+		assert_eq!(Suite::Rv32ui.test_name(path), None);
+
+		let invalid = TestOutcome::Error("InstructionError: InvalidInstruction: 0 at 0x0".into());
+		assert!(Suite::Rv32um.is_expected("mul", &invalid));
+		assert!(!Suite::Rv32um.is_expected("mul", &TestOutcome::Pass));
+		assert!(!Suite::Rv32um.is_expected("mul", &TestOutcome::Timeout));
+		assert!(!Suite::Rv32ui.is_expected("add", &invalid));
+		assert!(Suite::Rv32ui.is_expected("add", &TestOutcome::Pass));
+	}
+
+	/// Runs every vendored riscv-tests ELF.
+	///
+	/// Every suite must be vendored. Every test must pass, except the suite's expected failures,
+	/// which must fail as expected.
+	#[test]
+	fn test_vendored_riscv_tests() -> Result<(), std::io::Error> {
+		let mut failures = Vec::new();
+
+		for suite in Suite::ALL {
+			let tests = vendored_tests(&isa_dir(), suite)?;
+			assert!(!tests.is_empty(), "{}: no vendored tests, see README.md", suite.name());
+
+			let mut passed = 0;
+			for test in &tests {
+				let outcome = run_elf(test);
+				println!("{}: {}", test.display(), outcome);
+				if outcome == TestOutcome::Pass {
+					passed += 1;
+				}
+				let expected =
+					suite.test_name(test).is_some_and(|name| suite.is_expected(name, &outcome));
+				if !expected {
+					failures.push((test.clone(), outcome));
+				}
+			}
+			println!("{}: {passed}/{} passed", suite.name(), tests.len());
+		}
+
+		assert!(failures.is_empty(), "riscv-tests failed: {failures:#?}");
+
+		Ok(())
+	}
+}
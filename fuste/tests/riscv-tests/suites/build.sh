@@ -0,0 +1,27 @@
+#!/bin/sh
+# Builds the suites in this directory into `../isa` against the `env/fuste` environment.
+#
+# Needs a C preprocessor, an LLVM `llvm-mc` with the RISC-V target and `ld.lld`, which can be
+# overridden through `CPP`, `LLVM_MC` and `LD`, e.g. `LD="rust-lld -flavor gnu"`.
+
+set -eu
+
+CPP=${CPP:-cpp}
+LLVM_MC=${LLVM_MC:-llvm-mc}
+LD=${LD:-ld.lld}
+
+here=$(cd "$(dirname "$0")" && pwd)
+env_dir="$here/../env/fuste"
+isa_dir="$here/../isa"
+work=$(mktemp -d)
+trap 'rm -rf "$work"' EXIT
+
+for suite in rv32ui rv32um rv32ua; do
+  mkdir -p "$isa_dir/$suite"
+  for source in "$here/$suite"/*.S; do
+    name=$(basename "$source" .S)
+    $CPP -P -x assembler-with-cpp -I"$env_dir" -I"$here/macros/scalar" "$source" -o "$work/$name.s"
+    $LLVM_MC -triple=riscv32 -mattr=+m,+a,-relax -filetype=obj "$work/$name.s" -o "$work/$name.o"
+    $LD -T "$env_dir/link.ld" --build-id=none "$work/$name.o" -o "$isa_dir/$suite/$suite-fuste-$name"
+  done
+done
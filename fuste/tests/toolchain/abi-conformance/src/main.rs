@@ -0,0 +1,130 @@
+//! Makes each ECALL through the `fuste` environment and reports what the host returned, for the
+//! ABI tests in `fubox` to check. The ELF they run is vendored at
+//! `fuste/riscv-box/tests/abi/abi-conformance.elf`; `fuste/riscv-box/tests/abi.rs` describes how
+//! to rebuild it.
+#![no_std]
+#![no_main]
+use core::time::Duration;
+use fuste::{
+	channels, eprintln, exit, exit_code, fill_bytes,
+	io::{Stdin, Stdout},
+	return_data, signer_at_index, time, write, ChannelError, ChannelSystemId, ClockKind,
+	ExitStatus, TimeError, WriteError, WriteSystemId,
+};
+use fuste_serial_channel::SerialChannelError;
+
+fn report(line: &[u8]) {
+	// A write the host doesn't return success for can't be reported, so it fails the run.
+	if write(Stdout::SYSTEM_ID, line).is_err() {
+		exit(ExitStatus::Error);
+	}
+}
+
+fn hex_digits(byte: u8) -> [u8; 2] {
+	const DIGITS: &[u8; 16] = b"0123456789abcdef";
+	[DIGITS[(byte >> 4) as usize], DIGITS[(byte & 0xf) as usize]]
+}
+
+fn report_hex(bytes: &[u8]) {
+	for byte in bytes {
+		report(&hex_digits(*byte));
+	}
+}
+
+#[fuste::main]
+fn main() -> Result<(), &'static str> {
+	match fuste::args().nth(1) {
+		// The panic handler sends where the program panicked to the host through the abort ECALL.
+		Some("panic") => panic!("asked to panic"),
+		// An error returned from main is printed to stderr and exits with `ExitStatus::Error`.
+		Some("error") => return Err("asked to fail"),
+		Some("code") => exit_code(42),
+		Some("random") => {
+			// The bytes drawn, so that runs with the same seed can be compared.
+			let mut bytes = [0u8; 16];
+			if fill_bytes(&mut bytes).is_err() {
+				return Err("random failed");
+			}
+			report(b"random: ");
+			report_hex(&bytes);
+			report(b"\n");
+			return Ok(());
+		}
+		Some("time") => {
+			// The clock and its time, then the time which passed waiting a microsecond on it.
+			let start = time::read_clock().map_err(|_| "time failed")?;
+			report(match start.clock() {
+				ClockKind::Wall => b"time: wall ",
+				ClockKind::Fixed => b"time: fixed ",
+				ClockKind::Virtual => b"time: virtual ",
+			});
+			report_hex(&(start.since_epoch().as_nanos() as u64).to_be_bytes());
+			report(b"\n");
+
+			match time::busy_wait(Duration::from_micros(1)) {
+				Ok(()) | Err(TimeError::FixedClock) => {}
+				Err(_) => return Err("wait failed"),
+			}
+			let elapsed =
+				time::now().map_err(|_| "time failed")?.saturating_sub(start.since_epoch());
+			report(b"elapsed: ");
+			report_hex(&(elapsed.as_nanos() as u64).to_be_bytes());
+			report(b"\n");
+			return Ok(());
+		}
+		_ => {}
+	}
+
+	report(b"write: ok\n");
+	eprintln!("stderr: ok");
+
+	match write(WriteSystemId::constant(0x7f), b"unserved") {
+		Err(WriteError::InvalidSystem(_)) => report(b"invalid write: ok\n"),
+		_ => report(b"invalid write: failed\n"),
+	}
+
+	match signer_at_index::<32, 32>(0) {
+		Ok(signer) if signer.address().as_bytes().iter().all(|byte| *byte == 0) => {
+			report(b"channel: ok\n");
+		}
+		Err(SerialChannelError::ChannelError(ChannelError::Ignored(_))) => {
+			report(b"channel: ignored\n");
+		}
+		_ => report(b"channel: failed\n"),
+	}
+
+	// The signer channel completes when it's opened, so there's nothing to wait for.
+	match channels::wait(ChannelSystemId::constant(0x516d), Some(Duration::ZERO)) {
+		Ok(status) if status.is_success() => report(b"channel wait: ok\n"),
+		Err(ChannelError::Ignored(_)) => report(b"channel wait: ignored\n"),
+		_ => report(b"channel wait: failed\n"),
+	}
+
+	let mut input = [0u8; 32];
+	match Stdin.read_to_fill(&mut input) {
+		Ok(size) => {
+			report(b"read: ");
+			report(&input[..size]);
+			report(b"\n");
+		}
+		Err(_) => report(b"read: failed\n"),
+	}
+
+	match return_data(b"abi: ok") {
+		Ok(()) => report(b"return data: ok\n"),
+		Err(_) => report(b"return data: failed\n"),
+	}
+
+	match fill_bytes(&mut [0u8; 8]) {
+		Ok(()) => report(b"random: ok\n"),
+		Err(_) => report(b"random: failed\n"),
+	}
+
+	match time::now() {
+		Ok(_) => report(b"time: ok\n"),
+		Err(_) => report(b"time: failed\n"),
+	}
+
+	// The status is passed by value, so it reaches the host as the exit code.
+	exit(ExitStatus::Terminated)
+}